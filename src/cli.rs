@@ -1,41 +1,187 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::env;
 use std::io::stdin;
 use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::time::{Instant, SystemTime};
 
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
 
+use crate::format::{self, Column, OutputFormat, RenderOpts};
+use crate::locale::{Lang, Msg};
 use crate::parser::{
-    CheckInfo, CodingGuidelines, Guideline, GuidelineID, JsonStruct, Output, RulesCfg,
+    self, CheckInfo, CheckLevel, CodingGuidelines, Guideline, GuidelineID, GuidelineSummary,
+    JsonStruct, Output, OutputDiff, RulesCfg, RunMetadata, ToolRun, OUTPUT_SCHEMA, SCHEMA_VERSION,
+};
+use crate::tools::diagnostics::Diagnostic;
+use crate::tools::pattern::Pattern;
+use crate::tools::{
+    self, fix, source, CheckContext, Checker, LintValidator, LintsOpt, SupportedTool,
 };
-use crate::tools::{Checker, LintsOpt, SupportedTool};
 use crate::{utils, Error, Result};
 
 const GUILDELINES_CONTENT: &str = include_str!("guidelines.json");
 
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, subcommand_negates_reqs = true)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Arguments of the `check` command, which will be run when no subcommand was given.
+    #[command(flatten)]
+    pub check: Option<CheckArgs>,
+
+    /// The language of the output texts, such as "en" or "zh".
+    /// Defaults to the language in `LANG` environment variable, or "en" if it was not set.
+    #[arg(long, global = true)]
+    pub lang: Option<String>,
+
+    /// The path to a coding guideline catalogue, which will be used instead of the built-in one.
+    #[arg(long, global = true, value_parser = check_file_existence)]
+    pub guidelines: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn run(&self) -> Result<()> {
+        let global = GlobalOpts {
+            lang: Lang::resolve(self.lang.as_deref()),
+            guidelines: match &self.guidelines {
+                Some(path) => Cow::Owned(utils::read_to_string(path)?),
+                None => Cow::Borrowed(GUILDELINES_CONTENT),
+            },
+        };
+        match (&self.command, &self.check) {
+            (Some(_), Some(_)) => Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "arguments of the `check` command cannot be used with a subcommand",
+                )
+                .exit(),
+            (Some(cmd), None) => cmd.run(&global),
+            (None, Some(check)) => check.run(&global),
+            (None, None) => {
+                Args::command().print_help()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Options shared by all commands.
+#[derive(Debug)]
+pub struct GlobalOpts {
+    pub lang: Lang,
+    /// Content of the coding guideline catalogue.
+    pub guidelines: Cow<'static, str>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Run checks on the source code, according to the guidelines in the rule file.
+    Check(CheckArgs),
+    /// Show detailed explanations of coding guidelines.
+    Explain(ExplainArgs),
+    /// Apply the suggested fixes for violations of the guidelines in the rule file.
+    Fix(FixArgs),
+    /// Validate the lints used by guidelines against the installed toolchain.
+    Validate(ValidateArgs),
+    /// Manage the coding guideline catalogue.
+    Guidelines {
+        #[command(subcommand)]
+        command: GuidelinesCommand,
+    },
+    /// Process the JSON outputs of previous checks, without running the checks again.
+    Report {
+        #[command(subcommand)]
+        command: ReportCommand,
+    },
+    /// Print the JSON Schema of the JSON output.
+    Schema,
+}
+
+impl Command {
+    pub fn run(&self, global: &GlobalOpts) -> Result<()> {
+        match self {
+            Command::Check(args) => args.run(global),
+            Command::Explain(args) => args.run(global),
+            Command::Fix(args) => args.run(global),
+            Command::Validate(args) => args.run(global),
+            Command::Guidelines { command } => command.run(global),
+            Command::Report { command } => command.run(global),
+            Command::Schema => {
+                print!("{OUTPUT_SCHEMA}");
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum GuidelinesCommand {
+    /// Generate or update a catalogue from the Markdown sources of the guideline book.
+    Import(ImportArgs),
+}
+
+impl GuidelinesCommand {
+    pub fn run(&self, global: &GlobalOpts) -> Result<()> {
+        match self {
+            GuidelinesCommand::Import(args) => args.run(global),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReportCommand {
+    /// Merge the outputs of several checks, such as of different crates or CI shards.
+    Merge(MergeArgs),
+    /// Render an output in another format.
+    Render(RenderArgs),
+    /// Compare the outputs of two checks, such as of two releases, and show which results
+    /// are new, fixed or unchanged.
+    Diff(DiffArgs),
+}
+
+impl ReportCommand {
+    pub fn run(&self, global: &GlobalOpts) -> Result<()> {
+        match self {
+            ReportCommand::Merge(args) => args.run(),
+            ReportCommand::Render(args) => args.run(),
+            ReportCommand::Diff(args) => args.run(global),
+        }
+    }
+}
+
+// The arguments are added to the group of this struct explicitly, because clap leaves
+// the group empty when there's a flattened field, and `Args::check` would always be `None`.
+#[derive(clap::Args, Debug)]
+pub struct CheckArgs {
     /// The path to the checks configuration file, which is demanded.
-    #[arg(short, long = "rule-file", value_parser = check_file_existence)]
+    #[arg(short, long = "rule-file", group = "CheckArgs", value_parser = check_file_existence)]
     pub rule_file: PathBuf,
 
     /// Overrides the path to the source code file that will be checked.
-    #[arg(short, long = "src-file", value_parser = check_file_existence)]
+    #[arg(short, long = "src-file", group = "CheckArgs", value_parser = check_file_existence)]
     pub src_file: Option<PathBuf>,
 
-    /// The path to the output file.
-    #[arg(short, long, value_parser = check_dir_existence)]
-    pub output: Option<PathBuf>,
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Validate the lints used by the checked guidelines before running checks.
+    #[arg(long, group = "CheckArgs")]
+    pub validate: bool,
 }
 
-impl Args {
+impl CheckArgs {
     // TODO: read ids from RulesCfg, then for each id, find out which tools to
     // use via the map, and define running options with src_path for each tool, then run execute
     // with those options one by one, and finally, after getting the output file, write it
     // as the desired output path.
-    pub fn run(&self) -> Result<()> {
-        let guidelines = CodingGuidelines::deserialize(GUILDELINES_CONTENT)?;
+    pub fn run(&self, global: &GlobalOpts) -> Result<()> {
+        let lang = &global.lang;
+        let guidelines = CodingGuidelines::deserialize(&global.guidelines)?;
         let gl_map = guidelines.to_hashmap();
 
         let rule_content = utils::read_to_string(&self.rule_file)?;
@@ -43,40 +189,512 @@ impl Args {
 
         let src_path = if let Some(path) = &self.src_file {
             // TODO: use log crate's `info!`.
-            println!("overriding src path from commandline");
+            println!("{}", Msg::OverrideSrcPath.text(lang));
             path.as_path()
         } else {
             rule_cfg.file_path
         };
-        let output_path = if let Some(path) = &self.output {
-            path.clone()
+
+        if self.validate {
+            // TODO: use log crate's `warn!`.
+            let checked = rule_cfg
+                .coding_guidelines
+                .iter()
+                .filter_map(|id| gl_map.get(id).copied());
+            for issue in LintValidator::new().validate(checked)? {
+                println!("{}", issue.render(lang));
+            }
+        }
+
+        let mut output = run_checks(src_path, &rule_cfg, &gl_map, lang)?;
+        if let Some(metadata) = &mut output.metadata {
+            metadata.rules_file = Some(self.rule_file.clone());
+            metadata.rules_hash = Some(format!("{:016x}", utils::fnv1a(rule_content.as_bytes())));
+        }
+        self.output.write(output)?;
+        Ok(())
+    }
+}
+
+// Options of writing an `Output`, which is not a doc comment,
+// because clap would take it as the about of the commands it's flattened into.
+#[derive(clap::Args, Debug)]
+pub struct OutputArgs {
+    /// The path to the output file.
+    #[arg(short, long, value_parser = check_dir_existence)]
+    pub output: Option<PathBuf>,
+
+    /// The format of the output, which is one of "json", "gitlab", "checkstyle", "github",
+    /// "markdown", "csv" and "tsv".
+    ///
+    /// The output of "github" is printed unless an output file is given, and a job summary
+    /// is appended to the file in `GITHUB_STEP_SUMMARY` if it's set.
+    #[arg(long, default_value_t)]
+    pub format: OutputFormat,
+
+    /// Show at most this many results of each guideline in the Markdown report,
+    /// so that it fits in a comment of a pull request.
+    #[arg(long, value_name = "N")]
+    pub max_per_guideline: Option<usize>,
+
+    /// Columns of the CSV and TSV output separated by commas, such as "guideline_id,file,message".
+    /// All the columns are included by default.
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Vec<Column>,
+
+    /// Give each guideline a row in the CSV and TSV output, instead of joining them into one
+    /// row, when a result violates several guidelines.
+    #[arg(long)]
+    pub row_per_guideline: bool,
+
+    /// Read the source files of the results, and attach the lines of each result along with
+    /// at most N lines before and after them, which are shown in the Markdown report.
+    #[arg(long, value_name = "N")]
+    pub context_lines: Option<usize>,
+
+    /// Only write the summary of the results along with the metadata of the run,
+    /// which only works with the "json" format.
+    #[arg(long)]
+    pub summary_only: bool,
+}
+
+impl OutputArgs {
+    /// Render the output in the chosen format, and write it to the output file,
+    /// or print it if there's no file for the format.
+    ///
+    /// The summary of the results is computed first, along with their source context
+    /// if it's asked for.
+    pub fn write(&self, mut output: Output) -> Result<()> {
+        if self.summary_only && self.format != OutputFormat::Json {
+            return Err(Error::SummaryOnlyFormat(self.format.to_string()).into());
+        }
+        output.summarize();
+        if self.summary_only {
+            output = Output {
+                metadata: output.metadata,
+                summary: output.summary,
+                ..Output::from(vec![])
+            };
+        } else if let Some(context_lines) = self.context_lines {
+            output.capture_context(context_lines);
+        }
+        if self.format == OutputFormat::Github {
+            if let Some(summary_path) = env::var_os("GITHUB_STEP_SUMMARY") {
+                utils::append_to_file(format::github_step_summary(&output), summary_path)?;
+            }
+        }
+        let opts = RenderOpts {
+            max_per_guideline: self.max_per_guideline,
+            columns: self.columns.clone(),
+            row_per_guideline: self.row_per_guideline,
+        };
+        let rendered = self.format.render_with(&output, &opts)?;
+        let path = self
+            .output
+            .clone()
+            .or_else(|| self.format.default_file_name().map(PathBuf::from));
+        match path {
+            Some(path) => utils::write_to_file(rendered, path),
+            None => {
+                print!("{rendered}");
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct MergeArgs {
+    /// Paths to the JSON outputs to merge, results found by more than one of them
+    /// are only kept once.
+    #[arg(required = true, value_parser = check_file_existence)]
+    pub inputs: Vec<PathBuf>,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+impl MergeArgs {
+    pub fn run(&self) -> Result<()> {
+        let mut merged = Output::from(vec![]);
+        for input in &self.inputs {
+            let output = read_output(input)?;
+            if output.is_summary_only() {
+                return Err(Error::SummaryOnlyInput(input.clone()).into());
+            }
+            merged.merge(output);
+        }
+        self.output.write(merged)
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct RenderArgs {
+    /// Path to the JSON output to render.
+    #[arg(value_parser = check_file_existence)]
+    pub input: PathBuf,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+impl RenderArgs {
+    pub fn run(&self) -> Result<()> {
+        let output = read_output(&self.input)?;
+        if output.is_summary_only() && self.output.format != OutputFormat::Json {
+            return Err(Error::SummaryOnlyInput(self.input.clone()).into());
+        }
+        self.output.write(output)
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DiffArgs {
+    /// Path to the JSON output of the old check.
+    #[arg(value_parser = check_file_existence)]
+    pub old: PathBuf,
+
+    /// Path to the JSON output of the new check.
+    #[arg(value_parser = check_file_existence)]
+    pub new: PathBuf,
+
+    /// Exit with an error code if there are new results of this level or a more severe one,
+    /// which is one of "info", "prompt", "warn", "severe" and "fatal".
+    #[arg(long, value_name = "LEVEL")]
+    pub fail_on: Option<CheckLevel>,
+}
+
+impl DiffArgs {
+    /// Print the changes of each guideline, followed by the new and fixed results.
+    pub fn run(&self, global: &GlobalOpts) -> Result<()> {
+        let lang = &global.lang;
+        let old = read_output(&self.old)?;
+        let new = read_output(&self.new)?;
+        let diff = OutputDiff::new(&old, &new);
+
+        let deltas = diff.guideline_deltas();
+        let id_width = deltas
+            .iter()
+            .map(|delta| delta.id.to_string().len())
+            .max()
+            .unwrap_or_default();
+        for delta in &deltas {
+            println!(
+                "{:id_width$}  {} -> {} (+{}, -{})  {}",
+                delta.id.to_catalogue_string(),
+                delta.old_count(),
+                delta.new_count(),
+                delta.new,
+                delta.fixed,
+                delta.name,
+            );
+        }
+        for info in &diff.new {
+            println!("+ {}", describe_result(info));
+        }
+        for info in &diff.fixed {
+            println!("- {}", describe_result(info));
+        }
+        println!(
+            "{}",
+            Msg::DiffSummary(diff.new.len(), diff.fixed.len(), diff.unchanged.len()).text(lang)
+        );
+
+        if let Some(level) = self.fail_on {
+            let count = diff.new_at_least(level).len();
+            if count > 0 {
+                println!("{}", Msg::NewResultsAtLevel(count, level).text(lang));
+                exit(1);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A line describing a result, with its location and the guidelines it violates.
+fn describe_result(info: &CheckInfo) -> String {
+    let location = match (&info.file_path, info.begin_line) {
+        (Some(path), Some(line)) => format!("{}:{line}", path.display()),
+        (Some(path), None) => path.display().to_string(),
+        _ => String::new(),
+    };
+    let ids = info
+        .guideline_list
+        .iter()
+        .map(|gl| gl.id.to_catalogue_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{location}: {}/{} [{ids}]", info.tool, info.defect_name)
+}
+
+/// Read the JSON output of a previous check.
+fn read_output(path: &Path) -> Result<Output> {
+    let content = utils::read_to_string(path)?;
+    let output = Output::deserialize(&content)
+        .map_err(|e| Error::ParseOutput(path.to_path_buf(), e.to_string()))?;
+    if output.schema_version > SCHEMA_VERSION {
+        let reason = format!(
+            "schema version {} is newer than the supported version {SCHEMA_VERSION}",
+            output.schema_version
+        );
+        return Err(Error::ParseOutput(path.to_path_buf(), reason).into());
+    }
+    Ok(output)
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ExplainArgs {
+    /// IDs of the guidelines to explain, such as "G.TYP.BOL.01".
+    #[arg(required = true, value_parser = parse_guideline_id)]
+    pub ids: Vec<GuidelineID>,
+}
+
+impl ExplainArgs {
+    pub fn run(&self, global: &GlobalOpts) -> Result<()> {
+        let guidelines = CodingGuidelines::deserialize(&global.guidelines)?;
+        let gl_map = guidelines.to_hashmap();
+
+        let mut explanations = vec![];
+        for id in &self.ids {
+            let gl = gl_map
+                .get(id)
+                .ok_or_else(|| Error::GuidelineNotFound(id.to_string()))?;
+            explanations.push(gl.explain(&global.lang));
+        }
+        println!("{}", explanations.join("\n"));
+        Ok(())
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct FixArgs {
+    /// The path to the checks configuration file, which is demanded.
+    #[arg(short, long = "rule-file", value_parser = check_file_existence)]
+    pub rule_file: PathBuf,
+
+    /// Overrides the path to the source code file that will be fixed.
+    #[arg(short, long = "src-file", value_parser = check_file_existence)]
+    pub src_file: Option<PathBuf>,
+
+    /// Print the changes as a unified diff, without modifying any file.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Fix files even if they have uncommitted changes, or are not under version control.
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl FixArgs {
+    /// Only machine-applicable suggestions of the violations of the selected guidelines
+    /// are applied, then the checks are run again to confirm the violations are gone.
+    pub fn run(&self, global: &GlobalOpts) -> Result<()> {
+        let lang = &global.lang;
+        let guidelines = CodingGuidelines::deserialize(&global.guidelines)?;
+        let gl_map = guidelines.to_hashmap();
+
+        let rule_content = utils::read_to_string(&self.rule_file)?;
+        let rule_cfg = RulesCfg::deserialize(&rule_content)?;
+        let ids = &rule_cfg.coding_guidelines;
+        let src_path = self.src_file.as_deref().unwrap_or(rule_cfg.file_path);
+
+        let has_cargo = utils::command_exist("cargo", &["-V"])?;
+        let opts = lint_opts(src_path, ids, &gl_map, has_cargo, lang);
+        let violations = |opts: &[LintsOpt]| -> Result<Vec<Diagnostic>> {
+            let mut result = vec![];
+            for opt in opts {
+                for diag in opt.diagnostics()? {
+                    let info = diag.check_info();
+                    // clippy reports rustc lints as well
+                    if !result.contains(&diag)
+                        && !related_guidelines(&info, ids, &gl_map, lang).is_empty()
+                    {
+                        result.push(diag);
+                    }
+                }
+            }
+            Ok(result)
+        };
+
+        let suggestions = violations(&opts)?
+            .into_iter()
+            .flat_map(|diag| diag.suggestions)
+            .collect::<Vec<_>>();
+        let fixes = fix::apply_suggestions(&suggestions)?;
+        if fixes.is_empty() {
+            println!("{}", Msg::NothingToFix.text(lang));
+            return Ok(());
+        }
+
+        if self.dry_run {
+            for file_fix in &fixes {
+                print!("{}", file_fix.unified_diff());
+            }
+            return Ok(());
+        }
+
+        if !self.force {
+            let mut dirty = vec![];
+            for file_fix in &fixes {
+                if fix::is_dirty(&file_fix.file_path)? {
+                    dirty.push(file_fix.file_path.clone());
+                }
+            }
+            if !dirty.is_empty() {
+                return Err(Error::DirtyFiles(dirty).into());
+            }
+        }
+        for file_fix in &fixes {
+            utils::write_to_file(&file_fix.fixed, &file_fix.file_path)?;
+        }
+        let applied = fixes.iter().map(|f| f.applied).sum();
+        println!("{}", Msg::FixApplied(applied, fixes.len()).text(lang));
+
+        let remaining = violations(&opts)?;
+        for diag in &remaining {
+            let location = match (&diag.file_path, diag.line, diag.column) {
+                (Some(path), Some(line), Some(col)) => {
+                    format!("{}:{line}:{col}", path.display())
+                }
+                (Some(path), _, _) => path.display().to_string(),
+                _ => String::new(),
+            };
+            println!("{location}: {} ({})", diag.message, diag.lint);
+        }
+        println!("{}", Msg::RemainingViolations(remaining.len()).text(lang));
+        Ok(())
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ValidateArgs {
+    /// Only validate the guidelines listed in this checks configuration file.
+    #[arg(short, long = "rule-file", value_parser = check_file_existence)]
+    pub rule_file: Option<PathBuf>,
+}
+
+impl ValidateArgs {
+    /// Print every invalid lint, and exit with an error code if there's any.
+    pub fn run(&self, global: &GlobalOpts) -> Result<()> {
+        let lang = &global.lang;
+        let guidelines = CodingGuidelines::deserialize(&global.guidelines)?;
+
+        let mut invalid = 0;
+        let checked: Vec<&Guideline> = if let Some(rule_file) = &self.rule_file {
+            let rule_content = utils::read_to_string(rule_file)?;
+            let rule_cfg = RulesCfg::deserialize(&rule_content)?;
+            let gl_map = guidelines.to_hashmap();
+
+            let mut ids = rule_cfg.coding_guidelines.iter().collect::<Vec<_>>();
+            ids.sort_by_key(|id| id.to_string());
+            let mut checked = vec![];
+            for id in ids {
+                match gl_map.get(id) {
+                    Some(gl) => checked.push(*gl),
+                    None => {
+                        invalid += 1;
+                        println!("{}", Error::GuidelineNotFound(id.to_string()));
+                    }
+                }
+            }
+            checked
+        } else {
+            guidelines.coding_guidelines.iter().collect()
+        };
+        let issues = LintValidator::new().validate(checked.iter().copied())?;
+        for gl in &checked {
+            for t in gl.tool.iter().filter(|t| t.name == SupportedTool::Pattern) {
+                if let Err(e) = t.ident.parse::<Pattern>() {
+                    invalid += 1;
+                    println!("{}: {e}", gl.id.to_catalogue_string());
+                }
+            }
+        }
+
+        for issue in &issues {
+            println!("{}", issue.render(lang));
+        }
+        invalid += issues.len();
+        if invalid > 0 {
+            println!("{}", Msg::InvalidLints(invalid).text(lang));
+            exit(1);
+        }
+        println!("{}", Msg::LintsValid(checked.len()).text(lang));
+        Ok(())
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ImportArgs {
+    /// The directory containing the Markdown sources of the guideline book.
+    #[arg(value_parser = check_file_existence)]
+    pub dir: PathBuf,
+
+    /// The catalogue file to generate, or to update if it already exists.
+    #[arg(short, long, default_value = "guidelines.json", value_parser = check_dir_existence)]
+    pub output: PathBuf,
+
+    /// The language that the guideline book was written in.
+    #[arg(long, default_value = "zh")]
+    pub book_lang: String,
+
+    /// Only report the changes, without writing the catalogue file.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Remove the guidelines of the catalogue that are not in the book, which should only
+    /// be used when importing the whole book.
+    #[arg(long)]
+    pub prune: bool,
+}
+
+impl ImportArgs {
+    pub fn run(&self, global: &GlobalOpts) -> Result<()> {
+        let content = if self.output.is_file() {
+            utils::read_to_string(&self.output)?
+        } else {
+            String::new()
+        };
+        let mut catalogue = if content.is_empty() {
+            CodingGuidelines::default()
         } else {
-            PathBuf::from("output.json")
+            CodingGuidelines::deserialize(&content)?
         };
 
-        let check_info_vec = run_checks(src_path, &rule_cfg.coding_guidelines, &gl_map)?;
-        let output = Output::from(check_info_vec).to_json_string_pretty()?;
-        utils::write_to_file(output, output_path)?;
+        let book = parser::parse_book(&self.dir)?;
+        let report = parser::import_guidelines(
+            &mut catalogue,
+            &book,
+            &Lang::new(&self.book_lang),
+            self.prune,
+        );
+        print!("{}", report.render(&global.lang));
+
+        if !self.dry_run {
+            let mut json = catalogue.to_json_string_pretty()?;
+            json.push('\n');
+            utils::write_to_file(json, &self.output)?;
+        }
         Ok(())
     }
 }
 
 fn run_checks(
     path: &Path,
-    ids: &HashSet<GuidelineID>,
+    rule_cfg: &RulesCfg,
     gl_map: &HashMap<&GuidelineID, &Guideline>,
-) -> Result<Vec<CheckInfo>> {
+    lang: &Lang,
+) -> Result<Output> {
+    let started_at = SystemTime::now();
+    let ids = &rule_cfg.coding_guidelines;
     let mut result = vec![];
+    let mut ran_tools = vec![];
     let has_cargo = utils::command_exist("cargo", &["-V"])?;
 
     if !has_cargo {
         // TODO: use log `info!`; use proper interactive method, which allows user to
         // pass `-y` in order to skip interaction
-        print!(
-            "We couldn't find `cargo`'s executable to run, make sure it's \
-        in the path. Because some tools (such as miri) could not run without it, the result \
-        might be incomplete, continue? [y/N]: "
-        );
+        print!("{}", Msg::CargoNotFound.text(lang));
         let mut choice = String::new();
         stdin().read_line(&mut choice)?;
 
@@ -88,66 +706,214 @@ fn run_checks(
         }
     }
 
-    // FIXME: refractor below code.
-    // as it's basically copy-pastes and make it harder to add new tool support.
-    let mut maybe_clippy_opt = None;
-    let mut maybe_rustc_opt = None;
+    for opt in lint_opts(path, ids, gl_map, has_cargo, lang) {
+        let started = Instant::now();
+        let output = opt.check()?;
+        let filtered = opt.filter_output(&output);
+        for diag in filtered.stdout.iter().chain(&filtered.stderr) {
+            result.push(opt.check_info(diag)?);
+        }
+        let tool = if opt.is_clippy {
+            SupportedTool::Clippy
+        } else {
+            SupportedTool::Rustc
+        };
+        ran_tools.push(tool_run(tool, started, output.status.code()));
+    }
+
+    let checked_tools = ids
+        .iter()
+        .filter_map(|id| gl_map.get(id))
+        .flat_map(|gl| &gl.tool)
+        .collect::<Vec<_>>();
+    let checkers = tools::builtin_checkers(rule_cfg, &checked_tools)?;
+    if rule_cfg.advisory.is_none() && tools::uses_tool(&checked_tools, SupportedTool::Advisory) {
+        // TODO: use log crate's `warn!`.
+        println!("{}", Msg::AdvisoryDbNotConfigured.text(lang));
+    }
 
-    for id in ids {
-        let Some(Guideline { tool, .. }) = gl_map.get(id) else { continue; };
-        for t in tool {
-            match t.name {
-                SupportedTool::Clippy => {
-                    if maybe_clippy_opt.is_none() {
-                        maybe_clippy_opt = Some(LintsOpt {
-                            use_cargo: has_cargo,
-                            is_clippy: true,
-                            path: path.to_path_buf(),
-                            ..Default::default()
-                        });
-                    }
-                    maybe_clippy_opt
-                        .as_mut()
-                        .expect("lint option should be initialized at this point")
-                        .lint_names
-                        .push(t.ident.to_string());
-                }
-                SupportedTool::Rustc => {
-                    if maybe_rustc_opt.is_none() {
-                        maybe_rustc_opt = Some(LintsOpt {
-                            use_cargo: has_cargo,
-                            path: path.to_path_buf(),
-                            ..Default::default()
-                        });
-                    }
-                    maybe_rustc_opt
-                        .as_mut()
-                        .expect("lint option should be initialized at this point")
-                        .lint_names
-                        .push(t.ident.to_string());
-                }
-                // FIXME
-                _ => unimplemented!("support for '{}' is not implemented.", t.name),
-            }
+    let mut sources = vec![];
+    if checkers.iter().any(|checker| checker.needs_sources()) {
+        let parsed = source::parse_sources(path)?;
+        for (_, reason) in &parsed.skipped {
+            // TODO: use log crate's `warn!`.
+            println!("{}", Msg::SourceSkipped(reason).text(lang));
         }
+        sources = parsed.files;
+    }
+    let ctx = CheckContext {
+        path,
+        sources: &sources,
+        has_cargo,
+    };
+    let mut sections = vec![];
+    // clippy reports the rustc lints as well, and the lints of the checked guidelines
+    // could have reported the results of the checkers already, such as panic sites
+    let mut reported = HashSet::new();
+    result.retain(|info| reported.insert(result_key(info)));
+    for checker in &checkers {
+        let started = Instant::now();
+        let (found, section) = checker.run(&ctx)?;
+        let found = found
+            .into_iter()
+            .filter(|info| !reported.contains(&result_key(info)))
+            .collect::<Vec<_>>();
+        reported.extend(found.iter().map(result_key));
+        result.extend(found);
+        sections.extend(section);
+        ran_tools.push(tool_run(checker.tool(), started, None));
     }
 
-    if let Some(opt) = maybe_clippy_opt {
-        let output = opt.check()?;
-        let filtered = opt.filter_output(&output);
-        for err in filtered.stderr {
-            result.push(opt.check_info(&err)?);
+    for info in &mut result {
+        info.guideline_list = related_guidelines(info, ids, gl_map, lang);
+    }
+
+    let is_run = |tool: SupportedTool| ran_tools.iter().any(|run| run.name == tool);
+    let mut skipped_tools = vec![];
+    for t in &checked_tools {
+        if !is_run(t.name) && !skipped_tools.contains(&t.name) {
+            skipped_tools.push(t.name);
         }
     }
-    if let Some(opt) = maybe_rustc_opt {
-        let output = opt.check()?;
-        let filtered = opt.filter_output(&output);
-        for err in filtered.stderr {
-            result.push(opt.check_info(&err)?);
+    // guidelines are enforceable if any of their tools was run
+    let mut requested_guidelines = ids.iter().cloned().collect::<Vec<_>>();
+    requested_guidelines.sort_by_key(ToString::to_string);
+    let enforceable_guidelines = requested_guidelines
+        .iter()
+        .filter(|id| {
+            gl_map
+                .get(id)
+                .is_some_and(|gl| gl.tool.iter().any(|t| is_run(t.name)))
+        })
+        .cloned()
+        .collect();
+    let metadata = RunMetadata {
+        eunomia_version: env!("CARGO_PKG_VERSION").to_string(),
+        started_at: utils::format_timestamp(started_at),
+        finished_at: utils::format_timestamp(SystemTime::now()),
+        paths: vec![path.to_path_buf()],
+        rules_file: None,
+        rules_hash: None,
+        tools: ran_tools,
+        skipped_tools,
+        requested_guidelines,
+        enforceable_guidelines,
+    };
+
+    let mut output = Output {
+        metadata: Some(metadata),
+        ..Output::from(result)
+    };
+    for section in sections {
+        output.add_section(section);
+    }
+    Ok(output)
+}
+
+/// Identity of a result, for finding the ones reported by more than one tool.
+fn result_key(info: &CheckInfo) -> ResultKey {
+    (
+        info.tool,
+        info.defect_name.clone(),
+        info.file_path.as_deref().map(utils::canonical),
+        info.begin_line,
+        info.column,
+    )
+}
+
+type ResultKey = (
+    SupportedTool,
+    String,
+    Option<PathBuf>,
+    Option<usize>,
+    Option<usize>,
+);
+
+/// Record a tool that was run, along with how long it took since `started`.
+fn tool_run(name: SupportedTool, started: Instant, exit_code: Option<i32>) -> ToolRun {
+    // the time is taken before running the tool again to get its version
+    let duration_ms = started.elapsed().as_millis() as u64;
+    ToolRun {
+        name,
+        version: name.version(),
+        duration_ms: Some(duration_ms),
+        exit_code,
+    }
+}
+
+/// Collect options of running clippy and rustc, with the lints of the given guidelines.
+fn lint_opts(
+    path: &Path,
+    ids: &HashSet<GuidelineID>,
+    gl_map: &HashMap<&GuidelineID, &Guideline>,
+    has_cargo: bool,
+    lang: &Lang,
+) -> Vec<LintsOpt> {
+    let mut maybe_clippy_opt: Option<LintsOpt> = None;
+    let mut maybe_rustc_opt: Option<LintsOpt> = None;
+
+    for id in ids {
+        let Some(Guideline { tool, .. }) = gl_map.get(id) else {
+            continue;
+        };
+        for t in tool {
+            let opt = match t.name {
+                SupportedTool::Clippy => &mut maybe_clippy_opt,
+                SupportedTool::Rustc => &mut maybe_rustc_opt,
+                // source checkers don't run any program.
+                SupportedTool::Pattern
+                | SupportedTool::UnsafeAudit
+                | SupportedTool::Metrics
+                | SupportedTool::DocCoverage
+                | SupportedTool::Manifest
+                | SupportedTool::Advisory
+                | SupportedTool::License
+                | SupportedTool::Rustfmt
+                | SupportedTool::Panics => continue,
+                // FIXME: support running other tools from here.
+                // TODO: use log crate's `warn!`.
+                _ => {
+                    println!(
+                        "{}",
+                        Msg::ToolNotSupported(t.name, &id.to_string()).text(lang)
+                    );
+                    continue;
+                }
+            };
+            opt.get_or_insert_with(|| LintsOpt {
+                use_cargo: has_cargo,
+                is_clippy: t.name == SupportedTool::Clippy,
+                path: path.to_path_buf(),
+                json_output: true,
+                ..Default::default()
+            })
+            .lint_names
+            .push(t.ident.to_string());
         }
     }
 
-    Ok(result)
+    maybe_clippy_opt
+        .into_iter()
+        .chain(maybe_rustc_opt)
+        .collect()
+}
+
+/// Find out which of the checked guidelines are violated by a checking result.
+fn related_guidelines(
+    info: &CheckInfo,
+    ids: &HashSet<GuidelineID>,
+    gl_map: &HashMap<&GuidelineID, &Guideline>,
+    lang: &Lang,
+) -> Vec<GuidelineSummary> {
+    let mut summaries = ids
+        .iter()
+        .filter_map(|id| gl_map.get(id))
+        .filter(|gl| gl.tool.iter().any(|t| info.is_detected_by(t)))
+        .map(|gl| gl.summary(lang))
+        .collect::<Vec<_>>();
+    // `ids` has arbitrary order, sort them to get a stable output.
+    summaries.sort_by_key(|s| s.id.to_string());
+    summaries
 }
 
 fn check_file_existence(p: &str) -> Result<PathBuf> {
@@ -164,3 +930,7 @@ fn check_dir_existence(p: &str) -> Result<PathBuf> {
         .then_some(p.clone())
         .ok_or(Error::OrphanFilePath(p).into())
 }
+
+fn parse_guideline_id(s: &str) -> Result<GuidelineID> {
+    Ok(s.parse()?)
+}
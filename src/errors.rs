@@ -2,6 +2,8 @@ use std::error::Error as StdError;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
+use crate::tools::SupportedTool;
+
 #[derive(PartialEq, Debug)]
 /// Generic error types defining possible error outputs throughout this whole program.
 #[non_exhaustive]
@@ -10,12 +12,42 @@ pub enum Error {
     /// This is different than [`Error::InvalidGuidelineID`], this error should be
     /// thrown when the type of a guideline is not a single character.
     InvalidGuidelineType(String),
+    /// The guideline ID is valid, but there's no such guideline in the catalogue.
+    GuidelineNotFound(String),
     ParseUnsupportedEnumVariant(&'static str, String, Vec<String>),
     /// Should be thrown when provided pathbuf does not exist, the first argument
     /// will be shown as its identifier, such as 'file', 'xxx file', or 'xxx directory'.
     PathNotExist(&'static str, PathBuf),
     OrphanFilePath(PathBuf),
     InvalidFilePath(PathBuf),
+    /// Files that have uncommitted changes, which should not be modified automatically.
+    DirtyFiles(Vec<PathBuf>),
+    /// A source file could not be parsed, along with the reason.
+    ParseSource(PathBuf, String),
+    /// A pattern of the pattern checker is invalid, along with the reason.
+    InvalidPattern(String, String),
+    /// A `Cargo.toml` or `Cargo.lock` file could not be parsed, along with the reason.
+    ParseManifest(PathBuf, String),
+    /// No `Cargo.toml` could be found in the directory or any of its parents.
+    ManifestNotFound(PathBuf),
+    /// No `Cargo.lock` could be found in the directory or any of its parents.
+    LockFileNotFound(PathBuf),
+    /// An advisory of the advisory database could not be parsed, along with the reason.
+    ParseAdvisory(PathBuf, String),
+    /// A file is not a valid JSON output of this program, along with the reason.
+    ParseOutput(PathBuf, String),
+    /// `cargo metadata` failed to run on a manifest, along with its error output.
+    CargoMetadata(PathBuf, String),
+    /// A license is not a valid SPDX expression, along with the reason.
+    InvalidLicenseExpr(String, String),
+    /// An external tool failed to run, along with its error output.
+    ToolFailed(SupportedTool, String),
+    /// A message of clippy or rustc is not the JSON diagnostic of a lint.
+    ParseDiagnostic(String),
+    /// Only the summary of the output was asked for, in a format other than JSON.
+    SummaryOnlyFormat(String),
+    /// An output to merge or render only holds the summary of its results.
+    SummaryOnlyInput(PathBuf),
 }
 
 impl Display for Error {
@@ -30,12 +62,13 @@ impl Display for Error {
                 "'{ty}' is not a valid guideline type. A valid type \
                 should be a single character such as 'P' or 'G'"
             ),
+            GuidelineNotFound(id) => format!("no guideline with ID '{id}' could be found"),
             ParseUnsupportedEnumVariant(name, variant, all) => format!(
                 "'{variant}' is not a valid variant of {name}. Supported variants are: [{}]",
                 all.join(", ")
             ),
             PathNotExist(kind, path) => {
-                let maybe_space = (kind.is_empty()).then_some(" ").unwrap_or_default();
+                let maybe_space = if kind.is_empty() { " " } else { "" };
                 format!(
                     "the provided {kind}{maybe_space}path does not exist: '{}'",
                     path.display()
@@ -48,6 +81,56 @@ impl Display for Error {
             InvalidFilePath(path) => {
                 format!("the provided file path is invalid: '{}'", path.display())
             }
+            DirtyFiles(paths) => format!(
+                "the following files have uncommitted changes, or are not under version \
+                control, commit them or use `--force` to fix them anyway: [{}]",
+                paths
+                    .iter()
+                    .map(|p| format!("'{}'", p.display()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            ParseSource(path, reason) => {
+                format!("failed to parse source file '{}': {reason}", path.display())
+            }
+            InvalidPattern(pattern, reason) => {
+                format!("'{pattern}' is not a valid pattern: {reason}")
+            }
+            ParseManifest(path, reason) => {
+                format!("failed to parse manifest '{}': {reason}", path.display())
+            }
+            ManifestNotFound(path) => format!(
+                "could not find `Cargo.toml` in '{}' or any parent directory",
+                path.display()
+            ),
+            LockFileNotFound(path) => format!(
+                "could not find `Cargo.lock` in '{}' or any parent directory, \
+                run `cargo generate-lockfile` to create one",
+                path.display()
+            ),
+            ParseAdvisory(path, reason) => {
+                format!("failed to parse advisory '{}': {reason}", path.display())
+            }
+            ParseOutput(path, reason) => {
+                format!("failed to parse output '{}': {reason}", path.display())
+            }
+            CargoMetadata(path, reason) => format!(
+                "failed to run `cargo metadata` on '{}': {reason}",
+                path.display()
+            ),
+            InvalidLicenseExpr(expr, reason) => {
+                format!("'{expr}' is not a valid SPDX license expression: {reason}")
+            }
+            ToolFailed(tool, reason) => format!("failed to run '{tool}': {reason}"),
+            ParseDiagnostic(message) => format!("'{message}' is not a JSON diagnostic of a lint"),
+            SummaryOnlyFormat(format) => format!(
+                "`--summary-only` only works with the \"json\" format, but \"{format}\" was given"
+            ),
+            SummaryOnlyInput(path) => format!(
+                "'{}' only holds the summary of its results, which could neither be merged \
+                nor rendered in a format other than \"json\"",
+                path.display()
+            ),
         };
         f.write_str(&msg)
     }
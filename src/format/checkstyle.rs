@@ -0,0 +1,89 @@
+//! The XML report of [Checkstyle], results are grouped by their files.
+//!
+//! [Checkstyle]: https://checkstyle.org
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::Finding;
+use crate::parser::CheckLevel;
+
+fn severity(level: CheckLevel) -> &'static str {
+    match level {
+        CheckLevel::Fatal | CheckLevel::Severe => "error",
+        CheckLevel::Warn => "warning",
+        CheckLevel::Prompt | CheckLevel::Info => "info",
+    }
+}
+
+/// Escape the special characters of XML in an attribute value.
+fn escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&apos;"),
+            '\n' => res.push_str("&#10;"),
+            '\r' | '\t' => {
+                let _ = write!(res, "&#{};", u32::from(c));
+            }
+            // other control characters are not allowed in XML 1.0
+            c if c.is_control() => {}
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+pub(super) fn render(findings: &[Finding]) -> String {
+    let mut files: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        files.entry(&finding.path).or_default().push(finding);
+    }
+
+    let mut res = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    res.push_str("<checkstyle version=\"4.3\">\n");
+    for (path, findings) in files {
+        let _ = writeln!(res, "  <file name=\"{}\">", escape(path));
+        for finding in findings {
+            res.push_str("    <error");
+            if let Some(line) = finding.info.begin_line {
+                let _ = write!(res, " line=\"{line}\"");
+            }
+            if let Some(column) = finding.info.column {
+                let _ = write!(res, " column=\"{column}\"");
+            }
+            let message = std::iter::once(&finding.description)
+                .chain(&finding.notes)
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join("\n");
+            let _ = writeln!(
+                res,
+                " severity=\"{}\" message=\"{}\" source=\"{}\"/>",
+                severity(finding.level),
+                escape(&message),
+                escape(&finding.check_name),
+            );
+        }
+        res.push_str("  </file>\n");
+    }
+    res.push_str("</checkstyle>\n");
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape;
+
+    #[test]
+    fn escape_attributes() {
+        assert_eq!(
+            escape("a < b && \"c\"\n\u{7}'d'"),
+            "a &lt; b &amp;&amp; &quot;c&quot;&#10;&apos;d&apos;"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Flat tables of CSV and TSV, which could be opened by spreadsheets.
+//!
+//! Each result is a row, fields containing delimiters, quotes or line breaks are
+//! quoted as described in [RFC 4180], for both formats, which spreadsheets understand.
+//!
+//! [RFC 4180]: https://www.rfc-editor.org/rfc/rfc4180
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::{Finding, RenderOpts};
+use crate::parser::GuidelineSummary;
+
+/// Columns of the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// IDs of the violated guidelines, separated by `; ` unless each guideline has a row.
+    GuidelineId,
+    GuidelineName,
+    Level,
+    Tool,
+    DefectName,
+    File,
+    BeginLine,
+    EndLine,
+    Column,
+    /// The column where the primary span of the result ends, exclusively.
+    EndColumn,
+    Message,
+    /// Secondary locations and child messages, one per line.
+    Notes,
+}
+
+impl Column {
+    // TODO: put this method in a derive macro
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::GuidelineId,
+            Self::GuidelineName,
+            Self::Level,
+            Self::Tool,
+            Self::DefectName,
+            Self::File,
+            Self::BeginLine,
+            Self::EndLine,
+            Self::Column,
+            Self::EndColumn,
+            Self::Message,
+            Self::Notes,
+        ]
+    }
+
+    /// The value of this column, `guideline` is the one this row is for,
+    /// or `None` if the row is for all the guidelines of the finding.
+    fn value(&self, finding: &Finding, guideline: Option<&GuidelineSummary>) -> String {
+        let info = finding.info;
+        let guidelines = match guideline {
+            Some(gl) => vec![gl],
+            None => info.guideline_list.iter().collect(),
+        };
+        let join = |f: fn(&GuidelineSummary) -> String| {
+            guidelines
+                .iter()
+                .map(|gl| f(gl))
+                .collect::<Vec<_>>()
+                .join("; ")
+        };
+        let number = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_default();
+        match self {
+            Column::GuidelineId => join(|gl| gl.id.to_catalogue_string()),
+            Column::GuidelineName => join(|gl| gl.name.clone()),
+            Column::Level => guideline.map_or(finding.level, |gl| gl.level).to_string(),
+            Column::Tool => info.tool.to_string(),
+            Column::DefectName => info.defect_name.clone(),
+            Column::File => finding.path.clone(),
+            Column::BeginLine => number(info.begin_line),
+            Column::EndLine => number(info.end_line),
+            Column::Column => number(info.column),
+            Column::EndColumn => number(info.span.as_ref().and_then(|s| s.end_column)),
+            Column::Message => info.help_info.clone(),
+            Column::Notes => finding.notes.join("\n"),
+        }
+    }
+}
+
+// TODO: put this method in a derive macro
+impl Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            Column::GuidelineId => "guideline_id",
+            Column::GuidelineName => "guideline_name",
+            Column::Level => "level",
+            Column::Tool => "tool",
+            Column::DefectName => "defect_name",
+            Column::File => "file",
+            Column::BeginLine => "begin_line",
+            Column::EndLine => "end_line",
+            Column::Column => "column",
+            Column::EndColumn => "end_column",
+            Column::Message => "message",
+            Column::Notes => "notes",
+        };
+        f.write_str(str)
+    }
+}
+
+// TODO: put this method in a derive macro
+impl FromStr for Column {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "guideline_id" => Ok(Self::GuidelineId),
+            "guideline_name" => Ok(Self::GuidelineName),
+            "level" => Ok(Self::Level),
+            "tool" => Ok(Self::Tool),
+            "defect_name" => Ok(Self::DefectName),
+            "file" => Ok(Self::File),
+            "begin_line" => Ok(Self::BeginLine),
+            "end_line" => Ok(Self::EndLine),
+            "column" => Ok(Self::Column),
+            "end_column" => Ok(Self::EndColumn),
+            "message" => Ok(Self::Message),
+            "notes" => Ok(Self::Notes),
+            _ => Err(crate::Error::ParseUnsupportedEnumVariant(
+                "column",
+                s.to_string(),
+                Column::all().iter().map(ToString::to_string).collect(),
+            )),
+        }
+    }
+}
+
+/// Quote a field if it contains the delimiter, quotes or line breaks.
+fn escape(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub(super) fn render(findings: &[Finding], opts: &RenderOpts, delimiter: char) -> String {
+    let columns = if opts.columns.is_empty() {
+        Column::all()
+    } else {
+        opts.columns.clone()
+    };
+    let sep = delimiter.to_string();
+    let row = |fields: Vec<String>| {
+        let fields = fields
+            .iter()
+            .map(|field| escape(field, delimiter))
+            .collect::<Vec<_>>();
+        // spreadsheets expect CRLF line breaks between rows
+        format!("{}\r\n", fields.join(&sep))
+    };
+
+    let mut res = row(columns.iter().map(ToString::to_string).collect());
+    for finding in findings {
+        let guidelines = &finding.info.guideline_list;
+        if opts.row_per_guideline && !guidelines.is_empty() {
+            for gl in guidelines {
+                res.push_str(&row(columns
+                    .iter()
+                    .map(|col| col.value(finding, Some(gl)))
+                    .collect()));
+            }
+        } else {
+            res.push_str(&row(columns
+                .iter()
+                .map(|col| col.value(finding, None))
+                .collect()));
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape;
+
+    #[test]
+    fn escape_fields() {
+        assert_eq!(escape("plain text", ','), "plain text");
+        assert_eq!(escape("a, b", ','), "\"a, b\"");
+        assert_eq!(escape("a, b", '\t'), "a, b");
+        assert_eq!(escape("say \"hi\"\nnow", '\t'), "\"say \"\"hi\"\"\nnow\"");
+    }
+}
@@ -0,0 +1,115 @@
+//! [Workflow commands] of GitHub Actions, which show the results as annotations
+//! of the changed files in pull requests, along with a job summary in Markdown.
+//!
+//! [Workflow commands]: https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+
+use std::fmt::Write;
+
+use super::{markdown, Finding};
+use crate::parser::CheckLevel;
+
+fn command(level: CheckLevel) -> &'static str {
+    match level {
+        CheckLevel::Fatal | CheckLevel::Severe => "error",
+        CheckLevel::Warn => "warning",
+        CheckLevel::Prompt | CheckLevel::Info => "notice",
+    }
+}
+
+/// Escape the message of a command.
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape the value of a property of a command, which also can't contain `:` and `,`.
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+pub(super) fn render(findings: &[Finding]) -> String {
+    let mut res = String::new();
+    for finding in findings {
+        let info = finding.info;
+        let mut props = vec![];
+        if !finding.path.is_empty() {
+            props.push(format!("file={}", escape_property(&finding.path)));
+        }
+        if let Some(line) = info.begin_line {
+            props.push(format!("line={line}"));
+        }
+        if let Some(column) = info.column {
+            props.push(format!("col={column}"));
+        }
+        if let Some(end_line) = info.end_line {
+            props.push(format!("endLine={end_line}"));
+        }
+        // GitHub only takes the end column of annotations on a single line
+        if let Some(span) = info.span.as_ref().filter(|s| s.begin_line == s.end_line) {
+            if let Some(end_column) = span.end_column {
+                props.push(format!("endColumn={end_column}"));
+            }
+        }
+        // the most severe guideline is the one that decides the level
+        let title = info
+            .guideline_list
+            .iter()
+            .max_by_key(|gl| gl.level)
+            .map(|gl| gl.id.to_catalogue_string())
+            .unwrap_or_else(|| finding.check_name.clone());
+        props.push(format!("title={}", escape_property(&title)));
+
+        let mut message = finding.description.clone();
+        for note in &finding.notes {
+            message.push('\n');
+            message.push_str(note);
+        }
+        let _ = writeln!(
+            res,
+            "::{} {}::{}",
+            command(finding.level),
+            props.join(","),
+            escape_data(&message)
+        );
+    }
+    res
+}
+
+/// A Markdown summary of the results, with the count of results per guideline.
+pub(super) fn step_summary(findings: &[Finding]) -> String {
+    let mut res = String::from("## Eunomia\n\n");
+    if findings.is_empty() {
+        res.push_str("No violation was found.\n");
+        return res;
+    }
+    let (groups, unrelated) = markdown::group_by_guideline(findings);
+    let _ = writeln!(
+        res,
+        "Found {} result(s), violating {} guideline(s).\n",
+        findings.len(),
+        groups.len()
+    );
+    if !groups.is_empty() {
+        res.push_str(&markdown::guideline_table(&groups));
+    }
+    if !unrelated.is_empty() {
+        let _ = writeln!(
+            res,
+            "\n{} result(s) are not related to any checked guideline.",
+            unrelated.len()
+        );
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_data, escape_property};
+
+    #[test]
+    fn escape_commands() {
+        assert_eq!(escape_data("100%\nok: a, b"), "100%25%0Aok: a, b");
+        assert_eq!(escape_property("C:\\a,b"), "C%3A\\a%2Cb");
+    }
+}
@@ -0,0 +1,77 @@
+//! The [Code Quality report] of GitLab CI, which is a JSON array of issues.
+//!
+//! [Code Quality report]: https://docs.gitlab.com/ee/ci/testing/code_quality.html#code-quality-report-format
+
+use serde::Serialize;
+
+use super::Finding;
+use crate::parser::CheckLevel;
+use crate::Result;
+
+#[derive(Serialize)]
+struct Issue<'a> {
+    description: &'a str,
+    check_name: &'a str,
+    fingerprint: &'a str,
+    severity: &'static str,
+    /// Secondary locations and child messages, as a Markdown list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<Content>,
+    location: Location<'a>,
+}
+
+#[derive(Serialize)]
+struct Content {
+    body: String,
+}
+
+#[derive(Serialize)]
+struct Location<'a> {
+    path: &'a str,
+    lines: Lines,
+}
+
+#[derive(Serialize)]
+struct Lines {
+    begin: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end: Option<usize>,
+}
+
+fn severity(level: CheckLevel) -> &'static str {
+    match level {
+        CheckLevel::Fatal => "blocker",
+        CheckLevel::Severe => "critical",
+        CheckLevel::Warn => "major",
+        CheckLevel::Prompt => "minor",
+        CheckLevel::Info => "info",
+    }
+}
+
+pub(super) fn render(findings: &[Finding]) -> Result<String> {
+    let issues = findings
+        .iter()
+        .map(|finding| Issue {
+            description: &finding.description,
+            check_name: &finding.check_name,
+            fingerprint: &finding.fingerprint,
+            severity: severity(finding.level),
+            content: (!finding.notes.is_empty()).then(|| Content {
+                body: finding
+                    .notes
+                    .iter()
+                    .map(|note| format!("- {note}\n"))
+                    .collect(),
+            }),
+            location: Location {
+                path: &finding.path,
+                lines: Lines {
+                    // GitLab requires a line, results without one are shown at the top.
+                    begin: finding.info.begin_line.unwrap_or(1),
+                    end: finding.info.end_line,
+                },
+            },
+        })
+        .collect::<Vec<_>>();
+    Ok(serde_json::to_string_pretty(&issues)?)
+}
@@ -0,0 +1,300 @@
+//! A Markdown report, which could be posted as a comment of a pull request,
+//! or published in a wiki.
+//!
+//! It begins with tables of the counts of results per guideline and per level,
+//! followed by a collapsible section of each guideline listing its results.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::{Finding, RenderOpts};
+use crate::parser::{CheckLevel, SourceContext};
+
+/// Results violating the same guideline.
+pub(super) struct GuidelineGroup<'f, 'a> {
+    /// The guideline ID in upper case, such as `G.ERR.01`.
+    pub id: String,
+    pub name: &'a str,
+    pub level: CheckLevel,
+    pub findings: Vec<&'f Finding<'a>>,
+}
+
+/// Group the findings by the guidelines they violate, the more severe guidelines
+/// come first, and the findings not related to any guideline are returned separately.
+///
+/// A finding violating several guidelines belongs to all of their groups.
+pub(super) fn group_by_guideline<'f, 'a>(
+    findings: &'f [Finding<'a>],
+) -> (Vec<GuidelineGroup<'f, 'a>>, Vec<&'f Finding<'a>>) {
+    let mut groups: BTreeMap<String, GuidelineGroup> = BTreeMap::new();
+    let mut unrelated = vec![];
+    for finding in findings {
+        if finding.info.guideline_list.is_empty() {
+            unrelated.push(finding);
+        }
+        for gl in &finding.info.guideline_list {
+            let id = gl.id.to_catalogue_string();
+            groups
+                .entry(id.clone())
+                .or_insert_with(|| GuidelineGroup {
+                    id,
+                    name: &gl.name,
+                    level: gl.level,
+                    findings: vec![],
+                })
+                .findings
+                .push(finding);
+        }
+    }
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    // sorting is stable, so guidelines of the same level are still sorted by their IDs
+    groups.sort_by_key(|group| std::cmp::Reverse(group.level));
+    (groups, unrelated)
+}
+
+/// Escape the text in a cell of a table.
+fn escape_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+/// A table of the count of results per guideline.
+pub(super) fn guideline_table(groups: &[GuidelineGroup]) -> String {
+    let mut res = String::from("| Guideline | Name | Level | Count |\n");
+    res.push_str("| --- | --- | --- | ---: |\n");
+    for group in groups {
+        let _ = writeln!(
+            res,
+            "| {} | {} | {} | {} |",
+            group.id,
+            escape_cell(group.name),
+            group.level,
+            group.findings.len()
+        );
+    }
+    res
+}
+
+/// A table of the count of results per level, only the levels having results are listed.
+fn level_table(findings: &[Finding]) -> String {
+    let mut counts: BTreeMap<CheckLevel, usize> = BTreeMap::new();
+    for finding in findings {
+        *counts.entry(finding.level).or_default() += 1;
+    }
+    let mut res = String::from("| Level | Count |\n| --- | ---: |\n");
+    for (level, count) in counts.into_iter().rev() {
+        let _ = writeln!(res, "| {level} | {count} |");
+    }
+    res
+}
+
+/// Location of a finding, such as `src/lib.rs:3:7`.
+fn location(finding: &Finding) -> String {
+    let mut res = if finding.path.is_empty() {
+        "<unknown>".to_string()
+    } else {
+        finding.path.clone()
+    };
+    if let Some(line) = finding.info.begin_line {
+        let _ = write!(res, ":{line}");
+        if let Some(column) = finding.info.column {
+            let _ = write!(res, ":{column}");
+        }
+    }
+    res
+}
+
+/// A fenced block of code, with a fence longer than any backticks in the code.
+fn code_block(lang: &str, code: &str, indent: &str) -> String {
+    let longest = code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest.max(2) + 1);
+    let mut res = format!("{indent}{fence}{lang}\n");
+    for line in code.trim_end().lines() {
+        let _ = writeln!(res, "{indent}{line}");
+    }
+    let _ = writeln!(res, "{indent}{fence}");
+    res
+}
+
+/// The source context of a finding with line numbers, where the highlighted columns
+/// are marked below their lines, like compilers show them.
+fn context_block(context: &SourceContext, indent: &str) -> String {
+    let width = context
+        .lines
+        .last()
+        .map_or(1, |line| line.line.to_string().len());
+    let mut snippet = String::new();
+    for line in &context.lines {
+        let code = format!("{:>width$} | {}", line.line, line.text);
+        let _ = writeln!(snippet, "{}", code.trim_end());
+        if let Some(highlight) = line.highlight {
+            // tabs are kept, so that the markers line up with the code
+            let padding = line
+                .text
+                .chars()
+                .take(highlight.begin_column.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+            let markers = "^".repeat(highlight.end_column.saturating_sub(highlight.begin_column));
+            let _ = writeln!(snippet, "{:width$} | {padding}{markers}", "");
+        }
+    }
+    code_block("text", &snippet, indent)
+}
+
+/// List the findings, at most `limit` of them.
+fn finding_list(findings: &[&Finding], limit: Option<usize>) -> String {
+    let shown = limit.unwrap_or(findings.len()).min(findings.len());
+    let mut res = String::new();
+    for finding in &findings[..shown] {
+        let info = finding.info;
+        let help = if info.help_info.is_empty() {
+            &info.defect_name
+        } else {
+            &info.help_info
+        };
+        let _ = writeln!(
+            res,
+            "- `{}` {} (`{}`)",
+            location(finding),
+            help.trim(),
+            finding.check_name
+        );
+        for note in &finding.notes {
+            let _ = writeln!(res, "  - {}", note.trim());
+        }
+        if let Some(context) = &info.context {
+            res.push('\n');
+            res.push_str(&context_block(context, "  "));
+            res.push('\n');
+        } else if !info.code_string.trim().is_empty() {
+            res.push('\n');
+            res.push_str(&code_block("rust", &info.code_string, "  "));
+            res.push('\n');
+        }
+    }
+    if shown < findings.len() {
+        let _ = writeln!(res, "- ...and {} more", findings.len() - shown);
+    }
+    res
+}
+
+fn section(summary: &str, findings: &[&Finding], limit: Option<usize>) -> String {
+    format!(
+        "<details>\n<summary>{summary}</summary>\n\n{}\n\n</details>\n",
+        finding_list(findings, limit).trim_end()
+    )
+}
+
+pub(super) fn render(findings: &[Finding], opts: &RenderOpts) -> String {
+    let mut res = String::from("# Eunomia report\n\n");
+    if findings.is_empty() {
+        res.push_str("No violation was found.\n");
+        return res;
+    }
+    let (groups, unrelated) = group_by_guideline(findings);
+    let _ = writeln!(
+        res,
+        "Found {} result(s), violating {} guideline(s).\n",
+        findings.len(),
+        groups.len()
+    );
+    if !groups.is_empty() {
+        res.push_str(&guideline_table(&groups));
+        res.push('\n');
+    }
+    res.push_str(&level_table(findings));
+
+    res.push_str("\n## Results\n");
+    for group in &groups {
+        let summary = format!(
+            "<b>{}</b> {} ({}, {} result(s))",
+            group.id,
+            group.name,
+            group.level,
+            group.findings.len()
+        );
+        res.push('\n');
+        res.push_str(&section(&summary, &group.findings, opts.max_per_guideline));
+    }
+    if !unrelated.is_empty() {
+        let summary = format!(
+            "Not related to any checked guideline ({} result(s))",
+            unrelated.len()
+        );
+        res.push('\n');
+        res.push_str(&section(&summary, &unrelated, opts.max_per_guideline));
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{code_block, context_block};
+    use crate::parser::{CodeSpan, Highlight, SourceContext, SourceLine};
+
+    #[test]
+    fn fence_code_blocks() {
+        assert_eq!(
+            code_block("rust", "let x = 1;", ""),
+            "```rust\nlet x = 1;\n```\n"
+        );
+        assert_eq!(
+            code_block("rust", "/// ```\n/// f();\n/// ```\n", "  "),
+            "  ````rust\n  /// ```\n  /// f();\n  /// ```\n  ````\n"
+        );
+    }
+
+    #[test]
+    fn mark_highlighted_columns() {
+        let content = "fn f() {\n\tlet x = [1, 2];\n\n\tx[3]\n}\n";
+        let span = CodeSpan::new((2, 6), (4, 6));
+        let context = SourceContext::new(content, &span, 1).unwrap();
+        assert_eq!(
+            context_block(&context, ""),
+            "```text
+1 | fn f() {
+2 | \tlet x = [1, 2];
+  | \t    ^^^^^^^^^^^
+3 |
+4 | \tx[3]
+  | \t^^^^
+5 | }
+```
+"
+        );
+    }
+
+    #[test]
+    fn mark_zero_columns() {
+        let span = CodeSpan {
+            begin_column: Some(0),
+            end_column: Some(3),
+            ..CodeSpan::lines(1, 1)
+        };
+        let context = SourceContext::new("x[3]\n", &span, 0).unwrap();
+        assert_eq!(
+            context_block(&context, ""),
+            "```text\n1 | x[3]\n  | ^^\n```\n"
+        );
+
+        // contexts read from an output are not clamped
+        let context = SourceContext {
+            lines: vec![SourceLine {
+                line: 1,
+                text: "x[3]".to_string(),
+                highlight: Some(Highlight {
+                    begin_column: 0,
+                    end_column: 2,
+                }),
+            }],
+        };
+        assert_eq!(
+            context_block(&context, ""),
+            "```text\n1 | x[3]\n  | ^^\n```\n"
+        );
+    }
+}
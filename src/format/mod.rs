@@ -0,0 +1,243 @@
+//! Formats that the checking [`Output`] could be rendered as, besides of the default JSON,
+//! so that it could be consumed by other systems, such as CI services.
+//!
+//! Each [`CheckInfo`] is turned into a [`Finding`] first, which has everything those
+//! formats need, so a format only has to render the findings in its own syntax.
+
+mod checkstyle;
+mod delimited;
+mod github;
+mod gitlab;
+mod markdown;
+
+pub use self::delimited::Column;
+
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::parser::{fingerprints, CheckInfo, CheckLevel, Output};
+use crate::Result;
+
+/// Supported output formats.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The [`Output`] serialized as JSON.
+    #[default]
+    Json,
+    /// The [Code Quality report] of GitLab CI.
+    ///
+    /// [Code Quality report]: https://docs.gitlab.com/ee/ci/testing/code_quality.html
+    Gitlab,
+    /// The XML report of Checkstyle, which is understood by many CI services.
+    Checkstyle,
+    /// Workflow commands of GitHub Actions, which are printed instead of being written
+    /// to a file, so that the results show up as annotations.
+    Github,
+    /// A Markdown report, which fits comments of pull requests.
+    Markdown,
+    /// Comma-separated values, one result per row.
+    Csv,
+    /// Tab-separated values, one result per row.
+    Tsv,
+}
+
+impl OutputFormat {
+    // TODO: put this method in a derive macro
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Json,
+            Self::Gitlab,
+            Self::Checkstyle,
+            Self::Github,
+            Self::Markdown,
+            Self::Csv,
+            Self::Tsv,
+        ]
+    }
+
+    /// The file name of the output if the user didn't give one,
+    /// or `None` if the output should be printed.
+    pub fn default_file_name(&self) -> Option<&'static str> {
+        match self {
+            OutputFormat::Json => Some("output.json"),
+            OutputFormat::Gitlab => Some("gl-code-quality-report.json"),
+            OutputFormat::Checkstyle => Some("checkstyle-result.xml"),
+            OutputFormat::Github => None,
+            OutputFormat::Markdown => Some("report.md"),
+            OutputFormat::Csv => Some("report.csv"),
+            OutputFormat::Tsv => Some("report.tsv"),
+        }
+    }
+
+    pub fn render(&self, output: &Output) -> Result<String> {
+        self.render_with(output, &RenderOpts::default())
+    }
+
+    pub fn render_with(&self, output: &Output, opts: &RenderOpts) -> Result<String> {
+        match self {
+            OutputFormat::Json => output.to_json_string_pretty(),
+            OutputFormat::Gitlab => gitlab::render(&findings(output)),
+            OutputFormat::Checkstyle => Ok(checkstyle::render(&findings(output))),
+            OutputFormat::Github => Ok(github::render(&findings(output))),
+            OutputFormat::Markdown => Ok(markdown::render(&findings(output), opts)),
+            OutputFormat::Csv => Ok(delimited::render(&findings(output), opts, ',')),
+            OutputFormat::Tsv => Ok(delimited::render(&findings(output), opts, '\t')),
+        }
+    }
+}
+
+// TODO: put this method in a derive macro
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Gitlab => "gitlab",
+            OutputFormat::Checkstyle => "checkstyle",
+            OutputFormat::Github => "github",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+        };
+        f.write_str(str)
+    }
+}
+
+// TODO: put this method in a derive macro
+impl FromStr for OutputFormat {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "gitlab" => Ok(Self::Gitlab),
+            "checkstyle" => Ok(Self::Checkstyle),
+            "github" => Ok(Self::Github),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            _ => Err(crate::Error::ParseUnsupportedEnumVariant(
+                "output format",
+                s.to_string(),
+                OutputFormat::all()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            )),
+        }
+    }
+}
+
+/// Options of rendering, which are ignored by the formats they don't apply to.
+#[derive(Debug, Default, Clone)]
+pub struct RenderOpts {
+    /// Show at most this many results of each guideline in the Markdown report.
+    pub max_per_guideline: Option<usize>,
+    /// Columns of CSV and TSV, or all of them if it's empty.
+    pub columns: Vec<Column>,
+    /// Give each guideline a row in CSV and TSV, when a result violates several of them.
+    pub row_per_guideline: bool,
+}
+
+/// A Markdown summary of the output for the job summary of GitHub Actions.
+pub fn github_step_summary(output: &Output) -> String {
+    github::step_summary(&findings(output))
+}
+
+/// A checking result, with what the output formats need.
+#[derive(Debug, Clone)]
+pub struct Finding<'a> {
+    pub info: &'a CheckInfo,
+    /// Path of the file, relative to the current directory if it's inside,
+    /// and always separated by `/`.
+    pub path: String,
+    pub level: CheckLevel,
+    /// Name of the check that found this, such as `clippy::unwrap_used`,
+    /// or `unsafe-audit/missing-safety-comment` for tools other than the linters.
+    pub check_name: String,
+    pub description: String,
+    /// Secondary locations and child messages of the result, one per line, such as
+    /// `src/lib.rs:6:38: and (1) occurred earlier here` and `help: ...`.
+    pub notes: Vec<String>,
+    /// A fingerprint that is unique among the findings, and stable across runs.
+    pub fingerprint: String,
+}
+
+/// Turn the results of an output into findings.
+pub fn findings(output: &Output) -> Vec<Finding<'_>> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    output
+        .check_info
+        .iter()
+        .zip(fingerprints(&output.check_info))
+        .map(|(info, fingerprint)| {
+            let path = info
+                .file_path
+                .as_deref()
+                .map(|path| relative_path(path, &cwd))
+                .unwrap_or_default();
+            Finding {
+                info,
+                level: info.level(),
+                check_name: check_name(info),
+                description: description(info),
+                notes: notes(info, &path, &cwd),
+                path,
+                fingerprint,
+            }
+        })
+        .collect()
+}
+
+fn relative_path(path: &Path, base: &Path) -> String {
+    // paths could also be relative to another directory, such as the checked crate's
+    let path = path.strip_prefix(base).unwrap_or(path);
+    let path = path.to_string_lossy().replace('\\', "/");
+    path.trim_start_matches("./").to_string()
+}
+
+fn notes(info: &CheckInfo, path: &str, cwd: &Path) -> Vec<String> {
+    let locations = info.secondary_spans.iter().map(|span| {
+        let mut location = span
+            .file_path
+            .as_deref()
+            .map_or_else(|| path.to_string(), |file| relative_path(file, cwd));
+        location.push_str(&format!(":{}", span.begin_line));
+        if let Some(column) = span.begin_column {
+            location.push_str(&format!(":{column}"));
+        }
+        match &span.label {
+            Some(label) => format!("{location}: {label}"),
+            None => location,
+        }
+    });
+    locations
+        .chain(info.children.iter().map(ToString::to_string))
+        .collect()
+}
+
+fn check_name(info: &CheckInfo) -> String {
+    if info.defect_name.contains("::") || info.defect_name.is_empty() {
+        info.defect_name.clone()
+    } else {
+        format!("{}/{}", info.tool, info.defect_name)
+    }
+}
+
+/// The message of a result, followed by the guidelines it violates.
+fn description(info: &CheckInfo) -> String {
+    let message = if info.help_info.is_empty() {
+        &info.defect_name
+    } else {
+        &info.help_info
+    };
+    if info.guideline_list.is_empty() {
+        return message.clone();
+    }
+    let guidelines = info
+        .guideline_list
+        .iter()
+        .map(|gl| format!("{} {}", gl.id.to_catalogue_string(), gl.name))
+        .collect::<Vec<_>>()
+        .join("; ");
+    format!("{message} (violates: {guidelines})")
+}
@@ -1,5 +1,7 @@
 pub mod cli;
 mod errors;
+pub mod format;
+pub mod locale;
 pub mod parser;
 pub mod tools;
 pub mod utils;
@@ -0,0 +1,238 @@
+//! Localization of the texts produced by eunomia itself, such as messages
+//! and labels of the terminal output.
+//!
+//! Outputs of the tools (clippy, rustc, etc) are not translated.
+
+use std::env;
+use std::fmt::{Display, Formatter};
+
+use crate::parser::CheckLevel;
+use crate::tools::SupportedTool;
+
+/// The language that will be used when no other language was requested,
+/// or when a text does not have a translation in the requested language.
+pub const DEFAULT_LANG: &str = "en";
+
+/// A normalized language code, such as `"en"`, `"zh"` or `"zh-cn"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lang(String);
+
+impl Lang {
+    /// Normalize a language code, or a locale name from environment variables
+    /// such as `"zh_CN.UTF-8"`, into a [`Lang`].
+    pub fn new(code: &str) -> Self {
+        let code = code
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .replace('_', "-")
+            .to_ascii_lowercase();
+        Lang(code)
+    }
+
+    /// Get the language from the `LANG` environment variable,
+    /// the special `C` and `POSIX` locales are ignored.
+    pub fn from_env() -> Option<Self> {
+        let lang = env::var("LANG").ok()?;
+        match lang.trim() {
+            "" | "C" | "POSIX" => None,
+            l if l.starts_with("C.") => None,
+            l => Some(Lang::new(l)),
+        }
+    }
+
+    /// Decide the language to use, the one requested from commandline takes
+    /// precedence over the `LANG` environment variable.
+    pub fn resolve(requested: Option<&str>) -> Self {
+        requested
+            .map(Lang::new)
+            .or_else(Lang::from_env)
+            .unwrap_or_default()
+    }
+
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+
+    /// The primary language subtag, for example, `"zh"` is the primary language of `"zh-cn"`.
+    pub fn primary(&self) -> &str {
+        self.0.split('-').next().unwrap_or_default()
+    }
+}
+
+impl Default for Lang {
+    fn default() -> Self {
+        Lang(DEFAULT_LANG.to_string())
+    }
+}
+
+impl Display for Lang {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Messages and labels of eunomia's own output.
+#[derive(Debug)]
+pub enum Msg<'a> {
+    OverrideSrcPath,
+    CargoNotFound,
+    ToolNotSupported(SupportedTool, &'a str),
+    Level,
+    Enforcement,
+    Tools,
+    Versions,
+    Tags,
+    Description,
+    Rationale,
+    NegativeExample,
+    PositiveExample,
+    References,
+    ImportSummary(usize, usize, usize),
+    UnknownLint(SupportedTool, &'a str),
+    RenamedLint(SupportedTool, &'a str, &'a str),
+    RemovedLint(SupportedTool, &'a str, &'a str),
+    DidYouMean(&'a str),
+    LintsValid(usize),
+    InvalidLints(usize),
+    NothingToFix,
+    FixApplied(usize, usize),
+    RemainingViolations(usize),
+    AdvisoryDbNotConfigured,
+    SourceSkipped(&'a str),
+    DiffSummary(usize, usize, usize),
+    NewResultsAtLevel(usize, CheckLevel),
+}
+
+impl Msg<'_> {
+    /// Get the text of this message in the given language,
+    /// English will be used when it has no translation.
+    pub fn text(&self, lang: &Lang) -> String {
+        match lang.primary() {
+            "zh" => self.zh(),
+            _ => self.en(),
+        }
+    }
+
+    fn en(&self) -> String {
+        use Msg::*;
+        match self {
+            OverrideSrcPath => "overriding src path from commandline".into(),
+            CargoNotFound => "We couldn't find `cargo`'s executable to run, make sure it's \
+                in the path. Because some tools (such as miri) could not run without it, the result \
+                might be incomplete, continue? [y/N]: "
+                .into(),
+            ToolNotSupported(tool, id) => {
+                format!("running '{tool}' is not supported yet, skipping check for '{id}'")
+            }
+            Level => "level".into(),
+            Enforcement => "enforcement".into(),
+            Tools => "tools".into(),
+            Versions => "versions".into(),
+            Tags => "tags".into(),
+            Description => "Description".into(),
+            Rationale => "Rationale".into(),
+            NegativeExample => "Negative example".into(),
+            PositiveExample => "Positive example".into(),
+            References => "References".into(),
+            ImportSummary(added, removed, changed) => {
+                format!("{added} added, {removed} removed, {changed} changed")
+            }
+            UnknownLint(tool, lint) => format!("unknown {tool} lint `{lint}`"),
+            RenamedLint(tool, lint, new) => {
+                format!("{tool} lint `{lint}` has been renamed to `{new}`")
+            }
+            RemovedLint(tool, lint, reason) => {
+                format!("{tool} lint `{lint}` has been removed: {reason}")
+            }
+            DidYouMean(names) => format!(", did you mean: {names}?"),
+            LintsValid(count) => format!("all of the {count} guidelines use valid lints"),
+            InvalidLints(count) => format!("found {count} problem(s) in the guidelines"),
+            NothingToFix => "no violation could be fixed automatically".into(),
+            FixApplied(count, files) => format!("applied {count} fix(es) in {files} file(s)"),
+            RemainingViolations(0) => "all violations of the guidelines are gone".into(),
+            RemainingViolations(count) => format!("{count} violation(s) remain"),
+            AdvisoryDbNotConfigured => "no advisory database is configured with `advisory.db_path` \
+                in the rules file, skipping checks of security advisories"
+                .into(),
+            SourceSkipped(reason) => format!("{reason}, skipping the file"),
+            DiffSummary(new, fixed, unchanged) => {
+                format!("{new} new, {fixed} fixed, {unchanged} unchanged")
+            }
+            NewResultsAtLevel(count, level) => {
+                format!("found {count} new result(s) of level '{level}' or above")
+            }
+        }
+    }
+
+    fn zh(&self) -> String {
+        use Msg::*;
+        match self {
+            OverrideSrcPath => "使用命令行中指定的源代码路径".into(),
+            CargoNotFound => "找不到可执行的 `cargo`，请确认它在 PATH 中。\
+                由于部分工具（如 miri）依赖于它，检查结果可能不完整，是否继续？[y/N]: "
+                .into(),
+            ToolNotSupported(tool, id) => {
+                format!("暂不支持运行 '{tool}'，跳过对 '{id}' 的检查")
+            }
+            Level => "级别".into(),
+            Enforcement => "检测方式".into(),
+            Tools => "检测工具".into(),
+            Versions => "适用版本".into(),
+            Tags => "标签".into(),
+            Description => "说明".into(),
+            Rationale => "理由".into(),
+            NegativeExample => "反例".into(),
+            PositiveExample => "正例".into(),
+            References => "参考".into(),
+            ImportSummary(added, removed, changed) => {
+                format!("新增 {added} 条，移除 {removed} 条，修改 {changed} 条")
+            }
+            UnknownLint(tool, lint) => format!("未知的 {tool} lint `{lint}`"),
+            RenamedLint(tool, lint, new) => {
+                format!("{tool} lint `{lint}` 已被重命名为 `{new}`")
+            }
+            RemovedLint(tool, lint, reason) => {
+                format!("{tool} lint `{lint}` 已被移除：{reason}")
+            }
+            DidYouMean(names) => format!("，是否是指：{names}？"),
+            LintsValid(count) => format!("全部 {count} 条规则使用的 lint 均有效"),
+            InvalidLints(count) => format!("在规则中发现 {count} 个问题"),
+            NothingToFix => "没有可以自动修复的违规".into(),
+            FixApplied(count, files) => format!("已在 {files} 个文件中应用 {count} 处修复"),
+            RemainingViolations(0) => "所有违反规则之处均已修复".into(),
+            RemainingViolations(count) => format!("仍有 {count} 处违规"),
+            AdvisoryDbNotConfigured => {
+                "规则文件中未通过 `advisory.db_path` 指定安全公告数据库，跳过安全公告检查".into()
+            }
+            SourceSkipped(reason) => format!("{reason}，跳过该文件"),
+            DiffSummary(new, fixed, unchanged) => {
+                format!("新增 {new} 处，修复 {fixed} 处，未变 {unchanged} 处")
+            }
+            NewResultsAtLevel(count, level) => {
+                format!("发现 {count} 处级别为 '{level}' 或更严重的新增结果")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lang, Msg};
+
+    #[test]
+    fn normalize_lang() {
+        assert_eq!(Lang::new("zh_CN.UTF-8").code(), "zh-cn");
+        assert_eq!(Lang::new("zh_CN.UTF-8").primary(), "zh");
+        assert_eq!(Lang::new("en_US@euro").code(), "en-us");
+        assert_eq!(Lang::new("EN").code(), "en");
+        assert_eq!(Lang::default().code(), "en");
+    }
+
+    #[test]
+    fn translated_msg() {
+        assert_eq!(Msg::References.text(&Lang::new("zh-TW")), "参考");
+        assert_eq!(Msg::References.text(&Lang::new("fr")), "References");
+    }
+}
@@ -0,0 +1,563 @@
+//! This module parses the Markdown sources of the coding guideline book,
+//! and imports the guidelines it describes into a [`CodingGuidelines`] catalogue.
+//!
+//! A guideline in the book usually looks like:
+//!
+//! ```markdown
+//! ## G.TYP.BOL.01 不应将布尔值和布尔字面量进行比较
+//!
+//! **【级别】** 要求
+//!
+//! **【描述】**
+//!
+//! ...
+//!
+//! **【Lint 检测】**
+//!
+//! | lint name | Clippy 可检测 | Rustc 可检测 | Lint Group | 级别 |
+//! | --------- | ------------- | ------------ | ---------- | ---- |
+//! | [bool_comparison](https://rust-lang.github.io/rust-clippy/master/#bool_comparison) | yes | no | complexity | warn |
+//! ```
+
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::guideline::{
+    CheckLevel, CheckTool, CodingGuidelines, Examples, Guideline, GuidelineID, LocalizedText,
+};
+use super::output_file::normalize_lint_name;
+use crate::locale::{Lang, Msg};
+use crate::tools::SupportedTool;
+use crate::{utils, Result};
+
+lazy_static! {
+    static ref RE_HEADING: Regex = Regex::new(r"^(#{1,6})\s+(.*?)\s*#*\s*$").unwrap();
+    static ref RE_GUIDELINE_HEADING: Regex =
+        Regex::new(r"^(?P<id>[PGpg]\.[\w.]+?\.\d+)\s*[:：]?\s*(?P<title>.*)$").unwrap();
+    static ref RE_SECTION_MARKER: Regex =
+        Regex::new(r"^\s*(?:\*\*|__)?【(?P<key>[^】]+)】(?:\*\*|__)?\s*[:：]?\s*(?P<rest>.*)$")
+            .unwrap();
+    static ref RE_LINT_LINK: Regex =
+        Regex::new(r"\[`?(?P<name>[\w:-]+)`?\]\((?P<url>[^)]*)\)").unwrap();
+    static ref RE_LINT_CODE: Regex = Regex::new(r"`(?P<name>[\w:-]+)`").unwrap();
+}
+
+/// The level of a guideline written in the book, which is coarser than [`CheckLevel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookLevel {
+    /// Written as "要求" (requirement) in the book.
+    Requirement,
+    /// Written as "建议" (suggestion) in the book.
+    Suggestion,
+    /// Written as one of the [`CheckLevel`] variants.
+    Exact(CheckLevel),
+}
+
+impl BookLevel {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_matches('*').trim().to_lowercase();
+        match s.as_str() {
+            "要求" | "必须" | "requirement" | "required" => Some(BookLevel::Requirement),
+            "建议" | "推荐" | "suggestion" | "recommended" => Some(BookLevel::Suggestion),
+            _ => serde_json::from_value(serde_json::Value::String(s))
+                .ok()
+                .map(BookLevel::Exact),
+        }
+    }
+
+    /// Check whether a [`CheckLevel`] agrees with this level, so that the level
+    /// that was deliberately chosen in the catalogue will not be overridden.
+    pub fn accepts(&self, level: CheckLevel) -> bool {
+        use CheckLevel::*;
+        match self {
+            BookLevel::Requirement => matches!(level, Fatal | Severe | Warn),
+            BookLevel::Suggestion => matches!(level, Prompt | Info),
+            BookLevel::Exact(l) => *l == level,
+        }
+    }
+
+    pub fn to_check_level(self) -> CheckLevel {
+        match self {
+            BookLevel::Requirement => CheckLevel::Warn,
+            BookLevel::Suggestion => CheckLevel::Prompt,
+            BookLevel::Exact(l) => l,
+        }
+    }
+}
+
+/// A guideline item parsed from the book.
+#[derive(Debug)]
+pub struct BookGuideline {
+    pub id: GuidelineID,
+    pub title: String,
+    pub level: Option<BookLevel>,
+    pub description: Option<String>,
+    /// Lints mentioned in the lint section, along with the tool they belong to.
+    pub lints: Vec<(SupportedTool, String)>,
+}
+
+impl BookGuideline {
+    fn new(id: GuidelineID, title: &str) -> Self {
+        BookGuideline {
+            id,
+            title: title.trim().to_string(),
+            level: None,
+            description: None,
+            lints: vec![],
+        }
+    }
+
+    /// Level of this guideline when it's newly added to a catalogue.
+    ///
+    /// When the book doesn't specify it, principles ('P') are [`CheckLevel::Prompt`],
+    /// the others are [`CheckLevel::default`].
+    fn check_level(&self) -> CheckLevel {
+        match self.level {
+            Some(level) => level.to_check_level(),
+            None if self.id.ty == 'p' => CheckLevel::Prompt,
+            None => CheckLevel::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Description,
+    Lint,
+    Other,
+}
+
+impl Section {
+    fn from_key(key: &str) -> Option<Self> {
+        let key = key.trim().to_lowercase();
+        if key.contains("级别") || key.contains("level") {
+            None
+        } else if key.contains("描述") || key.contains("description") {
+            Some(Section::Description)
+        } else if key.contains("lint") || key.contains("检测") {
+            Some(Section::Lint)
+        } else {
+            Some(Section::Other)
+        }
+    }
+}
+
+/// Columns of a lint table telling whether a lint is provided by clippy or rustc.
+#[derive(Debug, Default, Clone, Copy)]
+struct LintTableHeader {
+    clippy: Option<usize>,
+    rustc: Option<usize>,
+}
+
+#[derive(Default)]
+struct ChapterParser {
+    result: Vec<BookGuideline>,
+    current: Option<(BookGuideline, usize)>,
+    section: Option<Section>,
+    description: Vec<String>,
+    table_header: Option<LintTableHeader>,
+}
+
+impl ChapterParser {
+    fn finish_current(&mut self) {
+        if let Some((mut gl, _)) = self.current.take() {
+            let desc = self.description.join("\n").trim().to_string();
+            if !desc.is_empty() {
+                gl.description = Some(desc);
+            }
+            self.result.push(gl);
+        }
+        self.section = None;
+        self.description.clear();
+        self.table_header = None;
+    }
+
+    fn parse_heading(&mut self, depth: usize, text: &str) {
+        if let Some(cap) = RE_GUIDELINE_HEADING.captures(text) {
+            if let Ok(id) = cap["id"].parse::<GuidelineID>() {
+                self.finish_current();
+                self.current = Some((BookGuideline::new(id, &cap["title"]), depth));
+                return;
+            }
+        }
+        match &self.current {
+            // a heading on the same or upper level ends the current guideline
+            Some((_, cur_depth)) if depth <= *cur_depth => self.finish_current(),
+            // section markers could also be written as headings
+            Some(_) => {
+                if let Some(cap) = RE_SECTION_MARKER.captures(text) {
+                    self.parse_section_marker(&cap["key"], &cap["rest"]);
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn parse_section_marker(&mut self, key: &str, rest: &str) {
+        let Some((gl, _)) = self.current.as_mut() else {
+            return;
+        };
+        self.table_header = None;
+        self.section = Section::from_key(key);
+        match self.section {
+            None => gl.level = BookLevel::parse(rest),
+            Some(Section::Description) if !rest.trim().is_empty() => {
+                self.description.push(rest.trim().to_string());
+            }
+            _ => (),
+        }
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        match self.section {
+            Some(Section::Description) => self.description.push(line.trim_end().to_string()),
+            Some(Section::Lint) => self.parse_lint_line(line),
+            _ => (),
+        }
+    }
+
+    fn parse_lint_line(&mut self, line: &str) {
+        let Some((gl, _)) = self.current.as_mut() else {
+            return;
+        };
+        let trimmed = line.trim();
+        if !trimmed.starts_with('|') {
+            self.table_header = None;
+            // lints could also be listed as list items
+            if let Some(item) = trimmed.strip_prefix(['-', '*', '+']) {
+                if let Some(lint) = lint_from_text(item, None) {
+                    push_lint(gl, lint);
+                }
+            }
+            return;
+        }
+
+        let cells = trimmed
+            .trim_matches('|')
+            .split('|')
+            .map(str::trim)
+            .collect::<Vec<_>>();
+        if cells
+            .iter()
+            .all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':' | ' ')))
+        {
+            // separator row
+            return;
+        }
+        let Some(header) = self.table_header else {
+            let mut header = LintTableHeader::default();
+            for (idx, cell) in cells.iter().enumerate() {
+                let cell = cell.to_lowercase();
+                if cell.contains("clippy") {
+                    header.clippy = Some(idx);
+                } else if cell.contains("rustc") {
+                    header.rustc = Some(idx);
+                }
+            }
+            self.table_header = Some(header);
+            return;
+        };
+
+        let is_yes = |idx: Option<usize>| {
+            idx.and_then(|i| cells.get(i)).is_some_and(|c| {
+                matches!(
+                    c.to_lowercase().as_str(),
+                    "yes" | "y" | "true" | "是" | "√" | "✔" | "✅"
+                )
+            })
+        };
+        let tool = if header.clippy.is_none() && header.rustc.is_none() {
+            None
+        } else if is_yes(header.clippy) {
+            Some(SupportedTool::Clippy)
+        } else if is_yes(header.rustc) {
+            Some(SupportedTool::Rustc)
+        } else {
+            // none of the tools could detect it
+            return;
+        };
+        if let Some(lint) = cells.first().and_then(|c| lint_from_text(c, tool)) {
+            push_lint(gl, lint);
+        }
+    }
+}
+
+/// Extract a lint name from a piece of Markdown text, such as a table cell or a list item.
+///
+/// When the tool is unknown, lints with `clippy::` prefix, or linked to clippy's
+/// documentation are clippy lints, the others are assumed to be rustc lints.
+fn lint_from_text(text: &str, tool: Option<SupportedTool>) -> Option<(SupportedTool, String)> {
+    let (name, url) = if let Some(cap) = RE_LINT_LINK.captures(text) {
+        (cap["name"].to_string(), cap["url"].to_string())
+    } else if let Some(cap) = RE_LINT_CODE.captures(text) {
+        (cap["name"].to_string(), String::new())
+    } else {
+        let word = text.split_whitespace().next()?;
+        if !word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-:".contains(c))
+        {
+            return None;
+        }
+        (word.to_string(), String::new())
+    };
+
+    let tool = tool.unwrap_or(if name.starts_with("clippy::") || url.contains("clippy") {
+        SupportedTool::Clippy
+    } else {
+        SupportedTool::Rustc
+    });
+    let name = match tool {
+        SupportedTool::Clippy => format!("clippy::{}", normalize_lint_name(&name)),
+        _ => name,
+    };
+    Some((tool, name))
+}
+
+fn push_lint(gl: &mut BookGuideline, lint: (SupportedTool, String)) {
+    if !gl.lints.contains(&lint) {
+        gl.lints.push(lint);
+    }
+}
+
+/// Parse all the guidelines in a Markdown chapter of the book.
+pub fn parse_book_chapter(content: &str) -> Vec<BookGuideline> {
+    let mut parser = ChapterParser::default();
+    let mut in_code_block = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        if let Some(cap) = RE_HEADING.captures(line) {
+            parser.parse_heading(cap[1].len(), &cap[2]);
+        } else if let Some(cap) = RE_SECTION_MARKER.captures(line) {
+            parser.parse_section_marker(&cap["key"], &cap["rest"]);
+        } else {
+            parser.parse_line(line);
+        }
+    }
+    parser.finish_current();
+
+    parser.result
+}
+
+/// Parse all the guidelines in the Markdown files under a directory of the book,
+/// a guideline will be ignored if its ID has been seen in previous files.
+pub fn parse_book<P: AsRef<Path>>(dir: P) -> Result<Vec<BookGuideline>> {
+    let mut result: Vec<BookGuideline> = vec![];
+    for file in utils::files_with_extension(dir, "md")? {
+        let content = utils::read_to_string(&file)?;
+        for gl in parse_book_chapter(&content) {
+            if result.iter().all(|g| g.id != gl.id) {
+                result.push(gl);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Differences of a catalogue before and after importing guidelines from the book.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub added: Vec<GuidelineID>,
+    pub removed: Vec<GuidelineID>,
+    /// IDs of the changed guidelines, along with the names of the changed fields.
+    pub changed: Vec<(GuidelineID, Vec<&'static str>)>,
+}
+
+impl ImportReport {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Render a human readable report in the given language.
+    pub fn render(&self, lang: &Lang) -> String {
+        let mut res = format!(
+            "{}\n",
+            Msg::ImportSummary(self.added.len(), self.removed.len(), self.changed.len()).text(lang)
+        );
+        for id in &self.added {
+            res.push_str(&format!("+ {}\n", id.to_catalogue_string()));
+        }
+        for id in &self.removed {
+            res.push_str(&format!("- {}\n", id.to_catalogue_string()));
+        }
+        for (id, fields) in &self.changed {
+            res.push_str(&format!(
+                "~ {} ({})\n",
+                id.to_catalogue_string(),
+                fields.join(", ")
+            ));
+        }
+        res
+    }
+}
+
+/// Update a catalogue with the guidelines parsed from the book.
+///
+/// - Guidelines that only exist in the book are added.
+/// - Guidelines that don't exist in the book are removed if `prune` is set, otherwise
+///   they are kept, since the book could be a part of it, such as a single chapter.
+/// - For guidelines that exist in both, texts of their names and descriptions in
+///   `lang` are updated, so is the level when it doesn't agree with the book.
+///   Their tool mappings are kept, since they could be edited by hand, only lints
+///   that are newly mentioned in the book will be added.
+pub fn import_guidelines<'g>(
+    catalogue: &mut CodingGuidelines<'g>,
+    book: &'g [BookGuideline],
+    lang: &Lang,
+    prune: bool,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+
+    if prune {
+        catalogue.coding_guidelines.retain(|gl| {
+            let exists = book.iter().any(|b| b.id == gl.id);
+            if !exists {
+                report.removed.push(gl.id.clone());
+            }
+            exists
+        });
+    }
+
+    for book_gl in book {
+        let Some(gl) = catalogue
+            .coding_guidelines
+            .iter_mut()
+            .find(|gl| gl.id == book_gl.id)
+        else {
+            catalogue
+                .coding_guidelines
+                .push(new_guideline(book_gl, lang));
+            report.added.push(book_gl.id.clone());
+            continue;
+        };
+
+        let mut changed = vec![];
+        if gl.name.set(lang, &book_gl.title) {
+            changed.push("name");
+        }
+        if let Some(level) = book_gl.level {
+            if !level.accepts(gl.level) {
+                gl.level = level.to_check_level();
+                changed.push("level");
+            }
+        }
+        if let Some(desc) = &book_gl.description {
+            let desc_changed = match &mut gl.description {
+                Some(text) => text.set(lang, desc),
+                None => {
+                    gl.description = Some(LocalizedText::new(lang, desc));
+                    true
+                }
+            };
+            if desc_changed {
+                changed.push("description");
+            }
+        }
+        let mut tool_changed = false;
+        for (tool, ident) in &book_gl.lints {
+            let exists = gl.tool.iter().any(|t| {
+                t.name == *tool && normalize_lint_name(t.ident) == normalize_lint_name(ident)
+            });
+            if !exists {
+                gl.tool.push(CheckTool { name: *tool, ident });
+                tool_changed = true;
+            }
+        }
+        if tool_changed {
+            changed.push("tool");
+        }
+
+        if !changed.is_empty() {
+            report.changed.push((gl.id.clone(), changed));
+        }
+    }
+
+    report
+}
+
+fn new_guideline<'g>(book_gl: &'g BookGuideline, lang: &Lang) -> Guideline<'g> {
+    Guideline {
+        id: book_gl.id.clone(),
+        name: LocalizedText::new(lang, &book_gl.title),
+        level: book_gl.check_level(),
+        tool: book_gl
+            .lints
+            .iter()
+            .map(|(name, ident)| CheckTool { name: *name, ident })
+            .collect(),
+        description: book_gl
+            .description
+            .as_deref()
+            .map(|desc| LocalizedText::new(lang, desc)),
+        rationale: None,
+        examples: Examples::default(),
+        references: vec![],
+        versions: None,
+        tags: vec![],
+        enforcement: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint_from_text, parse_book_chapter, BookLevel};
+    use crate::parser::CheckLevel;
+    use crate::tools::SupportedTool;
+
+    #[test]
+    fn parse_level() {
+        assert_eq!(BookLevel::parse(" 要求 "), Some(BookLevel::Requirement));
+        assert_eq!(BookLevel::parse("建议"), Some(BookLevel::Suggestion));
+        assert_eq!(
+            BookLevel::parse("Severe"),
+            Some(BookLevel::Exact(CheckLevel::Severe))
+        );
+        assert_eq!(BookLevel::parse("xxx"), None);
+        assert!(BookLevel::Requirement.accepts(CheckLevel::Fatal));
+        assert!(!BookLevel::Suggestion.accepts(CheckLevel::Warn));
+    }
+
+    #[test]
+    fn parse_lint_text() {
+        assert_eq!(
+            lint_from_text(
+                "[bool_comparison](https://rust-lang.github.io/rust-clippy/master/#bool_comparison)",
+                None
+            ),
+            Some((SupportedTool::Clippy, "clippy::bool_comparison".into()))
+        );
+        assert_eq!(
+            lint_from_text(" `non_snake_case` ", None),
+            Some((SupportedTool::Rustc, "non_snake_case".into()))
+        );
+        assert_eq!(
+            lint_from_text("clippy::double-neg", Some(SupportedTool::Clippy)),
+            Some((SupportedTool::Clippy, "clippy::double_neg".into()))
+        );
+        assert_eq!(lint_from_text("无", None), None);
+    }
+
+    #[test]
+    fn ignore_headings_in_code_blocks() {
+        let chapter = "## G.EXP.04 自增或自减运算使用+=或-=
+
+```rust
+## G.EXP.05 this is not a heading
+```
+";
+        let gls = parse_book_chapter(chapter);
+        assert_eq!(gls.len(), 1);
+        assert_eq!(gls[0].title, "自增或自减运算使用+=或-=");
+        assert_eq!(gls[0].description, None);
+    }
+}
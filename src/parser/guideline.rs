@@ -1,13 +1,17 @@
 //! This module contains definitions of CodingGuidelines related data.
 
 use super::{de, Deserialize, JsonStruct, Serialize};
+use crate::locale::{Lang, Msg, DEFAULT_LANG};
 use crate::tools::SupportedTool;
 use crate::{Error, Result};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
 use std::{hash::Hash, str::FromStr};
 
 /// Contains a `Vec` of [`Guideline`] items.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct CodingGuidelines<'g> {
     #[serde(borrow)]
     pub coding_guidelines: Vec<Guideline<'g>>,
@@ -26,35 +30,412 @@ impl<'g> CodingGuidelines<'g> {
 
         hm
     }
+
+    /// Serialize into a pretty JSON string, indented with four spaces like the
+    /// built-in catalogue does.
+    pub fn to_json_string_pretty(&self) -> Result<String> {
+        let mut buf = Vec::new();
+        let fmt = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        let mut ser = serde_json::Serializer::with_formatter(&mut buf, fmt);
+        self.serialize(&mut ser)?;
+        Ok(String::from_utf8(buf)?)
+    }
 }
 
 impl<'g> JsonStruct<'g> for CodingGuidelines<'g> {}
 
 /// Complete information about a coding guideline item,
 /// including its relation with tools.
-#[derive(Debug, Deserialize)]
+///
+/// Only `id`, `name` and `tool` are required, the rest of the fields are
+/// documentations that help reviewers understand why a guideline exists.
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Guideline<'g> {
+    #[serde(serialize_with = "serialize_id_uppercase")]
     pub id: GuidelineID,
-    pub name: &'g str,
+    #[serde(borrow)]
+    pub name: LocalizedText<'g>,
     #[serde(default)]
     pub level: CheckLevel,
     // FIXME: what a dumb non-plural name!
+    /// Tools that are able to check this guideline.
+    ///
+    /// This field is required, an empty list explicitly marks a guideline that
+    /// none of the [`SupportedTool`] could enforce yet, which needs to be reviewed manually.
     pub tool: Vec<CheckTool<'g>>,
+    /// Detailed description of this guideline.
+    #[serde(default, borrow, skip_serializing_if = "Option::is_none")]
+    pub description: Option<LocalizedText<'g>>,
+    /// The reason why this guideline exists.
+    #[serde(default, borrow, skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<LocalizedText<'g>>,
+    /// Code snippets that comply with, or violate this guideline.
+    #[serde(default, borrow, skip_serializing_if = "Examples::is_empty")]
+    pub examples: Examples<'g>,
+    /// External links to further readings, such as the guideline book or lint docs.
+    #[serde(default, borrow, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Cow<'g, str>>,
+    /// Rust versions this guideline applies to, such as `">=1.56"`.
+    #[serde(default, borrow, skip_serializing_if = "Option::is_none")]
+    pub versions: Option<Cow<'g, str>>,
+    #[serde(default, borrow, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Cow<'g, str>>,
+    /// How well this guideline could be enforced by tools,
+    /// use [`Guideline::enforcement`] to get the actual value when this is not specified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforcement: Option<Enforcement>,
+}
+
+impl<'g> Guideline<'g> {
+    /// Returns `true` if at least one tool is able to check this guideline.
+    pub fn is_enforceable(&self) -> bool {
+        !self.tool.is_empty()
+    }
+
+    /// Get the [`Enforcement`] of this guideline.
+    ///
+    /// If it was not specified, this will be [`Enforcement::Automatic`] when
+    /// this guideline [`is_enforceable`](Guideline::is_enforceable),
+    /// otherwise it'll be [`Enforcement::Manual`].
+    pub fn enforcement(&self) -> Enforcement {
+        self.enforcement.unwrap_or(if self.is_enforceable() {
+            Enforcement::Automatic
+        } else {
+            Enforcement::Manual
+        })
+    }
+
+    /// Generate a human readable explanation of this guideline,
+    /// containing all the documentations it has, in the given language.
+    pub fn explain(&self, lang: &Lang) -> String {
+        let mut res = format!(
+            "{}: {}\n\n",
+            self.id.to_catalogue_string(),
+            self.name.get(lang)
+        );
+        let mut push_field = |label: Msg, value: &dyn Display| {
+            res.push_str(&format!("{}: {value}\n", label.text(lang)));
+        };
+        push_field(Msg::Level, &self.level);
+        push_field(Msg::Enforcement, &self.enforcement());
+        if !self.tool.is_empty() {
+            let tools = self
+                .tool
+                .iter()
+                .map(|t| format!("{} ({})", t.name, t.ident))
+                .collect::<Vec<_>>();
+            push_field(Msg::Tools, &tools.join(", "));
+        }
+        if let Some(versions) = &self.versions {
+            push_field(Msg::Versions, versions);
+        }
+        if !self.tags.is_empty() {
+            push_field(Msg::Tags, &self.tags.join(", "));
+        }
+
+        let mut push_section = |title: Msg, content: &str| {
+            res.push_str(&format!(
+                "\n{}:\n{}\n",
+                title.text(lang),
+                content.trim_end()
+            ));
+        };
+        if let Some(desc) = &self.description {
+            push_section(Msg::Description, desc.get(lang));
+        }
+        if let Some(rationale) = &self.rationale {
+            push_section(Msg::Rationale, rationale.get(lang));
+        }
+        for code in &self.examples.negative {
+            push_section(Msg::NegativeExample, code);
+        }
+        for code in &self.examples.positive {
+            push_section(Msg::PositiveExample, code);
+        }
+        if !self.references.is_empty() {
+            push_section(Msg::References, &self.references.join("\n"));
+        }
+
+        res
+    }
+
+    /// Get the basic information of this guideline, with its name in the given language.
+    pub fn summary(&self, lang: &Lang) -> GuidelineSummary {
+        GuidelineSummary {
+            id: self.id.clone(),
+            name: self.name.get(lang).to_string(),
+            level: self.level,
+        }
+    }
+}
+
+/// A piece of text that could be written in multiple languages.
+///
+/// It could be deserialized from either a plain string, which is the text in
+/// [`DEFAULT_LANG`], or a map of language codes and texts, such as:
+///
+/// ```json
+/// { "en": "Use a consistent naming style", "zh": "使用统一的命名风格" }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocalizedText<'g> {
+    Plain(Cow<'g, str>),
+    Localized(Vec<(Lang, Cow<'g, str>)>),
+}
+
+impl<'g> LocalizedText<'g> {
+    /// Create a text in the given language, it's a plain text
+    /// if the language is [`DEFAULT_LANG`].
+    pub fn new(lang: &Lang, text: &'g str) -> Self {
+        if lang.code() == DEFAULT_LANG {
+            LocalizedText::Plain(Cow::Borrowed(text))
+        } else {
+            LocalizedText::Localized(vec![(lang.clone(), Cow::Borrowed(text))])
+        }
+    }
+
+    /// Set the text in the given language, while keeping the texts in other languages.
+    ///
+    /// Returns `true` if the text has been changed.
+    pub fn set(&mut self, lang: &Lang, text: &'g str) -> bool {
+        match self {
+            LocalizedText::Plain(plain) if lang.code() == DEFAULT_LANG => {
+                if plain == text {
+                    return false;
+                }
+                *plain = Cow::Borrowed(text);
+            }
+            LocalizedText::Plain(plain) => {
+                let default = std::mem::take(plain);
+                *self = LocalizedText::Localized(vec![
+                    (Lang::default(), default),
+                    (lang.clone(), Cow::Borrowed(text)),
+                ]);
+            }
+            LocalizedText::Localized(texts) => match texts.iter_mut().find(|(l, _)| l == lang) {
+                Some((_, old)) if old == text => return false,
+                Some((_, old)) => *old = Cow::Borrowed(text),
+                None => texts.push((lang.clone(), Cow::Borrowed(text))),
+            },
+        }
+        true
+    }
+
+    /// Get the text in the given language.
+    ///
+    /// If there's no translation for that language, this will fall back to
+    /// the text of its primary language (such as `"zh"` for `"zh-cn"`),
+    /// then the text of [`DEFAULT_LANG`], then the first available text.
+    pub fn get(&self, lang: &Lang) -> &str {
+        let texts = match self {
+            LocalizedText::Plain(text) => return text,
+            LocalizedText::Localized(texts) => texts,
+        };
+        let find = |pred: &dyn Fn(&Lang) -> bool| {
+            texts
+                .iter()
+                .find(|(l, _)| pred(l))
+                .map(|(_, text)| text.as_ref())
+        };
+
+        find(&|l| l == lang)
+            .or_else(|| find(&|l| l.code() == lang.primary()))
+            .or_else(|| find(&|l| l.primary() == lang.primary()))
+            .or_else(|| find(&|l| l.code() == DEFAULT_LANG))
+            .or_else(|| texts.first().map(|(_, text)| text.as_ref()))
+            .unwrap_or_default()
+    }
+}
+
+impl<'g> From<&'g str> for LocalizedText<'g> {
+    fn from(value: &'g str) -> Self {
+        LocalizedText::Plain(Cow::Borrowed(value))
+    }
+}
+
+// Displays the text in default language.
+impl Display for LocalizedText<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.get(&Lang::default()))
+    }
+}
+
+impl Serialize for LocalizedText<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        match self {
+            LocalizedText::Plain(text) => serializer.serialize_str(text),
+            LocalizedText::Localized(texts) => {
+                let mut map = serializer.serialize_map(Some(texts.len()))?;
+                for (lang, text) in texts {
+                    map.serialize_entry(lang.code(), text)?;
+                }
+                map.end()
+            }
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, PartialEq, Default, Clone, Copy)]
+impl<'de: 'g, 'g> Deserialize<'de> for LocalizedText<'g> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(LocalizedTextVisitor(PhantomData))
+    }
+}
+
+struct LocalizedTextVisitor<'g>(PhantomData<&'g ()>);
+
+impl<'de: 'g, 'g> de::Visitor<'de> for LocalizedTextVisitor<'g> {
+    type Value = LocalizedText<'g>;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("a string, or a map of language codes and strings")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(LocalizedText::Plain(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(LocalizedText::Plain(Cow::Owned(v.to_string())))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut texts = vec![];
+        while let Some((lang, text)) = map.next_entry::<String, LocalizedText<'g>>()? {
+            let LocalizedText::Plain(text) = text else {
+                return Err(de::Error::custom(format!(
+                    "text of language '{lang}' should be a string"
+                )));
+            };
+            texts.push((Lang::new(&lang), text));
+        }
+        Ok(LocalizedText::Localized(texts))
+    }
+}
+
+// Guideline IDs are written in upper case in the catalogue by convention.
+fn serialize_id_uppercase<S>(id: &GuidelineID, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&id.to_catalogue_string())
+}
+
+/// Code examples of a [`Guideline`].
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct Examples<'g> {
+    /// Code that violates the guideline.
+    #[serde(default, borrow, skip_serializing_if = "Vec::is_empty")]
+    pub negative: Vec<Cow<'g, str>>,
+    /// Code that complies with the guideline.
+    #[serde(default, borrow, skip_serializing_if = "Vec::is_empty")]
+    pub positive: Vec<Cow<'g, str>>,
+}
+
+impl Examples<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.positive.is_empty() && self.negative.is_empty()
+    }
+}
+
+/// How serious violating a guideline is.
+///
+/// Levels are declared from the least severe one, so that a more severe level is greater.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Default, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum CheckLevel {
-    Fatal,
-    Severe,
+    Info,
+    Prompt,
     #[serde(alias = "normal")]
     #[default]
     Warn,
-    Prompt,
-    Info,
+    Severe,
+    Fatal,
+}
+
+impl CheckLevel {
+    // TODO: put this method in a derive macro
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Info,
+            Self::Prompt,
+            Self::Warn,
+            Self::Severe,
+            Self::Fatal,
+        ]
+    }
+}
+
+impl Display for CheckLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use CheckLevel::*;
+        let str = match self {
+            Fatal => "fatal",
+            Severe => "severe",
+            Warn => "warn",
+            Prompt => "prompt",
+            Info => "info",
+        };
+        f.write_str(str)
+    }
+}
+
+// TODO: put this method in a derive macro
+impl FromStr for CheckLevel {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use CheckLevel::*;
+        match s.to_lowercase().as_str() {
+            "info" => Ok(Info),
+            "prompt" => Ok(Prompt),
+            "warn" | "normal" => Ok(Warn),
+            "severe" => Ok(Severe),
+            "fatal" => Ok(Fatal),
+            _ => Err(Error::ParseUnsupportedEnumVariant(
+                "check level",
+                s.to_string(),
+                CheckLevel::all().iter().map(ToString::to_string).collect(),
+            )),
+        }
+    }
+}
+
+/// Describes how well a [`Guideline`] could be enforced by the supported tools.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Enforcement {
+    /// Violations of the guideline could be fully detected by tools.
+    Automatic,
+    /// Tools could only detect some of the violations,
+    /// the rest of them still needs to be reviewed manually.
+    Partial,
+    /// The guideline could only be enforced by code reviews.
+    Manual,
 }
 
-#[derive(Debug, Deserialize)]
+impl Display for Enforcement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Enforcement::*;
+        let str = match self {
+            Automatic => "automatic",
+            Partial => "partial",
+            Manual => "manual",
+        };
+        f.write_str(str)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CheckTool<'g> {
     /// Name of the tool, must be one of the [`SupportedTool`] variants.
     pub name: SupportedTool,
@@ -76,7 +457,7 @@ impl<'de> Deserialize<'de> for SupportedTool {
 }
 
 /// An unique identifier for a guideline item.
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct GuidelineID {
     /// A character representing the type of this guideline,
     /// such as 'P', 'G'.
@@ -89,6 +470,14 @@ pub struct GuidelineID {
     pub idx: String,
 }
 
+impl GuidelineID {
+    /// The ID in the form written in the catalogue, which is in upper case,
+    /// such as "G.TYP.INT.01", while `Display` writes it in lower case.
+    pub fn to_catalogue_string(&self) -> String {
+        self.to_string().to_ascii_uppercase()
+    }
+}
+
 impl<T: AsRef<str>> PartialEq<T> for GuidelineID {
     fn eq(&self, other: &T) -> bool {
         if let Ok(id) = other.as_ref().parse::<Self>() {
@@ -123,9 +512,9 @@ impl FromStr for GuidelineID {
     }
 }
 
-impl ToString for GuidelineID {
-    fn to_string(&self) -> String {
-        format!("{}.{}.{}", self.ty, self.group, self.idx)
+impl Display for GuidelineID {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.ty, self.group, self.idx)
     }
 }
 
@@ -154,6 +543,8 @@ impl Serialize for GuidelineID {
 pub struct GuidelineSummary {
     pub id: GuidelineID,
     pub name: String,
+    #[serde(default)]
+    pub level: CheckLevel,
 }
 
 impl Hash for GuidelineSummary {
@@ -1,11 +1,20 @@
+mod book;
 mod guideline;
+mod output_diff;
 mod output_file;
 mod rules_config;
 
 use serde::{de, Deserialize, Serialize};
 
+pub use book::*;
 pub use guideline::*;
-pub use output_file::{CheckInfo, Output};
+pub use output_diff::{GuidelineDelta, OutputDiff};
+pub(crate) use output_file::normalize_lint_name;
+pub use output_file::{
+    fingerprints, CheckInfo, ChildKind, ChildMessage, CodeSpan, GuidelineCounts, Highlight, Output,
+    OutputSection, RunMetadata, SourceContext, SourceLine, Summary, ToolRun, ToolSummary,
+    OUTPUT_SCHEMA, SCHEMA_VERSION,
+};
 pub use rules_config::*;
 
 use crate::Result;
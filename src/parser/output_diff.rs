@@ -0,0 +1,116 @@
+//! This module compares the outputs of two runs, such as of two releases.
+
+use std::collections::{BTreeMap, HashSet};
+
+use super::guideline::{CheckLevel, GuidelineID};
+use super::output_file::{fingerprints, CheckInfo, Output};
+
+/// Results of two outputs, classified by whether they are found by the old run,
+/// the new run, or both.
+///
+/// Results are matched by their [`fingerprints`] instead of their positions,
+/// so that a result is not taken as new only because the code above it changed.
+#[derive(Debug, Default)]
+pub struct OutputDiff<'o> {
+    /// Results only found by the new run.
+    pub new: Vec<&'o CheckInfo>,
+    /// Results only found by the old run.
+    pub fixed: Vec<&'o CheckInfo>,
+    /// Results found by both runs, borrowed from the new output.
+    pub unchanged: Vec<&'o CheckInfo>,
+}
+
+impl<'o> OutputDiff<'o> {
+    pub fn new(old: &'o Output, new: &'o Output) -> Self {
+        let old_fps = fingerprints(&old.check_info);
+        let new_fps = fingerprints(&new.check_info);
+        let old_set = old_fps.iter().collect::<HashSet<_>>();
+        let new_set = new_fps.iter().collect::<HashSet<_>>();
+
+        let mut diff = OutputDiff::default();
+        for (info, fp) in new.check_info.iter().zip(&new_fps) {
+            if old_set.contains(fp) {
+                diff.unchanged.push(info);
+            } else {
+                diff.new.push(info);
+            }
+        }
+        diff.fixed = old
+            .check_info
+            .iter()
+            .zip(&old_fps)
+            .filter(|(_, fp)| !new_set.contains(fp))
+            .map(|(info, _)| info)
+            .collect();
+        diff
+    }
+
+    /// Changes of each guideline violated by either of the runs, sorted by their IDs.
+    pub fn guideline_deltas(&self) -> Vec<GuidelineDelta> {
+        let mut deltas: BTreeMap<String, GuidelineDelta> = BTreeMap::new();
+        let groups = [
+            (&self.new, DiffStatus::New),
+            (&self.fixed, DiffStatus::Fixed),
+            (&self.unchanged, DiffStatus::Unchanged),
+        ];
+        for (infos, status) in groups {
+            for gl in infos.iter().flat_map(|info| &info.guideline_list) {
+                let delta = deltas
+                    .entry(gl.id.to_string())
+                    .or_insert_with(|| GuidelineDelta {
+                        id: gl.id.clone(),
+                        name: gl.name.clone(),
+                        level: gl.level,
+                        new: 0,
+                        fixed: 0,
+                        unchanged: 0,
+                    });
+                match status {
+                    DiffStatus::New => delta.new += 1,
+                    DiffStatus::Fixed => delta.fixed += 1,
+                    DiffStatus::Unchanged => delta.unchanged += 1,
+                }
+            }
+        }
+        deltas.into_values().collect()
+    }
+
+    /// New results of the given level or a more severe one.
+    pub fn new_at_least(&self, level: CheckLevel) -> Vec<&'o CheckInfo> {
+        self.new
+            .iter()
+            .copied()
+            .filter(|info| info.level() >= level)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffStatus {
+    New,
+    Fixed,
+    Unchanged,
+}
+
+/// How the violations of a guideline changed between two runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuidelineDelta {
+    pub id: GuidelineID,
+    pub name: String,
+    pub level: CheckLevel,
+    pub new: usize,
+    pub fixed: usize,
+    pub unchanged: usize,
+}
+
+impl GuidelineDelta {
+    /// Number of violations found by the old run.
+    pub fn old_count(&self) -> usize {
+        self.fixed + self.unchanged
+    }
+
+    /// Number of violations found by the new run.
+    pub fn new_count(&self) -> usize {
+        self.new + self.unchanged
+    }
+}
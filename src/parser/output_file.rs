@@ -1,16 +1,59 @@
 //! This module defining data that are related to the output of running the checks.
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
 use std::path::PathBuf;
 
-use super::guideline::GuidelineSummary;
-use super::Serialize;
+use super::guideline::{CheckLevel, CheckTool, GuidelineID, GuidelineSummary};
+use super::{Deserialize, JsonStruct, Serialize};
+use crate::tools::doc_coverage::DocCoverage;
+use crate::tools::metrics::Metrics;
+use crate::tools::panics::PanicReport;
+use crate::tools::unsafe_audit::UnsafeInventory;
 use crate::tools::SupportedTool;
+use crate::utils::{self, fnv1a};
 use crate::Result;
 
+/// Version of the JSON format of [`Output`], which is described by the JSON Schema
+/// printed by `eunomia schema`.
+///
+/// It should be increased whenever a field is renamed, removed or changes its meaning,
+/// adding a field is fine as long as older outputs without it could still be read.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The JSON Schema of [`Output`] of the current [`SCHEMA_VERSION`].
+pub const OUTPUT_SCHEMA: &str = include_str!("../output.schema.json");
+
 /// Main output format of this program, containing a list of checked infomation.
-#[derive(Debug, Serialize)]
+///
+/// It could be deserialized from its JSON again, to be merged with others or rendered
+/// in another format.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Output {
+    /// The [`SCHEMA_VERSION`] of this output, which is `0` for outputs written
+    /// before it was introduced.
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Information about the run, only available when the output comes from a check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<RunMetadata>,
+    /// Statistics of the results, which are computed by [`Output::summarize`] when
+    /// the output is written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Summary>,
     pub check_info: Vec<CheckInfo>,
+    /// Counts of unsafe code, only available when the unsafe audit was run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unsafe_inventory: Option<UnsafeInventory>,
+    /// Raw metrics of functions and modules, only available when metrics were collected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<Metrics>,
+    /// Documentation coverage of public items, only available when it was checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_coverage: Option<DocCoverage>,
+    /// Panic sites of public functions, only available when they were checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panics: Option<PanicReport>,
 }
 
 impl Output {
@@ -21,14 +64,356 @@ impl Output {
     pub fn to_json_string_pretty(&self) -> Result<String> {
         Ok(serde_json::to_string_pretty(self)?)
     }
+
+    /// Merge the output of another run, such as of another crate or another shard.
+    ///
+    /// Results found by both runs are kept once, with the guidelines of both,
+    /// they are identified by their fingerprints and positions.
+    pub fn merge(&mut self, other: Output) {
+        for info in other.check_info {
+            let same = self.check_info.iter_mut().find(|existing| {
+                existing.begin_line == info.begin_line
+                    && existing.column == info.column
+                    && existing.fingerprint() == info.fingerprint()
+            });
+            match same {
+                Some(existing) => {
+                    for gl in info.guideline_list {
+                        if existing.guideline_list.iter().all(|g| g.id != gl.id) {
+                            existing.guideline_list.push(gl);
+                        }
+                    }
+                }
+                None => self.check_info.push(info),
+            }
+        }
+        merge_section(
+            &mut self.unsafe_inventory,
+            other.unsafe_inventory,
+            UnsafeInventory::merge,
+        );
+        merge_section(&mut self.metrics, other.metrics, Metrics::merge);
+        merge_section(
+            &mut self.doc_coverage,
+            other.doc_coverage,
+            DocCoverage::merge,
+        );
+        merge_section(&mut self.panics, other.panics, PanicReport::merge);
+        merge_section(&mut self.metadata, other.metadata, RunMetadata::merge);
+        // the summary of either output no longer holds for the merged one
+        self.summary = None;
+    }
+
+    /// Compute the [`Summary`] of the results and the metadata, unless the output
+    /// only holds its summary, which is kept as it is.
+    pub fn summarize(&mut self) {
+        if !self.is_summary_only() {
+            self.summary = Some(Summary::new(self));
+        }
+    }
+
+    /// Whether the results of this output were left out, keeping only their summary,
+    /// such as the ones written with `--summary-only`.
+    pub fn is_summary_only(&self) -> bool {
+        self.summary
+            .as_ref()
+            .is_some_and(|summary| summary.total != self.check_info.len())
+    }
+
+    /// Fill in a section, it's merged with the existing one if there is.
+    pub fn add_section(&mut self, section: OutputSection) {
+        match section {
+            OutputSection::UnsafeInventory(inventory) => merge_section(
+                &mut self.unsafe_inventory,
+                Some(inventory),
+                UnsafeInventory::merge,
+            ),
+            OutputSection::Metrics(metrics) => {
+                merge_section(&mut self.metrics, Some(metrics), Metrics::merge)
+            }
+            OutputSection::DocCoverage(coverage) => {
+                merge_section(&mut self.doc_coverage, Some(coverage), DocCoverage::merge)
+            }
+            OutputSection::Panics(report) => {
+                merge_section(&mut self.panics, Some(report), PanicReport::merge)
+            }
+        }
+    }
+
+    /// Read the source files of the results, and attach the lines of their primary spans,
+    /// along with at most `context_lines` lines before and after them.
+    ///
+    /// Relative paths of the files are resolved against the checked paths in the metadata,
+    /// the current directory, and their parents. Results whose file couldn't be read are
+    /// left unchanged.
+    pub fn capture_context(&mut self, context_lines: usize) {
+        let mut base_dirs = self
+            .metadata
+            .iter()
+            .flat_map(|metadata| &metadata.paths)
+            .map(|path| match path.parent() {
+                Some(parent) if path.is_file() => parent.to_path_buf(),
+                _ => path.clone(),
+            })
+            .collect::<Vec<_>>();
+        base_dirs.push(PathBuf::from("."));
+
+        let mut files: HashMap<PathBuf, Option<String>> = HashMap::new();
+        for info in &mut self.check_info {
+            let (Some(path), Some(span)) = (&info.file_path, info.primary_span()) else {
+                continue;
+            };
+            let content = files.entry(path.clone()).or_insert_with(|| {
+                base_dirs
+                    .iter()
+                    .find_map(|dir| utils::resolve_in_ancestors(dir, path))
+                    .and_then(|file| std::fs::read_to_string(file).ok())
+            });
+            if let Some(context) = content
+                .as_deref()
+                .and_then(|content| SourceContext::new(content, &span, context_lines))
+            {
+                info.context = Some(context);
+            }
+        }
+    }
+}
+
+impl JsonStruct<'_> for Output {}
+
+/// A section of [`Output`] besides the results, which is filled in by a built-in checker.
+#[derive(Debug)]
+pub enum OutputSection {
+    UnsafeInventory(UnsafeInventory),
+    Metrics(Metrics),
+    DocCoverage(DocCoverage),
+    Panics(PanicReport),
+}
+
+fn merge_section<T>(section: &mut Option<T>, other: Option<T>, merge: fn(&mut T, T)) {
+    match (section.as_mut(), other) {
+        (Some(this), Some(other)) => merge(this, other),
+        (None, other) => *section = other,
+        (Some(_), None) => (),
+    }
 }
 
 impl From<Vec<CheckInfo>> for Output {
     fn from(value: Vec<CheckInfo>) -> Self {
-        Self { check_info: value }
+        Self {
+            schema_version: SCHEMA_VERSION,
+            metadata: None,
+            summary: None,
+            check_info: value,
+            unsafe_inventory: None,
+            metrics: None,
+            doc_coverage: None,
+            panics: None,
+        }
+    }
+}
+
+/// Information about the run producing an [`Output`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub eunomia_version: String,
+    /// When the checks started, as a RFC 3339 timestamp in UTC.
+    pub started_at: String,
+    /// When the checks finished, as a RFC 3339 timestamp in UTC.
+    pub finished_at: String,
+    /// Paths of the checked source code.
+    pub paths: Vec<PathBuf>,
+    pub rules_file: Option<PathBuf>,
+    /// The FNV-1a hash of the content of the rules file, so that outputs checked with
+    /// different rules could be told apart.
+    pub rules_hash: Option<String>,
+    /// Tools that were run.
+    pub tools: Vec<ToolRun>,
+    /// Tools that the checked guidelines need, but were not run,
+    /// such as the ones that are not supported yet.
+    pub skipped_tools: Vec<SupportedTool>,
+    /// Guidelines listed in the rules.
+    #[serde(default)]
+    pub requested_guidelines: Vec<GuidelineID>,
+    /// Requested guidelines that could be checked by any of the tools that were run.
+    #[serde(default)]
+    pub enforceable_guidelines: Vec<GuidelineID>,
+}
+
+impl RunMetadata {
+    /// Merge the metadata of another run, the merged run spans both of them,
+    /// and the rules are only kept if both runs used the same ones.
+    pub fn merge(&mut self, other: RunMetadata) {
+        if other.started_at < self.started_at {
+            self.started_at = other.started_at;
+        }
+        if other.finished_at > self.finished_at {
+            self.finished_at = other.finished_at;
+        }
+        for path in other.paths {
+            if !self.paths.contains(&path) {
+                self.paths.push(path);
+            }
+        }
+        if self.rules_hash != other.rules_hash {
+            self.rules_file = None;
+            self.rules_hash = None;
+        }
+        for tool in other.tools {
+            if self.tools.iter().all(|t| t.name != tool.name) {
+                self.tools.push(tool);
+            }
+        }
+        for tool in other.skipped_tools {
+            if !self.skipped_tools.contains(&tool) {
+                self.skipped_tools.push(tool);
+            }
+        }
+        // tools skipped by one of the runs could be run by the other
+        let tools = &self.tools;
+        self.skipped_tools
+            .retain(|skipped| tools.iter().all(|t| t.name != *skipped));
+        merge_guidelines(&mut self.requested_guidelines, other.requested_guidelines);
+        merge_guidelines(
+            &mut self.enforceable_guidelines,
+            other.enforceable_guidelines,
+        );
+    }
+}
+
+fn merge_guidelines(ids: &mut Vec<GuidelineID>, other: Vec<GuidelineID>) {
+    for id in other {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids.sort_by_key(ToString::to_string);
+}
+
+/// A tool that was run to check the code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolRun {
+    pub name: SupportedTool,
+    /// Version of the program behind the tool, such as `rustc 1.70.0 (90c541806 2023-05-31)`,
+    /// or `None` if it's a checker built into eunomia.
+    pub version: Option<String>,
+    /// How long the tool took, in milliseconds.
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Exit code of the program behind the tool, or `None` if it's a checker
+    /// built into eunomia.
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+}
+
+/// Statistics of the results of an [`Output`], such as how many results each guideline
+/// and each file got, and how compliant the checked code is.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    /// Number of results.
+    pub total: usize,
+    /// Number of results of each level.
+    pub by_level: BTreeMap<CheckLevel, usize>,
+    /// Number of results violating each guideline, by lowercase ids,
+    /// a result violating several guidelines is counted by each of them.
+    pub by_guideline: BTreeMap<String, usize>,
+    /// Number of results in each file, results without a file are not counted.
+    pub by_file: BTreeMap<String, usize>,
+    /// Results of each tool, along with how it was run, in the order of running.
+    pub tools: Vec<ToolSummary>,
+    /// Numbers of guidelines, only available when the output has its metadata.
+    pub guidelines: Option<GuidelineCounts>,
+    /// Ratio of enforceable guidelines without any result, from `0` to `1`,
+    /// or `None` if no guideline was enforceable.
+    pub compliance: Option<f64>,
+}
+
+impl Summary {
+    pub fn new(output: &Output) -> Self {
+        let mut summary = Summary {
+            total: output.check_info.len(),
+            ..Default::default()
+        };
+        let metadata = output.metadata.as_ref();
+        summary.tools = metadata
+            .iter()
+            .flat_map(|metadata| &metadata.tools)
+            .map(|run| ToolSummary {
+                name: run.name,
+                findings: 0,
+                duration_ms: run.duration_ms,
+                exit_code: run.exit_code,
+            })
+            .collect();
+
+        let mut violated = HashSet::new();
+        for info in &output.check_info {
+            *summary.by_level.entry(info.level()).or_default() += 1;
+            for gl in &info.guideline_list {
+                *summary.by_guideline.entry(gl.id.to_string()).or_default() += 1;
+                violated.insert(&gl.id);
+            }
+            if let Some(path) = &info.file_path {
+                let path = path.to_string_lossy().replace('\\', "/");
+                let path = path.trim_start_matches("./").to_string();
+                *summary.by_file.entry(path).or_default() += 1;
+            }
+            match summary.tools.iter_mut().find(|t| t.name == info.tool) {
+                Some(tool) => tool.findings += 1,
+                None => summary.tools.push(ToolSummary {
+                    name: info.tool,
+                    findings: 1,
+                    duration_ms: None,
+                    exit_code: None,
+                }),
+            }
+        }
+
+        if let Some(metadata) = metadata {
+            let enforceable = &metadata.enforceable_guidelines;
+            let violated_enforceable = enforceable
+                .iter()
+                .filter(|id| violated.contains(id))
+                .count();
+            summary.guidelines = Some(GuidelineCounts {
+                requested: metadata.requested_guidelines.len(),
+                enforceable: enforceable.len(),
+                violated: violated.len(),
+            });
+            if !enforceable.is_empty() {
+                summary.compliance = Some(
+                    (enforceable.len() - violated_enforceable) as f64 / enforceable.len() as f64,
+                );
+            }
+        }
+        summary
     }
 }
 
+/// Results of a tool, along with how it was run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolSummary {
+    pub name: SupportedTool,
+    /// Number of results found by this tool.
+    pub findings: usize,
+    /// How long the tool took, in milliseconds, `None` if it's unknown.
+    pub duration_ms: Option<u64>,
+    /// Exit code of the program behind the tool, `None` if it's a checker built
+    /// into eunomia, or unknown.
+    pub exit_code: Option<i32>,
+}
+
+/// Numbers of guidelines of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuidelineCounts {
+    /// Guidelines listed in the rules.
+    pub requested: usize,
+    /// Requested guidelines that could be checked by any of the tools that were run.
+    pub enforceable: usize,
+    /// Guidelines violated by any result.
+    pub violated: usize,
+}
+
 /// Infomation about the checking result.
 ///
 /// This struct basically contains detailed information such as:
@@ -45,7 +430,7 @@ impl From<Vec<CheckInfo>> for Output {
 // FIXME: The default implementation is convenient when building the struct,
 // but may cause an empty struct, or struct with missing required fields being
 // serialized, switch to builder pattern when derive macro is available in the future.
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct CheckInfo {
     pub file_path: Option<PathBuf>,
     pub defect_name: String,
@@ -59,11 +444,49 @@ pub struct CheckInfo {
     pub code_string: String,
     pub help_info: String,
     pub additional_help_info: String,
+    /// The primary span of this result, `begin_line`, `end_line` and `column` are
+    /// kept for compatibility, and they are the same as the ones of this span.
+    #[serde(default)]
+    pub span: Option<CodeSpan>,
+    /// Other labelled spans related to this result, such as where a conflicting
+    /// access happened earlier.
+    #[serde(default)]
+    pub secondary_spans: Vec<CodeSpan>,
+    /// Notes and help messages attached to this result, which are also joined
+    /// into `additional_help_info` for compatibility.
+    #[serde(default)]
+    pub children: Vec<ChildMessage>,
+    /// Lines of the source file around the primary span, only available when
+    /// they were captured by [`Output::capture_context`].
+    #[serde(default)]
+    pub context: Option<SourceContext>,
     pub guideline_list: Vec<GuidelineSummary>,
 }
 
 impl CheckInfo {
-    pub fn defect_type(&self) -> DefectType {
+    /// Set the primary span, along with the lines and the column of this result.
+    pub fn with_span(mut self, span: CodeSpan) -> Self {
+        self.begin_line = Some(span.begin_line);
+        self.end_line = Some(span.end_line);
+        self.column = span.begin_column;
+        self.span = Some(span);
+        self
+    }
+
+    /// The primary span of this result, or the one made of its lines and column
+    /// if the tool didn't tell it.
+    pub fn primary_span(&self) -> Option<CodeSpan> {
+        if let Some(span) = &self.span {
+            return Some(span.clone());
+        }
+        let begin_line = self.begin_line?;
+        Some(CodeSpan {
+            begin_column: self.column,
+            ..CodeSpan::lines(begin_line, self.end_line.unwrap_or(begin_line))
+        })
+    }
+
+    pub fn defect_type(&self) -> DefectType<'_> {
         match self.tool {
             SupportedTool::Clippy => DefectType::ToolLint {
                 is_rustc_lint: false,
@@ -73,11 +496,280 @@ impl CheckInfo {
                 is_rustc_lint: true,
                 name: &self.defect_name,
             },
-            SupportedTool::Miri | SupportedTool::Sanitizer => {
-                DefectType::KeyWord(&self.defect_name)
+            SupportedTool::Miri
+            | SupportedTool::Sanitizer
+            | SupportedTool::Pattern
+            | SupportedTool::UnsafeAudit
+            | SupportedTool::Metrics
+            | SupportedTool::DocCoverage
+            | SupportedTool::Manifest
+            | SupportedTool::Advisory
+            | SupportedTool::License
+            | SupportedTool::Rustfmt
+            | SupportedTool::Panics => DefectType::KeyWord(&self.defect_name),
+        }
+    }
+
+    /// Check whether this result is the defect that the given [`CheckTool`] looks for,
+    /// which means it violates the guideline that tool belongs to.
+    ///
+    /// Lint names are compared regardless of the `clippy::` prefix and the usage of
+    /// `-` or `_`, because the tools report them inconsistently.
+    pub fn is_detected_by(&self, tool: &CheckTool) -> bool {
+        match self.defect_type() {
+            DefectType::ToolLint { name, .. } => {
+                matches!(tool.name, SupportedTool::Clippy | SupportedTool::Rustc)
+                    && !name.is_empty()
+                    && normalize_lint_name(name) == normalize_lint_name(tool.ident)
+            }
+            DefectType::KeyWord(keyword) => {
+                tool.name == self.tool
+                    && !tool.ident.is_empty()
+                    && (keyword.contains(tool.ident) || self.help_info.contains(tool.ident))
             }
         }
     }
+
+    /// The most severe level of the guidelines this result violates,
+    /// or the default level if it doesn't violate any of them.
+    pub fn level(&self) -> CheckLevel {
+        self.guideline_list
+            .iter()
+            .map(|gl| gl.level)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// A fingerprint identifying this result across runs.
+    ///
+    /// It's computed from the tool, the defect, the file and the trimmed code, but not
+    /// the line numbers, so that it stays the same when unrelated code moves this result.
+    /// Results with the same fingerprint could be told apart by [`fingerprints`].
+    pub fn fingerprint(&self) -> String {
+        let path = self
+            .file_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        let path = path.trim_start_matches("./");
+        let content = [
+            &self.tool.to_string(),
+            &self.defect_name,
+            path,
+            self.code_string.trim(),
+        ]
+        .join("\0");
+        format!("{:016x}", fnv1a(content.as_bytes()))
+    }
+}
+
+/// A range of source code, with 1-based lines and columns.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeSpan {
+    /// The file of this span, `None` if it's the file of the result it belongs to.
+    pub file_path: Option<PathBuf>,
+    pub begin_line: usize,
+    pub begin_column: Option<usize>,
+    pub end_line: usize,
+    /// The column after the last character of this span, like compilers report.
+    pub end_column: Option<usize>,
+    pub label: Option<String>,
+}
+
+impl CodeSpan {
+    pub fn new(begin: (usize, usize), end: (usize, usize)) -> Self {
+        CodeSpan {
+            begin_line: begin.0,
+            begin_column: Some(begin.1),
+            end_line: end.0,
+            end_column: Some(end.1),
+            ..Default::default()
+        }
+    }
+
+    /// A span covering whole lines, whose columns are unknown.
+    pub fn lines(begin_line: usize, end_line: usize) -> Self {
+        CodeSpan {
+            begin_line,
+            end_line,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_file(mut self, file_path: impl Into<PathBuf>) -> Self {
+        self.file_path = Some(file_path.into());
+        self
+    }
+}
+
+/// A note or help message attached to a result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChildMessage {
+    pub kind: ChildKind,
+    pub message: String,
+}
+
+impl ChildMessage {
+    pub fn note(message: impl Into<String>) -> Self {
+        ChildMessage {
+            kind: ChildKind::Note,
+            message: message.into(),
+        }
+    }
+
+    pub fn help(message: impl Into<String>) -> Self {
+        ChildMessage {
+            kind: ChildKind::Help,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ChildMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChildKind {
+    Note,
+    Help,
+}
+
+impl Display for ChildKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            ChildKind::Note => "note",
+            ChildKind::Help => "help",
+        };
+        f.write_str(str)
+    }
+}
+
+/// Lines of source code around a result, which are read from its file,
+/// so that they could be shown the same way no matter which tool found the result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceContext {
+    pub lines: Vec<SourceLine>,
+}
+
+/// A line of source code, with the columns covered by the span of the result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLine {
+    pub line: usize,
+    pub text: String,
+    /// The highlighted columns of this line, `None` if it's only shown as context.
+    pub highlight: Option<Highlight>,
+}
+
+/// A range of 1-based columns in a line, counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Highlight {
+    pub begin_column: usize,
+    /// The column after the last highlighted character.
+    pub end_column: usize,
+}
+
+impl SourceContext {
+    /// Take the lines covered by `span` from the content of its file, along with at most
+    /// `context_lines` lines before and after them.
+    ///
+    /// Lines in the middle of a multi-line span are highlighted from their indentation,
+    /// so are the lines of a span without columns. Returns `None` if the span is not
+    /// inside of the content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use eunomia::parser::{CodeSpan, Highlight, SourceContext};
+    /// let content = "fn f() {\n    x.unwrap();\n}\n";
+    /// let context = SourceContext::new(content, &CodeSpan::new((2, 7), (2, 15)), 1).unwrap();
+    /// assert_eq!(context.lines.len(), 3);
+    /// assert_eq!(context.lines[0].highlight, None);
+    /// assert_eq!(
+    ///     context.lines[1].highlight,
+    ///     Some(Highlight { begin_column: 7, end_column: 15 })
+    /// );
+    /// ```
+    pub fn new(content: &str, span: &CodeSpan, context_lines: usize) -> Option<Self> {
+        let lines = content.lines().collect::<Vec<_>>();
+        if span.begin_line == 0 || span.begin_line > lines.len() {
+            return None;
+        }
+        let end_line = span.end_line.clamp(span.begin_line, lines.len());
+        let first = span.begin_line.saturating_sub(context_lines).max(1);
+        let last = (end_line + context_lines).min(lines.len());
+
+        let lines = (first..=last)
+            .map(|line| {
+                let text = lines[line - 1];
+                let highlight = (span.begin_line..=end_line)
+                    .contains(&line)
+                    .then(|| {
+                        let after_last = text.chars().count() + 1;
+                        let indent = text.chars().take_while(|c| c.is_whitespace()).count() + 1;
+                        let begin = match span.begin_column {
+                            Some(column) if line == span.begin_line => column,
+                            _ => indent,
+                        };
+                        let end = match span.end_column {
+                            Some(column) if line == end_line => column,
+                            _ => after_last,
+                        };
+                        // columns are 1-based, but tools could report 0 for the first one
+                        let begin = begin.clamp(1, after_last);
+                        // an empty span still points at a character, if there's one
+                        let end = end.min(after_last).max((begin + 1).min(after_last));
+                        (begin < end).then_some(Highlight {
+                            begin_column: begin,
+                            end_column: end,
+                        })
+                    })
+                    .flatten();
+                SourceLine {
+                    line,
+                    text: text.to_string(),
+                    highlight,
+                }
+            })
+            .collect();
+        Some(SourceContext { lines })
+    }
+}
+
+/// Fingerprints of the results, which are unique among them.
+///
+/// Results with the same [`CheckInfo::fingerprint`], such as the same lint triggered
+/// by identical lines in a file, are suffixed with the order they appear.
+pub fn fingerprints(check_info: &[CheckInfo]) -> Vec<String> {
+    let mut seen = HashMap::new();
+    check_info
+        .iter()
+        .map(|info| {
+            let fingerprint = info.fingerprint();
+            let count = seen.entry(fingerprint.clone()).or_insert(0_usize);
+            *count += 1;
+            if *count == 1 {
+                fingerprint
+            } else {
+                format!("{fingerprint}-{count}")
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn normalize_lint_name(name: &str) -> String {
+    name.trim()
+        .trim_start_matches("clippy::")
+        .replace('-', "_")
+        .to_ascii_lowercase()
 }
 
 impl Serialize for SupportedTool {
@@ -92,10 +784,10 @@ impl Serialize for SupportedTool {
 /// Type of error/warning that got triggered, with defect name borrowed from [`CheckInfo`].
 ///
 /// - If the defection was detected by `clippy` or `rustc`,
-/// this will be a [`DefectType::ToolLint`] type.
+///   this will be a [`DefectType::ToolLint`] type.
 /// - But if it was detected by some tools that doesn't have clear identifications
-/// for its error/warning, we can then use keyword to query the result,
-/// therefore this will be a [`DefectType::KeyWord`] type.
+///   for its error/warning, we can then use keyword to query the result,
+///   therefore this will be a [`DefectType::KeyWord`] type.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DefectType<'c> {
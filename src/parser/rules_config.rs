@@ -1,13 +1,17 @@
 use super::{Deserialize, JsonStruct};
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use super::guideline::GuidelineID;
+use crate::tools::panics::PANIC_LINTS;
 
 /// The user defined rules configuration.
 ///
 /// User can specify which file to check, what compilation options to pass,
 /// and what guidelines the checks will be referenced.
+///
+/// Each of the optional checker sections also enables its checker when given,
+/// even if no checked guideline uses that checker.
 #[derive(Debug, Deserialize)]
 pub struct RulesCfg<'ru> {
     pub file_path: &'ru Path,
@@ -15,6 +19,138 @@ pub struct RulesCfg<'ru> {
     pub supplement_compilation_options: Option<&'ru str>,
     #[serde(default)]
     pub coding_guidelines: HashSet<GuidelineID>,
+    /// Thresholds of the metrics checker for the metrics of the source code.
+    #[serde(default)]
+    pub metrics: Option<MetricsCfg>,
+    /// Policies of the manifest checker for `Cargo.toml` and `Cargo.lock`.
+    #[serde(default)]
+    pub manifest: Option<ManifestCfg>,
+    /// The local advisory database that dependencies in `Cargo.lock` are checked against.
+    #[serde(default)]
+    pub advisory: Option<AdvisoryCfg>,
+    /// Licenses that dependencies may use, which are found with `cargo metadata`.
+    #[serde(default)]
+    pub license: Option<LicenseCfg>,
+    /// Options of rustfmt for checking the formatting of the source code.
+    #[serde(default)]
+    pub rustfmt: Option<RustfmtCfg>,
+    /// Options of the panic checker for collecting panic sites.
+    #[serde(default)]
+    pub panics: Option<PanicsCfg>,
 }
 
 impl<'ru> JsonStruct<'ru> for RulesCfg<'ru> {}
+
+/// Thresholds of the complexity and size metrics of functions,
+/// functions exceeding any of them are reported.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct MetricsCfg {
+    /// The maximum number of lines of a function, including its signature.
+    pub max_fn_lines: usize,
+    /// The maximum depth of nested control flow, such as `if` in a `for` loop.
+    pub max_nesting_depth: usize,
+    pub max_params: usize,
+    pub max_cyclomatic_complexity: usize,
+    pub max_cognitive_complexity: usize,
+}
+
+impl Default for MetricsCfg {
+    fn default() -> Self {
+        MetricsCfg {
+            max_fn_lines: 100,
+            max_nesting_depth: 4,
+            max_params: 5,
+            max_cyclomatic_complexity: 10,
+            max_cognitive_complexity: 15,
+        }
+    }
+}
+
+/// Policies about `Cargo.toml` and the dependencies.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ManifestCfg {
+    /// Fields that the `[package]` table must have, `license-file` could take the place
+    /// of `license`.
+    pub required_fields: Vec<String>,
+    /// Crates that must not be depended on, directly or indirectly.
+    pub banned_crates: Vec<String>,
+    /// If not empty, direct dependencies must be one of these crates.
+    pub allowed_crates: Vec<String>,
+    /// Crates that are allowed to have multiple versions in `Cargo.lock`.
+    pub allowed_duplicates: Vec<String>,
+}
+
+impl Default for ManifestCfg {
+    fn default() -> Self {
+        ManifestCfg {
+            required_fields: ["edition", "rust-version", "license", "repository"]
+                .map(String::from)
+                .to_vec(),
+            banned_crates: vec![],
+            allowed_crates: vec![],
+            allowed_duplicates: vec![],
+        }
+    }
+}
+
+/// Where to find the advisories that dependencies are checked against,
+/// nothing will be fetched from the network.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AdvisoryCfg {
+    /// A local clone, or a snapshot, of the [RustSec advisory database](https://github.com/rustsec/advisory-db).
+    pub db_path: PathBuf,
+    /// A local clone of the [crates.io index](https://github.com/rust-lang/crates.io-index),
+    /// which is used to find yanked dependencies, if given.
+    #[serde(default)]
+    pub index_path: Option<PathBuf>,
+    /// IDs of advisories that should not be reported.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+/// Licenses that dependencies are allowed or denied to use, as SPDX identifiers,
+/// such as `MIT`, or with an exception, such as `Apache-2.0 WITH LLVM-exception`.
+///
+/// A license with an exception is allowed when the license itself is allowed,
+/// unless the whole `<license> WITH <exception>` is denied.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct LicenseCfg {
+    /// If not empty, licenses that are not in this list are unknown,
+    /// otherwise all licenses that are not denied are allowed.
+    pub allowed: Vec<String>,
+    pub denied: Vec<String>,
+}
+
+/// Options of rustfmt, see <https://rust-lang.github.io/rustfmt> for the available ones.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RustfmtCfg {
+    /// The edition to format the code with, `2021` if not given.
+    pub edition: Option<String>,
+    /// Configuration options, such as `{"max_width": 80, "group_imports": "StdExternalCrate"}`,
+    /// which take precedence over the `rustfmt.toml` of the checked crate.
+    pub config: BTreeMap<String, serde_json::Value>,
+}
+
+/// What are considered as panic sites by the panic checker.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct PanicsCfg {
+    /// Clippy lints that find panic sites, see [`PANIC_LINTS`] for the default ones.
+    pub lints: Vec<String>,
+    /// Whether `assert!`, `assert_eq!` and `assert_ne!` are panic sites,
+    /// which is not covered by any lint.
+    pub assertions: bool,
+}
+
+impl Default for PanicsCfg {
+    fn default() -> Self {
+        PanicsCfg {
+            lints: PANIC_LINTS.iter().map(|lint| lint.to_string()).collect(),
+            assertions: true,
+        }
+    }
+}
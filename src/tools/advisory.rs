@@ -0,0 +1,461 @@
+//! A checker matching the dependencies in `Cargo.lock` against a local copy of the
+//! [RustSec advisory database](https://github.com/rustsec/advisory-db), see [`AdvisoryCfg`].
+//!
+//! Advisories are read from `crates/<crate>/<ID>.md` files of the database, each of them
+//! starts with a TOML front matter like:
+//!
+//! ````markdown
+//! ```toml
+//! [advisory]
+//! id = "RUSTSEC-2020-0071"
+//! package = "time"
+//! informational = "unmaintained" # for advisories that are not vulnerabilities
+//!
+//! [versions]
+//! patched = [">= 0.2.23"]
+//! unaffected = ["= 0.1.43"]
+//! ```
+//!
+//! # Potential segfault in the time crate
+//! ````
+//!
+//! Older advisories written in `.toml` files, with a `title` in the `[advisory]` table,
+//! are also supported. Withdrawn advisories are ignored.
+//!
+//! Yanked dependencies are found with a local clone of the crates.io index, if given.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use super::manifest::TomlFile;
+use super::{BuiltinChecker, CheckContext, SupportedTool};
+use crate::parser::{AdvisoryCfg, CheckInfo, CheckTool, OutputSection, RulesCfg};
+use crate::{utils, Error, Result};
+
+/// Kinds of advisories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdvisoryKind {
+    Vulnerability,
+    Unmaintained,
+    Unsound,
+    /// Other informational advisories, such as `notice`.
+    Informational(String),
+}
+
+impl AdvisoryKind {
+    /// Name of the defect reported when a dependency is affected by an advisory of this kind.
+    pub fn defect_name(&self) -> &'static str {
+        match self {
+            AdvisoryKind::Vulnerability => "vulnerable-dependency",
+            AdvisoryKind::Unmaintained => "unmaintained-dependency",
+            AdvisoryKind::Unsound => "unsound-dependency",
+            AdvisoryKind::Informational(_) => "informational-advisory",
+        }
+    }
+}
+
+/// An advisory of the database.
+#[derive(Debug, Clone)]
+pub struct Advisory {
+    pub id: String,
+    /// Name of the affected crate.
+    pub package: String,
+    pub title: String,
+    pub kind: AdvisoryKind,
+    pub url: Option<String>,
+    pub patched: Vec<VersionReq>,
+    /// Versions that were never affected, such as the ones before the bug was introduced.
+    pub unaffected: Vec<VersionReq>,
+    /// Whether the advisory was withdrawn, such advisories are never reported.
+    pub withdrawn: bool,
+}
+
+impl Advisory {
+    /// Parse an advisory from the content of a `.md` or `.toml` file.
+    pub fn parse(path: &Path, content: &str) -> Result<Self> {
+        let err = |reason: &str| Error::ParseAdvisory(path.to_path_buf(), reason.to_string());
+
+        let (front_matter, markdown) = if path.extension().is_some_and(|e| e == "md") {
+            let rest = content
+                .trim_start()
+                .strip_prefix("```toml")
+                .ok_or_else(|| err("missing the TOML front matter"))?;
+            rest.split_once("\n```")
+                .ok_or_else(|| err("unclosed TOML front matter"))?
+        } else {
+            (content, "")
+        };
+        let file: AdvisoryFile = toml::from_str(front_matter).map_err(|e| err(e.message()))?;
+
+        // the title of markdown advisories is the first heading
+        let title = file
+            .advisory
+            .title
+            .or_else(|| {
+                markdown
+                    .lines()
+                    .find_map(|line| line.strip_prefix("# "))
+                    .map(|title| title.trim().to_string())
+            })
+            .unwrap_or_default();
+        let kind = match file.advisory.informational.as_deref() {
+            None => AdvisoryKind::Vulnerability,
+            Some("unmaintained") => AdvisoryKind::Unmaintained,
+            Some("unsound") => AdvisoryKind::Unsound,
+            Some(other) => AdvisoryKind::Informational(other.to_string()),
+        };
+        let parse_reqs = |reqs: Vec<String>| {
+            reqs.iter()
+                .map(|req| VersionReq::parse(req).map_err(|e| err(&format!("'{req}': {e}"))))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Advisory {
+            id: file.advisory.id,
+            package: file.advisory.package,
+            title,
+            kind,
+            url: file.advisory.url,
+            patched: parse_reqs(file.versions.patched)?,
+            unaffected: parse_reqs(file.versions.unaffected)?,
+            withdrawn: file.advisory.withdrawn.is_some(),
+        })
+    }
+
+    /// Whether a version of the crate is affected by this advisory.
+    ///
+    /// An advisory without patched or unaffected versions affects all versions.
+    pub fn affects(&self, version: &Version) -> bool {
+        !self
+            .patched
+            .iter()
+            .chain(&self.unaffected)
+            .any(|req| req.matches(version))
+    }
+}
+
+#[derive(Deserialize)]
+struct AdvisoryFile {
+    advisory: AdvisoryMeta,
+    #[serde(default)]
+    versions: AdvisoryVersions,
+}
+
+#[derive(Deserialize)]
+struct AdvisoryMeta {
+    id: String,
+    package: String,
+    title: Option<String>,
+    informational: Option<String>,
+    url: Option<String>,
+    /// The date when the advisory was withdrawn.
+    withdrawn: Option<toml::Value>,
+}
+
+#[derive(Default, Deserialize)]
+struct AdvisoryVersions {
+    #[serde(default)]
+    patched: Vec<String>,
+    #[serde(default)]
+    unaffected: Vec<String>,
+}
+
+/// Advisories of the local database, grouped by the names of the affected crates.
+#[derive(Debug, Default)]
+pub struct AdvisoryDb {
+    pub advisories: HashMap<String, Vec<Advisory>>,
+}
+
+impl AdvisoryDb {
+    /// Load all advisories of crates from a database directory,
+    /// which could be the root of the database, or its `crates` directory.
+    pub fn open(path: &Path) -> Result<Self> {
+        if !path.is_dir() {
+            return Err(
+                Error::PathNotExist("advisory database directory", path.to_path_buf()).into(),
+            );
+        }
+        let crates_dir = path.join("crates");
+        let dir = if crates_dir.is_dir() {
+            &crates_dir
+        } else {
+            path
+        };
+
+        let mut files = utils::files_with_extension(dir, "md")?;
+        files.extend(utils::files_with_extension(dir, "toml")?);
+        let mut db = AdvisoryDb::default();
+        for file in files {
+            let content = utils::read_to_string(&file)?;
+            let advisory = Advisory::parse(&file, &content)?;
+            if advisory.withdrawn {
+                continue;
+            }
+            db.advisories
+                .entry(advisory.package.clone())
+                .or_default()
+                .push(advisory);
+        }
+        for advisories in db.advisories.values_mut() {
+            advisories.sort_by(|a, b| a.id.cmp(&b.id));
+        }
+        Ok(db)
+    }
+
+    /// Find the advisories affecting a version of a crate.
+    pub fn affecting<'a>(
+        &'a self,
+        name: &str,
+        version: &'a Version,
+    ) -> impl Iterator<Item = &'a Advisory> + 'a {
+        self.advisories
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter(move |advisory| advisory.affects(version))
+    }
+}
+
+/// A local clone of the crates.io index.
+///
+/// Each crate has a file of JSON lines, one for each published version, located at
+/// `1/a`, `2/ab`, `3/a/abc` or `ab/cd/abcd` depending on the length of its name.
+#[derive(Debug)]
+pub struct CratesIndex {
+    pub path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct IndexEntry {
+    vers: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+impl CratesIndex {
+    fn crate_path(&self, name: &str) -> PathBuf {
+        let name = name.to_lowercase();
+        let relative = match name.len() {
+            1 => format!("1/{name}"),
+            2 => format!("2/{name}"),
+            3 => format!("3/{}/{name}", &name[..1]),
+            _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+        };
+        self.path.join(relative)
+    }
+
+    /// Whether a version of a crate is yanked,
+    /// crates that are not in the index are considered as not yanked.
+    pub fn is_yanked(&self, name: &str, version: &str) -> Result<bool> {
+        let path = self.crate_path(name);
+        if !path.is_file() {
+            return Ok(false);
+        }
+        let content = utils::read_to_string(&path)?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<IndexEntry>(line).ok())
+            .any(|entry| entry.vers == version && entry.yanked))
+    }
+}
+
+/// The advisory checker.
+#[derive(Debug)]
+pub struct AdvisoryChecker {
+    pub db: AdvisoryDb,
+    pub index: Option<CratesIndex>,
+    /// IDs of advisories that should not be reported.
+    pub ignore: Vec<String>,
+}
+
+impl AdvisoryChecker {
+    /// Load the advisory database of the configuration.
+    pub fn new(cfg: &AdvisoryCfg) -> Result<Self> {
+        Ok(AdvisoryChecker {
+            db: AdvisoryDb::open(&cfg.db_path)?,
+            index: cfg.index_path.clone().map(|path| CratesIndex { path }),
+            ignore: cfg.ignore.clone(),
+        })
+    }
+
+    /// Check the `Cargo.lock` of the crate containing the given path.
+    pub fn check_crate(&self, path: &Path) -> Result<Vec<CheckInfo>> {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            path.parent()
+                .ok_or_else(|| Error::OrphanFilePath(path.to_path_buf()))?
+        };
+        let lock = utils::find_in_ancestors(dir, "Cargo.lock")
+            .ok_or_else(|| Error::LockFileNotFound(dir.to_path_buf()))?;
+        self.check(&lock)
+    }
+
+    /// Check the packages of a `Cargo.lock`, packages in the workspace are skipped.
+    pub fn check(&self, lock: &Path) -> Result<Vec<CheckInfo>> {
+        let lock = TomlFile::read(lock)?;
+        let mut result = vec![];
+        for pkg in lock.lock_packages() {
+            let Some(source) = pkg.source else {
+                continue;
+            };
+            let Ok(version) = Version::parse(pkg.version) else {
+                continue;
+            };
+            let line = lock.lock_line(pkg.name, pkg.version);
+            for advisory in self.db.affecting(pkg.name, &version) {
+                if self.ignore.contains(&advisory.id) {
+                    continue;
+                }
+                let help = format!(
+                    "`{} {}` is affected by {}: {}",
+                    pkg.name, pkg.version, advisory.id, advisory.title
+                );
+                let mut info = lock.check_info(
+                    SupportedTool::Advisory,
+                    line,
+                    advisory.kind.defect_name(),
+                    help,
+                );
+                info.additional_help_info = additional_help(advisory);
+                result.push(info);
+            }
+            if source.starts_with("registry+") || source.starts_with("sparse+") {
+                if let Some(index) = &self.index {
+                    if index.is_yanked(pkg.name, pkg.version)? {
+                        let help = format!("`{} {}` is yanked", pkg.name, pkg.version);
+                        result.push(lock.check_info(
+                            SupportedTool::Advisory,
+                            line,
+                            "yanked-dependency",
+                            help,
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl BuiltinChecker for AdvisoryChecker {
+    /// The checker is only created if the advisory database is configured,
+    /// even if the checked guidelines need it.
+    fn from_rules(rule_cfg: &RulesCfg, _: &[&CheckTool]) -> Result<Option<Self>> {
+        rule_cfg
+            .advisory
+            .as_ref()
+            .map(AdvisoryChecker::new)
+            .transpose()
+    }
+
+    fn tool(&self) -> SupportedTool {
+        SupportedTool::Advisory
+    }
+
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)> {
+        Ok((self.check_crate(ctx.path)?, None))
+    }
+}
+
+/// Describe the affected and patched versions of an advisory, such as:
+/// "affected: versions not matching `>=0.2.23`, `=0.1.43`; patched: `>=0.2.23`".
+fn additional_help(advisory: &Advisory) -> String {
+    let join = |reqs: &[VersionReq]| {
+        reqs.iter()
+            .map(|req| format!("`{req}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let safe = advisory
+        .patched
+        .iter()
+        .chain(&advisory.unaffected)
+        .cloned()
+        .collect::<Vec<_>>();
+    let affected = if safe.is_empty() {
+        "all versions".to_string()
+    } else {
+        format!("versions not matching {}", join(&safe))
+    };
+    let patched = if advisory.patched.is_empty() {
+        "none".to_string()
+    } else {
+        join(&advisory.patched)
+    };
+    let mut help = format!("affected: {affected}; patched: {patched}");
+    if let Some(url) = &advisory.url {
+        help.push_str(&format!("; see {url}"));
+    }
+    help
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use semver::Version;
+
+    use super::{Advisory, AdvisoryKind, CratesIndex};
+
+    #[test]
+    fn parse_advisories() {
+        let content = r#"```toml
+[advisory]
+id = "RUSTSEC-2020-0071"
+package = "time"
+date = "2020-11-18"
+
+[versions]
+patched = [">= 0.2.23"]
+unaffected = ["= 0.1.43"]
+```
+
+# Potential segfault in the time crate
+
+Details.
+"#;
+        let advisory = Advisory::parse(Path::new("RUSTSEC-2020-0071.md"), content).unwrap();
+        assert_eq!(advisory.id, "RUSTSEC-2020-0071");
+        assert_eq!(advisory.title, "Potential segfault in the time crate");
+        assert_eq!(advisory.kind, AdvisoryKind::Vulnerability);
+        let affects = |v: &str| advisory.affects(&Version::parse(v).unwrap());
+        assert!(affects("0.1.44"));
+        assert!(affects("0.2.22"));
+        assert!(!affects("0.1.43"));
+        assert!(!affects("0.2.23"));
+        assert!(!affects("0.3.0"));
+
+        let content = r#"[advisory]
+id = "RUSTSEC-2016-0001"
+package = "openssl"
+title = "SSL/TLS MitM vulnerability"
+informational = "unsound"
+"#;
+        let advisory = Advisory::parse(Path::new("RUSTSEC-2016-0001.toml"), content).unwrap();
+        assert_eq!(advisory.title, "SSL/TLS MitM vulnerability");
+        assert_eq!(advisory.kind, AdvisoryKind::Unsound);
+        assert!(advisory.affects(&Version::new(0, 10, 0)));
+    }
+
+    #[test]
+    fn crates_index_paths() {
+        let index = CratesIndex {
+            path: "index".into(),
+        };
+        let paths = ["a", "ab", "abc", "Serde"].map(|name| index.crate_path(name));
+        assert_eq!(
+            paths,
+            [
+                "index/1/a",
+                "index/2/ab",
+                "index/3/a/abc",
+                "index/se/rd/serde"
+            ]
+            .map(PathBuf::from)
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Lint diagnostics of clippy and rustc, parsed from their JSON output,
+//! see [`LintsOpt::json_output`](super::LintsOpt::json_output).
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+
+use super::SupportedTool;
+use crate::parser::{CheckInfo, ChildMessage, CodeSpan};
+use crate::utils;
+
+/// A lint diagnostic emitted by clippy or rustc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Name of the lint, such as `clippy::bool_comparison`.
+    pub lint: String,
+    pub message: String,
+    pub file_path: Option<PathBuf>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub span: Option<CodeSpan>,
+    /// The lines of code of the primary span.
+    pub code_string: String,
+    /// Other labelled spans, and the spans of located notes, such as
+    /// where a moved value was moved.
+    pub secondary_spans: Vec<CodeSpan>,
+    pub children: Vec<ChildMessage>,
+    /// Machine-applicable suggestions of this diagnostic.
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A suggestion of a diagnostic, which could replace several parts of the code,
+/// such as both the declaration and the uses of a variable.
+///
+/// Its replacements only make sense together, so they are either all applied or none.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Suggestion {
+    pub replacements: Vec<Replacement>,
+}
+
+/// Replacing a range of bytes in a file with a text.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Replacement {
+    pub file_path: PathBuf,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub text: String,
+}
+
+impl Diagnostic {
+    pub fn tool(&self) -> SupportedTool {
+        if self.lint.starts_with("clippy::") {
+            SupportedTool::Clippy
+        } else {
+            SupportedTool::Rustc
+        }
+    }
+
+    /// Generalize this diagnostic to a [`CheckInfo`],
+    /// so that it could be related to guidelines.
+    pub fn check_info(&self) -> CheckInfo {
+        CheckInfo {
+            file_path: self.file_path.clone(),
+            defect_name: self.lint.clone(),
+            tool: self.tool(),
+            begin_line: self.line,
+            end_line: self.span.as_ref().map(|span| span.end_line),
+            column: self.column,
+            code_string: self.code_string.clone(),
+            help_info: self.message.clone(),
+            additional_help_info: self
+                .children
+                .iter()
+                .map(|child| format!("{child}\n"))
+                .collect(),
+            span: self.span.clone(),
+            secondary_spans: self.secondary_spans.clone(),
+            children: self.children.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parse diagnostics from the JSON output of a tool.
+///
+/// File names are relative to the directory of the workspace, or the directory
+/// the tool runs in, so they will be resolved against `base_dir` and its ancestors.
+pub fn parse_diagnostics(output: &Output, base_dir: &Path) -> Vec<Diagnostic> {
+    let stdout = lint_messages(&String::from_utf8_lossy(&output.stdout));
+    let stderr = lint_messages(&String::from_utf8_lossy(&output.stderr));
+
+    let mut result = vec![];
+    for message in stdout.iter().chain(&stderr) {
+        if let Some(diag) = parse_message(message, base_dir) {
+            if !result.contains(&diag) {
+                result.push(diag);
+            }
+        }
+    }
+    result
+}
+
+/// Collect the lint diagnostics in the JSON output of a tool, as one line of JSON each.
+///
+/// Cargo prints one message per line on stdout, wrapping diagnostics in
+/// `"compiler-message"`, while rustc prints diagnostics directly on stderr.
+/// The same diagnostic could be reported for multiple targets, it's only kept once.
+pub fn lint_messages(output: &str) -> Vec<String> {
+    let mut result = vec![];
+    for line in output.lines() {
+        let Ok(value) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let Some(message) = diagnostic_of(&value).filter(|m| lint_name(m).is_some()) else {
+            continue;
+        };
+        let message = message.to_string();
+        if !result.contains(&message) {
+            result.push(message);
+        }
+    }
+    result
+}
+
+/// Parse a line of the JSON output of a tool, which is either a diagnostic, or a message
+/// of cargo wrapping it. Returns `None` if it isn't a lint diagnostic.
+pub fn parse_message(line: &str, base_dir: &Path) -> Option<Diagnostic> {
+    let value = serde_json::from_str::<Value>(line).ok()?;
+    parse_diagnostic(diagnostic_of(&value)?, base_dir)
+}
+
+/// The diagnostic of a message, which could be wrapped by cargo.
+fn diagnostic_of(value: &Value) -> Option<&Value> {
+    match value.get("reason").and_then(Value::as_str) {
+        Some("compiler-message") => Some(&value["message"]),
+        Some(_) => None,
+        None => Some(value),
+    }
+}
+
+/// The name of the lint of a diagnostic, `None` if it's not emitted by a lint.
+fn lint_name(message: &Value) -> Option<&str> {
+    let code = message["code"]["code"].as_str()?;
+    // error codes such as "E0308" are not lints
+    let is_error_code = code.starts_with('E') && code[1..].chars().all(|c| c.is_ascii_digit());
+    (!is_error_code).then_some(code)
+}
+
+fn parse_diagnostic(message: &Value, base_dir: &Path) -> Option<Diagnostic> {
+    let lint = lint_name(message)?;
+    let primary = message["spans"]
+        .as_array()?
+        .iter()
+        .find(|span| span["is_primary"].as_bool() == Some(true));
+
+    let primary_file = primary.and_then(|span| span["file_name"].as_str());
+
+    let mut secondary_spans = message["spans"]
+        .as_array()?
+        .iter()
+        .filter(|span| span["is_primary"].as_bool() != Some(true))
+        .filter_map(|span| code_span(span, primary_file, base_dir))
+        .collect::<Vec<_>>();
+    let mut children = vec![];
+    for child in message["children"].as_array().into_iter().flatten() {
+        let child_message = child["message"].as_str().unwrap_or_default();
+        let located = child["spans"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|span| span["suggested_replacement"].is_null())
+            .filter_map(|span| code_span(span, primary_file, base_dir))
+            .collect::<Vec<_>>();
+        // notes pointing at other code are rendered as labelled spans
+        if !located.is_empty() {
+            secondary_spans.extend(
+                located
+                    .into_iter()
+                    .map(|span| span.with_label(child_message)),
+            );
+            continue;
+        }
+        match child["level"].as_str() {
+            Some("help") => children.push(ChildMessage::help(child_message)),
+            Some("note") => children.push(ChildMessage::note(child_message)),
+            _ => (),
+        }
+    }
+
+    let mut suggestions = vec![];
+    collect_suggestions(message, base_dir, &mut suggestions);
+
+    Some(Diagnostic {
+        lint: lint.to_string(),
+        message: message["message"].as_str().unwrap_or_default().to_string(),
+        file_path: primary
+            .and_then(|span| span["file_name"].as_str())
+            .map(|name| resolve_path(base_dir, name)),
+        line: primary
+            .and_then(|span| span["line_start"].as_u64())
+            .map(|n| n as usize),
+        column: primary
+            .and_then(|span| span["column_start"].as_u64())
+            .map(|n| n as usize),
+        span: primary.and_then(|span| code_span(span, primary_file, base_dir)),
+        code_string: primary
+            .and_then(|span| span["text"].as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|line| line["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        secondary_spans,
+        children,
+        suggestions,
+    })
+}
+
+/// Convert a span of a diagnostic, its file is only kept if it differs from `primary_file`.
+fn code_span(span: &Value, primary_file: Option<&str>, base_dir: &Path) -> Option<CodeSpan> {
+    let position = |key: &str| span[key].as_u64().map(|n| n as usize);
+    let mut result = CodeSpan::new(
+        (position("line_start")?, position("column_start")?),
+        (position("line_end")?, position("column_end")?),
+    );
+    if let Some(label) = span["label"].as_str() {
+        result = result.with_label(label);
+    }
+    match span["file_name"].as_str() {
+        Some(file) if Some(file) != primary_file => {
+            Some(result.with_file(resolve_path(base_dir, file)))
+        }
+        _ => Some(result),
+    }
+}
+
+/// Collect the suggestions of a message and its children, the replacements
+/// of each message make up a suggestion.
+fn collect_suggestions(message: &Value, base_dir: &Path, result: &mut Vec<Suggestion>) {
+    let mut replacements = vec![];
+    for span in message["spans"].as_array().into_iter().flatten() {
+        if span["suggestion_applicability"].as_str() != Some("MachineApplicable") {
+            continue;
+        }
+        let (Some(text), Some(file), Some(start), Some(end)) = (
+            span["suggested_replacement"].as_str(),
+            span["file_name"].as_str(),
+            span["byte_start"].as_u64(),
+            span["byte_end"].as_u64(),
+        ) else {
+            continue;
+        };
+        replacements.push(Replacement {
+            file_path: resolve_path(base_dir, file),
+            byte_start: start as usize,
+            byte_end: end as usize,
+            text: text.to_string(),
+        });
+    }
+    if !replacements.is_empty() {
+        replacements.sort();
+        result.push(Suggestion { replacements });
+    }
+    for child in message["children"].as_array().into_iter().flatten() {
+        collect_suggestions(child, base_dir, result);
+    }
+}
+
+fn resolve_path(base_dir: &Path, file: &str) -> PathBuf {
+    utils::resolve_in_ancestors(base_dir, file).unwrap_or_else(|| base_dir.join(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint_messages, parse_diagnostics};
+    use crate::parser::{ChildMessage, CodeSpan};
+    use std::os::unix::process::ExitStatusExt;
+    use std::path::Path;
+    use std::process::{ExitStatus, Output};
+
+    #[test]
+    fn parse_cargo_messages() {
+        let stdout = r#"{"reason":"compiler-artifact","package_id":"mock"}
+{"reason":"compiler-message","message":{"message":"equality checks against true are unnecessary","code":{"code":"clippy::bool_comparison","explanation":null},"level":"warning","spans":[{"file_name":"src/lints.rs","byte_start":297,"byte_end":309,"line_start":15,"line_end":15,"column_start":8,"column_end":20,"is_primary":true,"suggested_replacement":null,"suggestion_applicability":null}],"children":[{"message":"try","code":null,"level":"help","spans":[{"file_name":"src/lints.rs","byte_start":297,"byte_end":309,"line_start":15,"line_end":15,"column_start":8,"column_end":20,"is_primary":true,"suggested_replacement":"flag","suggestion_applicability":"MachineApplicable"}],"children":[]}]}}
+{"reason":"compiler-message","message":{"message":"mismatched types","code":{"code":"E0308","explanation":null},"level":"error","spans":[],"children":[]}}
+"#;
+        let output = Output {
+            status: ExitStatus::from_raw(0),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: vec![],
+        };
+        let diags = parse_diagnostics(&output, Path::new("/mock"));
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].lint, "clippy::bool_comparison");
+        assert_eq!(diags[0].line, Some(15));
+        assert_eq!(diags[0].column, Some(8));
+        assert_eq!(diags[0].span, Some(CodeSpan::new((15, 8), (15, 20))));
+        assert!(diags[0].secondary_spans.is_empty());
+        assert_eq!(diags[0].children, [ChildMessage::help("try")]);
+        assert_eq!(diags[0].suggestions.len(), 1);
+        let replacements = &diags[0].suggestions[0].replacements;
+        assert_eq!(replacements.len(), 1);
+        assert_eq!(replacements[0].text, "flag");
+        assert_eq!(replacements[0].file_path, Path::new("/mock/src/lints.rs"));
+
+        // only the lint diagnostic is kept, without the message of cargo wrapping it
+        let messages = lint_messages(&stdout.repeat(2));
+        assert_eq!(messages.len(), 1);
+        let message = serde_json::from_str::<serde_json::Value>(&messages[0]).unwrap();
+        assert_eq!(message["code"]["code"], "clippy::bool_comparison");
+        assert!(message.get("reason").is_none());
+    }
+}
@@ -0,0 +1,474 @@
+//! A checker measuring the documentation coverage of public items.
+//!
+//! Public items are the crate itself, and the modules, types, traits, constants, statics
+//! and functions declared `pub` in public modules, including methods of public traits and
+//! public inherent methods.
+//! Besides of being documented, public functions should have these sections when needed:
+//!
+//! - `# Errors`, if the function returns a `Result`.
+//! - `# Safety`, if the function is `unsafe`.
+//!
+//! `# Panics` sections are checked by the [panic checker](super::panics), which knows
+//! where functions could panic.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use proc_macro2::Span;
+use serde::{Deserialize, Serialize};
+use syn::visit::{self, Visit};
+use syn::{Attribute, Ident, ReturnType, Signature, Type, Visibility};
+
+use super::source::{doc_comment, has_doc_section, is_cfg_test, SourceFile};
+use super::{uses_tool, BuiltinChecker, CheckContext, SourceChecker, SupportedTool};
+use crate::parser::{CheckInfo, CheckTool, CodeSpan, OutputSection, RulesCfg};
+use crate::Result;
+
+/// A problem of the documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocIssue {
+    MissingDocs,
+    MissingErrorsSection,
+    MissingSafetySection,
+}
+
+impl DocIssue {
+    pub fn defect_name(&self) -> &'static str {
+        match self {
+            DocIssue::MissingDocs => "missing-docs",
+            DocIssue::MissingErrorsSection => "missing-errors-doc",
+            DocIssue::MissingSafetySection => "missing-safety-doc",
+        }
+    }
+}
+
+impl Display for DocIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            DocIssue::MissingDocs => "is not documented",
+            DocIssue::MissingErrorsSection => "returns a `Result`, but has no `# Errors` section",
+            DocIssue::MissingSafetySection => "is unsafe, but has no `# Safety` section",
+        };
+        f.write_str(str)
+    }
+}
+
+/// A public item, along with the problems of its documentation.
+#[derive(Debug, Clone)]
+pub struct PublicItem {
+    pub file_path: PathBuf,
+    pub module: String,
+    /// Kind of the item, such as `struct`.
+    pub kind: &'static str,
+    pub name: String,
+    pub line: usize,
+    /// 1-based column of the item's name.
+    pub column: usize,
+    pub documented: bool,
+    pub issues: Vec<DocIssue>,
+}
+
+/// Numbers of public items and documented ones.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageCount {
+    pub public_items: usize,
+    pub documented: usize,
+    /// Percentage of the documented items, which is 100 if there's no public item.
+    pub percentage: f64,
+}
+
+impl CoverageCount {
+    fn add(&mut self, item: &PublicItem) {
+        self.public_items += 1;
+        if item.documented {
+            self.documented += 1;
+        }
+        self.percentage = self.documented as f64 * 100.0 / self.public_items as f64;
+    }
+
+    fn add_count(&mut self, other: &CoverageCount) {
+        self.public_items += other.public_items;
+        self.documented += other.documented;
+        if self.public_items > 0 {
+            self.percentage = self.documented as f64 * 100.0 / self.public_items as f64;
+        }
+    }
+}
+
+/// Documentation coverage of the checked crate, in total and per module.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocCoverage {
+    pub total: CoverageCount,
+    pub modules: BTreeMap<String, CoverageCount>,
+}
+
+impl<'a> FromIterator<&'a PublicItem> for DocCoverage {
+    fn from_iter<T: IntoIterator<Item = &'a PublicItem>>(iter: T) -> Self {
+        let mut coverage = DocCoverage {
+            total: CoverageCount {
+                percentage: 100.0,
+                ..Default::default()
+            },
+            modules: BTreeMap::new(),
+        };
+        for item in iter {
+            coverage.total.add(item);
+            coverage
+                .modules
+                .entry(item.module.clone())
+                .or_default()
+                .add(item);
+        }
+        coverage
+    }
+}
+
+impl DocCoverage {
+    /// Merge the coverage of another run, such as of another crate.
+    ///
+    /// Module paths are relative to their crates, such as `crate::foo`, so counts of
+    /// the same module are added up, and the total is counted again from the modules.
+    pub fn merge(&mut self, other: DocCoverage) {
+        for (module, count) in other.modules {
+            self.modules
+                .entry(module)
+                .or_insert_with(|| CoverageCount {
+                    percentage: 100.0,
+                    ..Default::default()
+                })
+                .add_count(&count);
+        }
+        self.total = CoverageCount {
+            percentage: 100.0,
+            ..Default::default()
+        };
+        for count in self.modules.values() {
+            self.total.add_count(count);
+        }
+    }
+}
+
+/// The documentation coverage checker.
+#[derive(Debug, Default)]
+pub struct DocChecker;
+
+impl DocChecker {
+    /// Find all the public items in a crate.
+    ///
+    /// A module declared as `mod foo;` is documented by either its outer doc comments,
+    /// or the inner ones of its own file, so the files need to be checked together.
+    pub fn public_items(&self, files: &[SourceFile]) -> Vec<PublicItem> {
+        let inner_documented = files
+            .iter()
+            .filter(|file| doc_comment(&file.ast.attrs).is_some())
+            .map(|file| file.module.as_str())
+            .collect::<HashSet<_>>();
+        let mut declarations = ModDeclarations::default();
+        for file in files {
+            declarations.modules = vec![file.module.clone()];
+            declarations.visit_file(&file.ast);
+        }
+
+        let mut result = vec![];
+        for file in files {
+            let is_crate_root =
+                file.module == "crate" && file.path.file_stem().is_some_and(|stem| stem == "lib");
+            if is_crate_root {
+                let documented = inner_documented.contains("crate");
+                result.push(PublicItem {
+                    file_path: file.path.clone(),
+                    module: file.module.clone(),
+                    kind: "crate",
+                    name: file.module.clone(),
+                    line: 1,
+                    column: 1,
+                    documented,
+                    issues: if documented {
+                        vec![]
+                    } else {
+                        vec![DocIssue::MissingDocs]
+                    },
+                });
+            }
+            let mut visitor = ItemVisitor {
+                file,
+                inner_documented: &inner_documented,
+                modules: vec![file.module.clone()],
+                exported: vec![declarations.is_exported(&file.module)],
+                in_public_trait: false,
+                result: vec![],
+            };
+            visitor.visit_file(&file.ast);
+            result.extend(visitor.result);
+        }
+        result
+    }
+
+    pub fn coverage(&self, files: &[SourceFile]) -> DocCoverage {
+        self.public_items(files).iter().collect()
+    }
+}
+
+impl SourceChecker for DocChecker {
+    fn check_file(&self, file: &SourceFile) -> Vec<CheckInfo> {
+        self.check_sources(std::slice::from_ref(file))
+    }
+
+    fn check_sources(&self, files: &[SourceFile]) -> Vec<CheckInfo> {
+        let mut result = vec![];
+        for item in self.public_items(files) {
+            let Some(file) = files.iter().find(|f| f.path == item.file_path) else {
+                continue;
+            };
+            for issue in &item.issues {
+                result.push(CheckInfo {
+                    file_path: Some(item.file_path.clone()),
+                    defect_name: issue.defect_name().to_string(),
+                    tool: SupportedTool::DocCoverage,
+                    begin_line: Some(item.line),
+                    column: Some(item.column),
+                    code_string: file.line(item.line).trim().to_string(),
+                    help_info: format!("public {} `{}` {issue}", item.kind, item.name),
+                    span: Some(CodeSpan {
+                        begin_column: Some(item.column),
+                        ..CodeSpan::lines(item.line, item.line)
+                    }),
+                    ..Default::default()
+                });
+            }
+        }
+        result
+    }
+}
+
+impl BuiltinChecker for DocChecker {
+    fn from_rules(_: &RulesCfg, checked_tools: &[&CheckTool]) -> Result<Option<Self>> {
+        Ok(uses_tool(checked_tools, SupportedTool::DocCoverage).then_some(DocChecker))
+    }
+
+    fn tool(&self) -> SupportedTool {
+        SupportedTool::DocCoverage
+    }
+
+    fn needs_sources(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)> {
+        let coverage = self.coverage(ctx.sources);
+        Ok((
+            self.check_sources(ctx.sources),
+            Some(OutputSection::DocCoverage(coverage)),
+        ))
+    }
+}
+
+struct ItemVisitor<'a> {
+    file: &'a SourceFile,
+    /// Modules having inner documentation in their own files.
+    inner_documented: &'a HashSet<&'a str>,
+    /// Paths of the module being visited and its parents.
+    modules: Vec<String>,
+    /// Whether each module in `modules` could be reached from other crates.
+    exported: Vec<bool>,
+    /// Whether the items being visited belong to a public trait.
+    in_public_trait: bool,
+    result: Vec<PublicItem>,
+}
+
+impl ItemVisitor<'_> {
+    fn module(&self) -> String {
+        self.modules.last().cloned().unwrap_or_default()
+    }
+
+    fn is_exported(&self, vis: &Visibility) -> bool {
+        self.exported.last().copied().unwrap_or_default() && is_public(vis)
+    }
+
+    fn add(&mut self, kind: &'static str, name: &Ident, documented: bool) {
+        let issues = if documented {
+            vec![]
+        } else {
+            vec![DocIssue::MissingDocs]
+        };
+        self.push(kind, name.to_string(), name.span(), documented, issues);
+    }
+
+    fn push(
+        &mut self,
+        kind: &'static str,
+        name: String,
+        span: Span,
+        documented: bool,
+        issues: Vec<DocIssue>,
+    ) {
+        let start = span.start();
+        self.result.push(PublicItem {
+            file_path: self.file.path.clone(),
+            module: self.module(),
+            kind,
+            name,
+            line: start.line,
+            column: start.column + 1,
+            documented,
+            issues,
+        });
+    }
+
+    fn add_item(
+        &mut self,
+        kind: &'static str,
+        vis: &Visibility,
+        name: &Ident,
+        attrs: &[Attribute],
+    ) {
+        if self.is_exported(vis) {
+            self.add(kind, name, doc_comment(attrs).is_some());
+        }
+    }
+
+    fn add_fn(&mut self, sig: &Signature, attrs: &[Attribute]) {
+        let documented = doc_comment(attrs).is_some();
+        let mut issues = vec![];
+        if !documented {
+            issues.push(DocIssue::MissingDocs);
+        }
+        if returns_result(sig) && !has_doc_section(attrs, "Errors") {
+            issues.push(DocIssue::MissingErrorsSection);
+        }
+        if sig.unsafety.is_some() && !has_doc_section(attrs, "Safety") {
+            issues.push(DocIssue::MissingSafetySection);
+        }
+        let name = sig.ident.to_string();
+        self.push("fn", name, sig.ident.span(), documented, issues);
+    }
+}
+
+/// Collects the public modules of a crate, to find out whether the items inside
+/// could be reached from other crates.
+#[derive(Default)]
+struct ModDeclarations {
+    modules: Vec<String>,
+    public: HashSet<String>,
+}
+
+impl ModDeclarations {
+    /// Whether the module and all its parents are public.
+    fn is_exported(&self, module: &str) -> bool {
+        let segments = module.split("::").collect::<Vec<_>>();
+        (2..=segments.len()).all(|n| self.public.contains(&segments[..n].join("::")))
+    }
+}
+
+impl<'ast> Visit<'ast> for ModDeclarations {
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        let path = format!(
+            "{}::{}",
+            self.modules.last().cloned().unwrap_or_default(),
+            item.ident
+        );
+        if is_public(&item.vis) {
+            self.public.insert(path.clone());
+        }
+        self.modules.push(path);
+        visit::visit_item_mod(self, item);
+        self.modules.pop();
+    }
+}
+
+fn is_public(vis: &Visibility) -> bool {
+    matches!(vis, Visibility::Public(_))
+}
+
+fn returns_result(sig: &Signature) -> bool {
+    let ReturnType::Type(_, ty) = &sig.output else {
+        return false;
+    };
+    let Type::Path(path) = ty.as_ref() else {
+        return false;
+    };
+    path.path
+        .segments
+        .last()
+        .is_some_and(|seg| seg.ident == "Result")
+}
+
+impl<'ast> Visit<'ast> for ItemVisitor<'_> {
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        if is_cfg_test(&item.attrs) {
+            return;
+        }
+        let path = format!("{}::{}", self.module(), item.ident);
+        let exported = self.is_exported(&item.vis);
+        if exported {
+            // inner doc comments of inline modules are parsed into `attrs` as well
+            let documented = doc_comment(&item.attrs).is_some()
+                || (item.content.is_none() && self.inner_documented.contains(path.as_str()));
+            self.add("module", &item.ident, documented);
+        }
+        self.modules.push(path);
+        self.exported.push(exported);
+        visit::visit_item_mod(self, item);
+        self.exported.pop();
+        self.modules.pop();
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        if self.is_exported(&item.vis) {
+            self.add_fn(&item.sig, &item.attrs);
+        }
+        // functions nested in functions are not public
+    }
+
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        self.add_item("struct", &item.vis, &item.ident, &item.attrs);
+    }
+
+    fn visit_item_enum(&mut self, item: &'ast syn::ItemEnum) {
+        self.add_item("enum", &item.vis, &item.ident, &item.attrs);
+    }
+
+    fn visit_item_union(&mut self, item: &'ast syn::ItemUnion) {
+        self.add_item("union", &item.vis, &item.ident, &item.attrs);
+    }
+
+    fn visit_item_type(&mut self, item: &'ast syn::ItemType) {
+        self.add_item("type", &item.vis, &item.ident, &item.attrs);
+    }
+
+    fn visit_item_const(&mut self, item: &'ast syn::ItemConst) {
+        self.add_item("const", &item.vis, &item.ident, &item.attrs);
+    }
+
+    fn visit_item_static(&mut self, item: &'ast syn::ItemStatic) {
+        self.add_item("static", &item.vis, &item.ident, &item.attrs);
+    }
+
+    fn visit_item_trait(&mut self, item: &'ast syn::ItemTrait) {
+        self.add_item("trait", &item.vis, &item.ident, &item.attrs);
+        let exported = self.is_exported(&item.vis);
+        let in_public_trait = std::mem::replace(&mut self.in_public_trait, exported);
+        visit::visit_item_trait(self, item);
+        self.in_public_trait = in_public_trait;
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast syn::TraitItemFn) {
+        if self.in_public_trait {
+            self.add_fn(&item.sig, &item.attrs);
+        }
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        // methods of trait implementations are documented by the trait
+        if item.trait_.is_none() {
+            visit::visit_item_impl(self, item);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        if self.is_exported(&item.vis) {
+            self.add_fn(&item.sig, &item.attrs);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Applying the machine-applicable suggestions that clippy and rustc emit
+//! along with their [diagnostics](super::diagnostics).
+//!
+//! The tools need to be run with JSON output, see
+//! [`LintsOpt::json_output`](super::LintsOpt::json_output), because the human readable
+//! output doesn't tell the exact positions of suggestions.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use similar::TextDiff;
+
+use super::diagnostics::{Replacement, Suggestion};
+use crate::{utils, Error, Result};
+
+/// The fixed content of a file.
+#[derive(Debug)]
+pub struct FileFix {
+    pub file_path: PathBuf,
+    pub original: String,
+    pub fixed: String,
+    /// The number of suggestions that were applied to this file.
+    pub applied: usize,
+}
+
+impl FileFix {
+    /// Show the changes as a unified diff.
+    pub fn unified_diff(&self) -> String {
+        let path = self.file_path.display().to_string();
+        TextDiff::from_lines(&self.original, &self.fixed)
+            .unified_diff()
+            .context_radius(3)
+            .header(&path, &path)
+            .to_string()
+    }
+}
+
+/// Apply suggestions to their files, without writing them.
+///
+/// A suggestion is skipped if any of its replacements overlaps with one of a previous
+/// suggestion, as they would conflict, and it will be reported again after re-running
+/// the checks.
+pub fn apply_suggestions(suggestions: &[Suggestion]) -> Result<Vec<FileFix>> {
+    let mut suggestions = suggestions.iter().collect::<Vec<_>>();
+    suggestions.sort();
+    suggestions.dedup();
+
+    let mut originals: BTreeMap<&Path, String> = BTreeMap::new();
+    for rep in suggestions.iter().flat_map(|s| &s.replacements) {
+        if !originals.contains_key(rep.file_path.as_path()) {
+            originals.insert(&rep.file_path, utils::read_to_string(&rep.file_path)?);
+        }
+    }
+
+    let mut accepted: BTreeMap<&Path, Vec<&Replacement>> = BTreeMap::new();
+    let mut applied: BTreeMap<&Path, usize> = BTreeMap::new();
+    for suggestion in suggestions {
+        let fits = suggestion.replacements.iter().enumerate().all(|(i, rep)| {
+            let original = &originals[rep.file_path.as_path()];
+            let overlaps = |other: &&Replacement| {
+                other.file_path == rep.file_path
+                    && other.byte_start < rep.byte_end.max(rep.byte_start + 1)
+                    && rep.byte_start < other.byte_end.max(other.byte_start + 1)
+            };
+            rep.byte_start <= rep.byte_end
+                && rep.byte_end <= original.len()
+                && original.is_char_boundary(rep.byte_start)
+                && original.is_char_boundary(rep.byte_end)
+                && !suggestion.replacements[..i].iter().any(|r| overlaps(&r))
+                && !accepted
+                    .get(rep.file_path.as_path())
+                    .is_some_and(|reps| reps.iter().any(overlaps))
+        });
+        if !fits {
+            continue;
+        }
+        let mut files = vec![];
+        for rep in &suggestion.replacements {
+            accepted.entry(&rep.file_path).or_default().push(rep);
+            if !files.contains(&rep.file_path.as_path()) {
+                files.push(&rep.file_path);
+            }
+        }
+        for file in files {
+            *applied.entry(file).or_default() += 1;
+        }
+    }
+
+    let mut result = vec![];
+    for (path, mut reps) in accepted {
+        reps.sort();
+        let original = originals.remove(path).unwrap_or_default();
+        let mut fixed = String::with_capacity(original.len());
+        let mut last_end = 0;
+        for rep in reps {
+            fixed.push_str(&original[last_end..rep.byte_start]);
+            fixed.push_str(&rep.text);
+            last_end = rep.byte_end;
+        }
+        fixed.push_str(&original[last_end..]);
+
+        if fixed != original {
+            result.push(FileFix {
+                file_path: path.to_path_buf(),
+                original,
+                fixed,
+                applied: applied[path],
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// Check whether a file has uncommitted changes, files that are not tracked
+/// by git are considered dirty as well, since their changes could not be reverted.
+pub fn is_dirty(path: &Path) -> Result<bool> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| Error::OrphanFilePath(path.to_path_buf()))?;
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args([
+            "status",
+            "--porcelain",
+            "--ignored",
+            "--untracked-files=all",
+            "--",
+        ])
+        .arg(path)
+        .output();
+    // not being able to run git means the file is not in a repository
+    Ok(!matches!(output, Ok(o) if o.status.success() && o.stdout.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_suggestions, Replacement, Suggestion};
+    use std::fs;
+
+    #[test]
+    fn apply_whole_suggestions() {
+        let content = "fn f() -> i32 { let a = 1; a + a }\n";
+        let file_path = std::env::temp_dir()
+            .join(format!("eunomia_fix_{}", std::process::id()))
+            .join("lib.rs");
+        fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        fs::write(&file_path, content).unwrap();
+
+        let rep = |code: &str, nth: usize, text: &str| {
+            let byte_start = content.match_indices(code).nth(nth).unwrap().0;
+            Replacement {
+                file_path: file_path.clone(),
+                byte_start,
+                byte_end: byte_start + code.len(),
+                text: text.to_string(),
+            }
+        };
+        let rename = Suggestion {
+            replacements: vec![rep("a", 0, "x"), rep("a", 1, "x"), rep("a", 2, "x")],
+        };
+        // overlaps with the renaming, so its first part can't be applied alone either
+        let double = Suggestion {
+            replacements: vec![rep("1", 0, "2"), rep("a + a", 0, "a * 2")],
+        };
+        let fixes = apply_suggestions(&[double, rename.clone(), rename]).unwrap();
+        fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].fixed, "fn f() -> i32 { let x = 1; x + x }\n");
+        assert_eq!(fixes[0].applied, 1);
+    }
+}
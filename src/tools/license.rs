@@ -0,0 +1,434 @@
+//! A checker of the licenses of all dependencies, which are resolved with `cargo metadata`,
+//! see [`LicenseCfg`] for the allowed and denied licenses.
+//!
+//! The `license` field of each package is an [SPDX license expression], such as
+//! `MIT OR Apache-2.0`, which is evaluated as:
+//!
+//! - `A OR B`: allowed if any of them is allowed, denied if all of them are denied;
+//! - `A AND B`: denied if any of them is denied, allowed if all of them are allowed;
+//! - `A WITH B`: see [`LicenseCfg`].
+//!
+//! Anything else is unknown, including packages without a `license`, or with only
+//! a `license-file`, which could not be evaluated.
+//!
+//! [SPDX license expression]: https://spdx.github.io/spdx-spec/v2.3/SPDX-license-expressions/
+
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use super::manifest::TomlFile;
+use super::{uses_tool, BuiltinChecker, CheckContext, SupportedTool};
+use crate::parser::{CheckInfo, CheckTool, LicenseCfg, OutputSection, RulesCfg};
+use crate::{utils, Error, Result};
+
+/// A parsed SPDX license expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LicenseExpr {
+    License {
+        id: String,
+        exception: Option<String>,
+    },
+    And(Vec<LicenseExpr>),
+    Or(Vec<LicenseExpr>),
+}
+
+impl FromStr for LicenseExpr {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |reason: &str| Error::InvalidLicenseExpr(s.to_string(), reason.to_string());
+        // `/` is a legacy separator of `OR`, such as `MIT/Apache-2.0`.
+        let spaced = s
+            .replace('(', " ( ")
+            .replace(')', " ) ")
+            .replace('/', " OR ");
+        let tokens = spaced.split_whitespace().collect::<Vec<_>>();
+        if tokens.is_empty() {
+            return Err(err("empty expression"));
+        }
+        let mut parser = ExprParser { tokens, pos: 0 };
+        let expr = parser.parse_or().map_err(|reason| err(&reason))?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(token) => Err(err(&format!("unexpected `{token}`"))),
+        }
+    }
+}
+
+impl Display for LicenseExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join = |exprs: &[LicenseExpr], op: &str| {
+            exprs
+                .iter()
+                .map(|e| match e {
+                    LicenseExpr::License { .. } => e.to_string(),
+                    _ => format!("({e})"),
+                })
+                .collect::<Vec<_>>()
+                .join(op)
+        };
+        match self {
+            LicenseExpr::License { id, exception } => match exception {
+                Some(exception) => write!(f, "{id} WITH {exception}"),
+                None => f.write_str(id),
+            },
+            LicenseExpr::And(exprs) => f.write_str(&join(exprs, " AND ")),
+            LicenseExpr::Or(exprs) => f.write_str(&join(exprs, " OR ")),
+        }
+    }
+}
+
+/// A recursive descent parser of license expressions, `WITH` binds tighter than `AND`,
+/// which binds tighter than `OR`.
+struct ExprParser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl ExprParser<'_> {
+    fn eat(&mut self, keyword: &str) -> bool {
+        let matched = self
+            .tokens
+            .get(self.pos)
+            .is_some_and(|token| token.eq_ignore_ascii_case(keyword));
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn parse_or(&mut self) -> Result<LicenseExpr, String> {
+        let mut exprs = vec![self.parse_and()?];
+        while self.eat("OR") {
+            exprs.push(self.parse_and()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            LicenseExpr::Or(exprs)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<LicenseExpr, String> {
+        let mut exprs = vec![self.parse_with()?];
+        while self.eat("AND") {
+            exprs.push(self.parse_with()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            LicenseExpr::And(exprs)
+        })
+    }
+
+    fn parse_with(&mut self) -> Result<LicenseExpr, String> {
+        if self.eat("(") {
+            let expr = self.parse_or()?;
+            if !self.eat(")") {
+                return Err("unclosed parenthesis".to_string());
+            }
+            return Ok(expr);
+        }
+        let id = self.ident()?;
+        let exception = if self.eat("WITH") {
+            Some(self.ident()?)
+        } else {
+            None
+        };
+        Ok(LicenseExpr::License { id, exception })
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.tokens.get(self.pos) {
+            None => Err("unexpected end of expression".to_string()),
+            Some(&token)
+                if ["(", ")", "AND", "OR", "WITH"]
+                    .iter()
+                    .any(|kw| token.eq_ignore_ascii_case(kw)) =>
+            {
+                Err(format!("expected a license, found `{token}`"))
+            }
+            Some(&token) => {
+                self.pos += 1;
+                Ok(token.to_string())
+            }
+        }
+    }
+}
+
+/// Result of evaluating a license against the allowed and denied lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicenseStatus {
+    Allowed,
+    Denied,
+    Unknown,
+}
+
+/// A package of the dependency graph, from the output of `cargo metadata`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    pub id: String,
+    pub license: Option<String>,
+    pub license_file: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<Package>,
+    workspace_members: Vec<String>,
+}
+
+/// The license checker.
+#[derive(Debug, Default)]
+pub struct LicenseChecker {
+    pub cfg: LicenseCfg,
+}
+
+impl LicenseChecker {
+    pub fn new(cfg: LicenseCfg) -> Self {
+        LicenseChecker { cfg }
+    }
+
+    /// Evaluate a license expression.
+    pub fn evaluate(&self, expr: &LicenseExpr) -> LicenseStatus {
+        use LicenseStatus::*;
+        match expr {
+            LicenseExpr::License { id, exception } => {
+                let contains = |list: &[String], license: &str| {
+                    list.iter().any(|l| l.eq_ignore_ascii_case(license))
+                };
+                let full = expr.to_string();
+                if exception.is_some() && contains(&self.cfg.denied, &full) {
+                    Denied
+                } else if exception.is_some() && contains(&self.cfg.allowed, &full) {
+                    Allowed
+                } else if contains(&self.cfg.denied, id) {
+                    Denied
+                } else if self.cfg.allowed.is_empty() || contains(&self.cfg.allowed, id) {
+                    Allowed
+                } else {
+                    Unknown
+                }
+            }
+            LicenseExpr::And(exprs) => {
+                let status = exprs.iter().map(|e| self.evaluate(e)).collect::<Vec<_>>();
+                if status.contains(&Denied) {
+                    Denied
+                } else if status.contains(&Unknown) {
+                    Unknown
+                } else {
+                    Allowed
+                }
+            }
+            LicenseExpr::Or(exprs) => {
+                let status = exprs.iter().map(|e| self.evaluate(e)).collect::<Vec<_>>();
+                if status.contains(&Allowed) {
+                    Allowed
+                } else if status.contains(&Unknown) {
+                    Unknown
+                } else {
+                    Denied
+                }
+            }
+        }
+    }
+
+    /// Resolve the dependencies of the crate containing the given path with
+    /// `cargo metadata`, and check their licenses.
+    pub fn check_crate(&self, path: &Path) -> Result<Vec<CheckInfo>> {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            path.parent()
+                .ok_or_else(|| Error::OrphanFilePath(path.to_path_buf()))?
+        };
+        let manifest = utils::find_in_ancestors(dir, "Cargo.toml")
+            .ok_or_else(|| Error::ManifestNotFound(dir.to_path_buf()))?;
+        let output = Command::new("cargo")
+            .args(["metadata", "--format-version", "1", "--manifest-path"])
+            .arg(&manifest)
+            .output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(Error::CargoMetadata(manifest, stderr).into());
+        }
+        let metadata: Metadata = serde_json::from_slice(&output.stdout)?;
+        let dependencies = metadata
+            .packages
+            .into_iter()
+            .filter(|pkg| !metadata.workspace_members.contains(&pkg.id))
+            .collect::<Vec<_>>();
+
+        // `cargo metadata` creates the lock file if there wasn't one.
+        let lock = manifest
+            .parent()
+            .and_then(|dir| utils::find_in_ancestors(dir, "Cargo.lock"))
+            .map(|lock| TomlFile::read(&lock))
+            .transpose()?;
+        Ok(self.check_packages(&dependencies, lock.as_ref(), &manifest))
+    }
+
+    /// Check the licenses of packages, the findings are located at the packages in
+    /// `Cargo.lock` if given, or the manifest otherwise.
+    fn check_packages(
+        &self,
+        packages: &[Package],
+        lock: Option<&TomlFile>,
+        manifest: &Path,
+    ) -> Vec<CheckInfo> {
+        let mut result = vec![];
+        for pkg in packages {
+            let name = format!("{} {}", pkg.name, pkg.version);
+            let (defect_name, help, license) = match &pkg.license {
+                Some(license) => match license.parse::<LicenseExpr>() {
+                    Ok(expr) => match self.evaluate(&expr) {
+                        LicenseStatus::Allowed => continue,
+                        LicenseStatus::Denied => (
+                            "denied-license",
+                            format!("license `{license}` of `{name}` is denied"),
+                            license.clone(),
+                        ),
+                        LicenseStatus::Unknown => (
+                            "unknown-license",
+                            format!("license `{license}` of `{name}` is not in the allowed list"),
+                            license.clone(),
+                        ),
+                    },
+                    Err(e) => ("unknown-license", e.to_string(), license.clone()),
+                },
+                None => {
+                    let help = match &pkg.license_file {
+                        Some(file) => format!(
+                            "`{name}` only has a license file '{}', which should be reviewed \
+                            manually",
+                            file.display()
+                        ),
+                        None => format!("`{name}` does not have a license"),
+                    };
+                    ("unknown-license", help, String::new())
+                }
+            };
+            let mut info = match lock {
+                Some(lock) => {
+                    let line = lock.lock_line(&pkg.name, &pkg.version);
+                    lock.check_info(SupportedTool::License, line, defect_name, help)
+                }
+                None => CheckInfo {
+                    file_path: Some(manifest.to_path_buf()),
+                    defect_name: defect_name.to_string(),
+                    tool: SupportedTool::License,
+                    help_info: help,
+                    ..Default::default()
+                },
+            };
+            info.additional_help_info = license;
+            result.push(info);
+        }
+        result
+    }
+}
+
+impl BuiltinChecker for LicenseChecker {
+    fn from_rules(rule_cfg: &RulesCfg, checked_tools: &[&CheckTool]) -> Result<Option<Self>> {
+        let enabled =
+            rule_cfg.license.is_some() || uses_tool(checked_tools, SupportedTool::License);
+        Ok(enabled.then(|| LicenseChecker::new(rule_cfg.license.clone().unwrap_or_default())))
+    }
+
+    fn tool(&self) -> SupportedTool {
+        SupportedTool::License
+    }
+
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)> {
+        Ok((self.check_crate(ctx.path)?, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LicenseChecker, LicenseExpr, LicenseStatus};
+    use crate::parser::LicenseCfg;
+
+    fn license(id: &str) -> LicenseExpr {
+        LicenseExpr::License {
+            id: id.to_string(),
+            exception: None,
+        }
+    }
+
+    #[test]
+    fn parse_license_exprs() {
+        assert_eq!(
+            "MIT OR Apache-2.0".parse::<LicenseExpr>().unwrap(),
+            LicenseExpr::Or(vec![license("MIT"), license("Apache-2.0")])
+        );
+        assert_eq!(
+            "MIT/Apache-2.0".parse::<LicenseExpr>().unwrap(),
+            LicenseExpr::Or(vec![license("MIT"), license("Apache-2.0")])
+        );
+        let expr = "(MIT OR Apache-2.0) AND Apache-2.0 WITH LLVM-exception OR Zlib"
+            .parse::<LicenseExpr>()
+            .unwrap();
+        assert_eq!(
+            expr,
+            LicenseExpr::Or(vec![
+                LicenseExpr::And(vec![
+                    LicenseExpr::Or(vec![license("MIT"), license("Apache-2.0")]),
+                    LicenseExpr::License {
+                        id: "Apache-2.0".to_string(),
+                        exception: Some("LLVM-exception".to_string()),
+                    },
+                ]),
+                license("Zlib"),
+            ])
+        );
+        assert_eq!(
+            expr.to_string(),
+            "((MIT OR Apache-2.0) AND Apache-2.0 WITH LLVM-exception) OR Zlib"
+        );
+        assert!("MIT OR".parse::<LicenseExpr>().is_err());
+        assert!("(MIT".parse::<LicenseExpr>().is_err());
+        assert!("MIT Apache-2.0".parse::<LicenseExpr>().is_err());
+    }
+
+    #[test]
+    fn evaluate_licenses() {
+        let checker = LicenseChecker::new(LicenseCfg {
+            allowed: ["MIT", "Apache-2.0"].map(String::from).to_vec(),
+            denied: ["GPL-3.0", "Apache-2.0 WITH Classpath-exception-2.0"]
+                .map(String::from)
+                .to_vec(),
+        });
+        let eval = |expr: &str| checker.evaluate(&expr.parse().unwrap());
+        assert_eq!(eval("MIT"), LicenseStatus::Allowed);
+        assert_eq!(eval("mit"), LicenseStatus::Allowed);
+        assert_eq!(eval("Zlib"), LicenseStatus::Unknown);
+        assert_eq!(eval("MIT OR GPL-3.0"), LicenseStatus::Allowed);
+        assert_eq!(eval("MIT AND GPL-3.0"), LicenseStatus::Denied);
+        assert_eq!(eval("MIT AND Zlib"), LicenseStatus::Unknown);
+        assert_eq!(eval("GPL-3.0 OR Zlib"), LicenseStatus::Unknown);
+        assert_eq!(
+            eval("Apache-2.0 WITH LLVM-exception"),
+            LicenseStatus::Allowed
+        );
+        assert_eq!(
+            eval("Apache-2.0 WITH Classpath-exception-2.0"),
+            LicenseStatus::Denied
+        );
+
+        // everything not denied is allowed without an allowed list
+        let checker = LicenseChecker::new(LicenseCfg {
+            allowed: vec![],
+            denied: vec!["GPL-3.0".to_string()],
+        });
+        assert_eq!(
+            checker.evaluate(&"Zlib".parse().unwrap()),
+            LicenseStatus::Allowed
+        );
+    }
+}
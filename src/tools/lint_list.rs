@@ -0,0 +1,363 @@
+//! Validation of the lints used by coding guidelines, against the lints that are
+//! known by the installed toolchain.
+//!
+//! Known lints are listed with `rustc -W help` and `clippy-driver -W help`,
+//! lints that are not in the list will then be passed to the compiler, in order to
+//! find out whether they were renamed, removed, or simply don't exist.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::SupportedTool;
+use crate::locale::{Lang, Msg};
+use crate::parser::{Guideline, GuidelineID};
+use crate::{utils, Result};
+
+lazy_static! {
+    static ref RE_RENAMED: Regex =
+        Regex::new(r"lint `(?P<old>[^`]+)` has been renamed to `(?P<new>[^`]+)`").unwrap();
+    static ref RE_REMOVED: Regex =
+        Regex::new(r"lint `(?P<name>[^`]+)` has been removed: (?P<reason>.*)").unwrap();
+    static ref RE_UNKNOWN: Regex = Regex::new(r"unknown lint: `(?P<name>[^`]+)`").unwrap();
+    static ref RE_DID_YOU_MEAN: Regex = Regex::new(r"did you mean: `(?P<name>[^`]+)`").unwrap();
+}
+
+/// The maximum number of suggestions for each unknown lint.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Lint checks and lint groups known by a tool.
+#[derive(Debug, Default)]
+pub struct KnownLints {
+    names: HashSet<String>,
+}
+
+impl KnownLints {
+    /// Get the list of known lints by running `<tool> -W help`.
+    pub fn query(tool: SupportedTool) -> Result<Self> {
+        let output = Command::new(program(tool)).args(["-W", "help"]).output()?;
+        Ok(Self::parse(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Parse the output of `-W help`, which lists lints in tables like:
+    ///
+    /// ```text
+    /// Lint checks provided by rustc:
+    ///
+    ///                  name  default  meaning
+    ///                  ----  -------  -------
+    ///    non-snake-case  warn     variables, methods, functions, lifetime parameters and modules should have snake case names
+    /// ```
+    pub fn parse(help: &str) -> Self {
+        let mut names = HashSet::new();
+        let mut in_table = false;
+        for line in help.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("Lint checks") || trimmed.starts_with("Lint groups") {
+                in_table = true;
+                continue;
+            }
+            let Some(name) = trimmed.split_whitespace().next() else {
+                continue;
+            };
+            if in_table && name != "name" && !name.starts_with('-') {
+                names.insert(normalize(name));
+            }
+        }
+        KnownLints { names }
+    }
+
+    pub fn contains(&self, lint: &str) -> bool {
+        self.names.contains(&normalize(lint))
+    }
+
+    /// Find the known lints with the closest names, the closest one comes first.
+    pub fn closest(&self, lint: &str) -> Vec<String> {
+        let lint = normalize(lint);
+        // the `clippy::` prefix should not make longer names more tolerant
+        let max_distance = (lint.trim_start_matches("clippy::").len() / 4).max(2);
+        let mut candidates = self
+            .names
+            .iter()
+            .map(|name| (utils::edit_distance(&lint, name), name))
+            .filter(|(dist, _)| *dist <= max_distance)
+            .collect::<Vec<_>>();
+        candidates.sort();
+        candidates
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, name)| name.clone())
+            .collect()
+    }
+}
+
+/// What's wrong with a lint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintStatus {
+    Unknown,
+    Renamed(String),
+    /// Removed, or deprecated, along with the reason.
+    Removed(String),
+}
+
+/// A lint used by a guideline that is not known by the toolchain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    pub guideline: GuidelineID,
+    pub tool: SupportedTool,
+    pub ident: String,
+    pub status: LintStatus,
+    /// The closest names of known lints, the most relevant one comes first.
+    pub suggestions: Vec<String>,
+}
+
+impl LintIssue {
+    /// Render a human readable message in the given language.
+    pub fn render(&self, lang: &Lang) -> String {
+        let id = self.guideline.to_catalogue_string();
+        let mut msg = match &self.status {
+            LintStatus::Unknown => Msg::UnknownLint(self.tool, &self.ident),
+            LintStatus::Renamed(new) => Msg::RenamedLint(self.tool, &self.ident, new),
+            LintStatus::Removed(reason) => Msg::RemovedLint(self.tool, &self.ident, reason),
+        }
+        .text(lang);
+        if self.status == LintStatus::Unknown && !self.suggestions.is_empty() {
+            let names = self
+                .suggestions
+                .iter()
+                .map(|s| format!("`{s}`"))
+                .collect::<Vec<_>>();
+            msg.push_str(&Msg::DidYouMean(&names.join(", ")).text(lang));
+        }
+        format!("{id}: {msg}")
+    }
+}
+
+impl Display for LintIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(&Lang::default()))
+    }
+}
+
+/// Validates lints of guidelines, known lints of each tool are queried only once.
+#[derive(Debug, Default)]
+pub struct LintValidator {
+    known: HashMap<SupportedTool, KnownLints>,
+}
+
+impl LintValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Find out the lints of clippy and rustc that are not known by the toolchain.
+    ///
+    /// Idents of other tools are not lints, so they are not validated.
+    pub fn validate<'a, 'g: 'a, I>(&mut self, guidelines: I) -> Result<Vec<LintIssue>>
+    where
+        I: IntoIterator<Item = &'a Guideline<'g>>,
+    {
+        let mut unknown: Vec<(GuidelineID, SupportedTool, &str)> = vec![];
+        for gl in guidelines {
+            for tool in &gl.tool {
+                if !matches!(tool.name, SupportedTool::Clippy | SupportedTool::Rustc) {
+                    continue;
+                }
+                let known = self.known_lints(tool.name)?;
+                if !known.contains(tool.ident) {
+                    unknown.push((gl.id.clone(), tool.name, tool.ident));
+                }
+            }
+        }
+
+        let mut status_map = HashMap::new();
+        for tool in [SupportedTool::Clippy, SupportedTool::Rustc] {
+            let idents = unknown
+                .iter()
+                .filter(|(_, t, _)| *t == tool)
+                .map(|(_, _, ident)| *ident)
+                .collect::<HashSet<_>>();
+            if !idents.is_empty() {
+                status_map.insert(tool, probe(tool, &idents)?);
+            }
+        }
+
+        let mut issues = vec![];
+        for (guideline, tool, ident) in unknown {
+            let (status, hint) = status_map
+                .get(&tool)
+                .and_then(|map| map.get(&normalize(ident)))
+                .cloned()
+                .unwrap_or((LintStatus::Unknown, None));
+            let suggestions = match &status {
+                LintStatus::Renamed(new) => vec![new.clone()],
+                LintStatus::Removed(_) => vec![],
+                LintStatus::Unknown => {
+                    let mut suggestions = hint.into_iter().collect::<Vec<_>>();
+                    for name in self.known_lints(tool)?.closest(ident) {
+                        if suggestions.iter().all(|s| normalize(s) != name) {
+                            suggestions.push(name);
+                        }
+                    }
+                    suggestions.truncate(MAX_SUGGESTIONS);
+                    suggestions
+                }
+            };
+            issues.push(LintIssue {
+                guideline,
+                tool,
+                ident: ident.to_string(),
+                status,
+                suggestions,
+            });
+        }
+        Ok(issues)
+    }
+
+    fn known_lints(&mut self, tool: SupportedTool) -> Result<&KnownLints> {
+        match self.known.entry(tool) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => Ok(entry.insert(KnownLints::query(tool)?)),
+        }
+    }
+}
+
+fn program(tool: SupportedTool) -> &'static str {
+    match tool {
+        SupportedTool::Clippy => "clippy-driver",
+        _ => "rustc",
+    }
+}
+
+fn normalize(lint: &str) -> String {
+    lint.trim().replace('-', "_").to_lowercase()
+}
+
+/// Ask the compiler about lints by compiling an empty crate with them enabled,
+/// and collect the status of each lint, along with the name it suggested.
+fn probe(
+    tool: SupportedTool,
+    idents: &HashSet<&str>,
+) -> Result<HashMap<String, (LintStatus, Option<String>)>> {
+    // probes could run at the same time, in this process or others,
+    // so each of them compiles into its own directory
+    static PROBES: AtomicUsize = AtomicUsize::new(0);
+    let out_dir = std::env::temp_dir().join(format!(
+        "eunomia_lint_probe_{}_{}",
+        std::process::id(),
+        PROBES.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&out_dir)?;
+    let output = Command::new(program(tool))
+        .args(["--crate-type", "lib", "--emit", "metadata"])
+        .args(["--crate-name", "eunomia_lint_probe", "--out-dir"])
+        .arg(&out_dir)
+        .args(idents.iter().map(|ident| format!("-W{ident}")))
+        .arg("-")
+        .stdin(Stdio::null())
+        .output();
+    // the output is only written to get the diagnostics, and failing to clean it up
+    // doesn't affect them
+    let _ = fs::remove_dir_all(&out_dir);
+    Ok(parse_probe_output(&String::from_utf8_lossy(
+        &output?.stderr,
+    )))
+}
+
+fn parse_probe_output(stderr: &str) -> HashMap<String, (LintStatus, Option<String>)> {
+    let mut result = HashMap::new();
+    for section in stderr.split("\n\n") {
+        if let Some(cap) = RE_RENAMED.captures(section) {
+            let status = LintStatus::Renamed(cap["new"].to_string());
+            result.insert(normalize(&cap["old"]), (status, None));
+        } else if let Some(cap) = RE_REMOVED.captures(section) {
+            let status = LintStatus::Removed(cap["reason"].trim().to_string());
+            result.insert(normalize(&cap["name"]), (status, None));
+        } else if let Some(cap) = RE_UNKNOWN.captures(section) {
+            let hint = RE_DID_YOU_MEAN
+                .captures(section)
+                .map(|c| c["name"].to_string());
+            result.insert(normalize(&cap["name"]), (LintStatus::Unknown, hint));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_probe_output, KnownLints, LintStatus};
+
+    #[test]
+    fn parse_known_lints() {
+        let help = "Available lint options:
+    -W <foo>           Warn about <foo>
+
+Lint checks provided by rustc:
+
+                 name  default  meaning
+                 ----  -------  -------
+       non-snake-case  warn     variables should have snake case names
+
+Lint checks loaded by this crate:
+
+                    name  default  meaning
+                    ----  -------  -------
+ clippy::bool-comparison  warn     comparing a variable to a boolean, e.g., `if x == true` or `if x != true`
+
+Lint groups loaded by this crate:
+
+           name  sub-lints
+           ----  ---------
+    clippy::all  clippy::bool-comparison
+";
+        let known = KnownLints::parse(help);
+        assert_eq!(known.names.len(), 3);
+        assert!(known.contains("non_snake_case"));
+        assert!(known.contains("clippy::bool_comparison"));
+        assert!(known.contains("clippy::all"));
+        assert!(!known.contains("-W"));
+        assert_eq!(
+            known.closest("clippy::bool_comparsion"),
+            ["clippy::bool_comparison"]
+        );
+        assert!(known.closest("totally_different").is_empty());
+    }
+
+    #[test]
+    fn parse_probe() {
+        let stderr = "warning: lint `clippy::double_neg` has been renamed to `double_negations`
+  |
+  = help: use the new name `double_negations`
+
+warning[E0602]: unknown lint: `clippy::bool_comparsion`
+  |
+  = help: did you mean: `clippy::bool_comparison`
+
+warning: lint `box_pointers` has been removed: it does not detect other kinds of allocations
+  |
+  = note: requested on the command line with `-W box_pointers`
+
+warning: 3 warnings emitted
+";
+        let res = parse_probe_output(stderr);
+        assert_eq!(res.len(), 3);
+        assert_eq!(
+            res["clippy::double_neg"],
+            (LintStatus::Renamed("double_negations".into()), None)
+        );
+        assert_eq!(
+            res["clippy::bool_comparsion"],
+            (LintStatus::Unknown, Some("clippy::bool_comparison".into()))
+        );
+        assert_eq!(
+            res["box_pointers"].0,
+            LintStatus::Removed("it does not detect other kinds of allocations".into())
+        );
+    }
+}
@@ -4,20 +4,44 @@
 use std::collections::HashMap;
 use std::process::{Command, Output};
 
-use regex::Regex;
-
-use super::{Checker, FilteredOutput, SupportedTool};
+use super::diagnostics::{self, Diagnostic};
+use super::{Checker, FilteredOutput};
 use crate::parser::CheckInfo;
-use crate::{utils, Error, Result};
-use std::path::PathBuf;
+use crate::{Error, Result};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct LintsOpt {
     pub is_clippy: bool,
     pub use_cargo: bool,
     pub lint_names: Vec<String>,
     pub envs: HashMap<String, String>,
     pub path: PathBuf,
+    /// Emit diagnostics as JSON, which contain suggestions that can be applied.
+    pub json_output: bool,
+}
+
+impl LintsOpt {
+    /// The directory where the tool runs in.
+    pub fn working_dir(&self) -> Result<&Path> {
+        if self.path.is_dir() {
+            Ok(self.path.as_path())
+        } else {
+            self.path
+                .parent()
+                .ok_or(Error::OrphanFilePath(self.path.clone()).into())
+        }
+    }
+
+    /// Run the tool with JSON output, and collect the lint diagnostics it emits.
+    pub fn diagnostics(&self) -> Result<Vec<Diagnostic>> {
+        let opt = LintsOpt {
+            json_output: true,
+            ..self.clone()
+        };
+        let output = opt.check()?;
+        Ok(diagnostics::parse_diagnostics(&output, opt.working_dir()?))
+    }
 }
 
 impl Checker for LintsOpt {
@@ -26,7 +50,11 @@ impl Checker for LintsOpt {
         let mut env = self.envs.clone();
         let program = match (self.is_clippy, self.use_cargo) {
             (true, true) => {
-                args.extend(["clippy".into(), "--".into()]);
+                args.push("clippy".into());
+                if self.json_output {
+                    args.push("--message-format=json".into());
+                }
+                args.push("--".into());
                 config_lints_for_args_or_env(true, &self.lint_names, &mut args, &mut env);
                 "cargo"
             }
@@ -36,6 +64,9 @@ impl Checker for LintsOpt {
             }
             (false, true) => {
                 args.push("check".into());
+                if self.json_output {
+                    args.push("--message-format=json".into());
+                }
                 config_lints_for_args_or_env(false, &self.lint_names, &mut args, &mut env);
                 "cargo"
             }
@@ -53,13 +84,10 @@ impl Checker for LintsOpt {
                 "rustc"
             }
         };
-        let cur_dir = if self.path.is_dir() {
-            self.path.as_path()
-        } else {
-            self.path
-                .parent()
-                .ok_or(Error::OrphanFilePath(self.path.clone()))?
-        };
+        if self.json_output && !self.use_cargo {
+            args.push("--error-format=json".into());
+        }
+        let cur_dir = self.working_dir()?;
         let output = Command::new(program)
             .current_dir(cur_dir)
             .args(args)
@@ -67,7 +95,16 @@ impl Checker for LintsOpt {
             .output()?;
         Ok(output)
     }
+    /// Split the output into diagnostics, which are the lines of JSON of the lint
+    /// diagnostics if [`LintsOpt::json_output`] is set, or the rendered ones otherwise.
     fn filter_output(&self, output: &Output) -> FilteredOutput {
+        if self.json_output {
+            // cargo prints the diagnostics on stdout, and rustc prints them on stderr
+            return FilteredOutput {
+                stdout: diagnostics::lint_messages(&String::from_utf8_lossy(&output.stdout)),
+                stderr: diagnostics::lint_messages(&String::from_utf8_lossy(&output.stderr)),
+            };
+        }
         // rustc_lint result is usually stderr type, so we keep stdout empty.
         let stdout = Vec::new();
 
@@ -91,107 +128,21 @@ impl Checker for LintsOpt {
         FilteredOutput { stdout, stderr }
     }
 
-    /// Generalize clippy output to [`CheckInfo`] struct.
-    ///
-    /// Clippy output is contructed using similar fashion,
-    /// where the first line contains help information summary,
-    /// the line followed by '-->' contains source file location, start line and column number,
-    /// and following is the code, etc.
-    ///
-    /// # Example
-    ///
-    /// ```rust,ignore
-    /// let raw_result: "error: range is out of bounds
-    ///  --> src/clippy.rs:12:19
-    ///   |
-    ///12 |     let _ = &x[2..9];
-    ///   |                   ^
-    ///   |
-    ///   = help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#out_of_bounds_indexing
-    ///   = note: `#[deny(clippy::out_of_bounds_indexing)]` on by default"
+    /// Generalize a diagnostic of clippy or rustc to [`CheckInfo`] struct, which is
+    /// a line of JSON filtered from their output when running with
+    /// [`LintsOpt::json_output`], such as:
     ///
-    /// let info = ClippyOpt::check_info(raw_result).unwrap();
-    /// assert_eq!(info.help_info, Some("range is out of bounds".into()));
-    /// assert_eq!(info.defect_name, "out_of_bounds_indexing".into());
+    /// ```text
+    /// {"message":"range is out of bounds","code":{"code":"clippy::out_of_bounds_indexing",...},
+    /// "level":"error","spans":[{"file_name":"src/lints.rs","line_start":12,...}],...}
     /// ```
+    ///
+    /// Its file paths are resolved against the directory the tool runs in,
+    /// see [`diagnostics::parse_diagnostics`].
     fn check_info(&self, raw_result: &str) -> Result<CheckInfo> {
-        let mut lines = raw_result.trim().lines();
-
-        // First line contains help message
-        let help_info = lines
-            .next()
-            .and_then(|s| s.split_once(':'))
-            .map_or(String::new(), |(_, msg)| msg.trim().into());
-
-        // Second line might contains position info
-        let (file_path, begin_line, column) = match lines.next().map(|s| {
-            s.trim()
-                .trim_start_matches("--> ")
-                .split(':')
-                .collect::<Vec<_>>()
-        }) {
-            Some(v) if v.len() == 3 => (
-                Some(PathBuf::from(v[0])),
-                v[1].trim().parse::<usize>().ok(),
-                v[2].trim().parse::<usize>().ok(),
-            ),
-            _ => (None, None, None),
-        };
-
-        let mut code_string = String::new();
-        let mut extracted_info = ExtractedCheckInfo::default();
-        let help_lint_name_regex = if self.is_clippy {
-            Some(Regex::new(r"rust-clippy/master/index.html#(\w+)")?)
-        } else {
-            None
-        };
-        let note_lint_name_regex = Some(Regex::new(r"(`-(?:W|D) (.*?)`)|(\((.*?)\)\])")?);
-        // A flag to mark whether the program is looking for error code snippets or not,
-        // otherwise it's possible that a suggestion snippet will be taken by mistake.
-        let mut looking_for_code = true;
-
-        for line in lines {
-            let trimmed = line.trim();
-            // FIXME: this method is dumb, it's expensive and could cause false positive if
-            // a string variable has '= help: ' inside of it.
-            if trimmed.contains("= help: ") {
-                looking_for_code = false;
-                update_info_from_help_or_note(
-                    &mut extracted_info,
-                    trimmed,
-                    help_lint_name_regex.as_ref(),
-                );
-            } else if trimmed.contains("= note: ") {
-                looking_for_code = false;
-                update_info_from_help_or_note(
-                    &mut extracted_info,
-                    trimmed,
-                    note_lint_name_regex.as_ref(),
-                );
-            } else if looking_for_code {
-                if let Some(code) = maybe_code_line(trimmed) {
-                    code_string.push_str(code);
-                }
-            }
-        }
-
-        Ok(CheckInfo {
-            file_path,
-            defect_name: extracted_info.defect_name,
-            tool: if self.is_clippy {
-                SupportedTool::Clippy
-            } else {
-                SupportedTool::Rustc
-            },
-            begin_line,
-            column,
-            code_string,
-            help_info,
-            additional_help_info: extracted_info.additional_help,
-            // FIXME: currently we haven't figured out a way to get the end line.
-            // TODO: get guideline relation map, and put them here.
-            ..Default::default()
-        })
+        let diag = diagnostics::parse_message(raw_result, self.working_dir()?)
+            .ok_or_else(|| Error::ParseDiagnostic(raw_result.to_string()))?;
+        Ok(diag.check_info())
     }
 }
 
@@ -213,14 +164,6 @@ fn config_lints_for_args_or_env(
     }
 }
 
-#[derive(Default)]
-/// Subset of [`CheckInfo`], that could be extracted from 'help' and 'note' section
-/// of clippy output.
-struct ExtractedCheckInfo {
-    defect_name: String,
-    additional_help: String,
-}
-
 /// The first line of lints result is inconsistent depending on different scenario,
 /// it could be the result straight away such as `warning: xxx` or `error: xxx`.
 /// Or, it could be notification such as `    Checking crate xxx` or `    Blocking waiting for ...`
@@ -239,63 +182,3 @@ fn skip_first_line_when_possible(s: &str) -> Vec<&str> {
     result.extend(lines);
     result
 }
-
-fn update_info_from_help_or_note(info: &mut ExtractedCheckInfo, line: &str, re: Option<&Regex>) {
-    info.additional_help
-        .push_str(&format!("{}\n", line.trim_start_matches("= ")));
-    if let Some(re) = re {
-        if let Some(lint_name) = utils::regex_utils::capture_last(re, line) {
-            info.defect_name = lint_name.trim().into();
-        }
-    }
-}
-
-fn maybe_code_line(s: &str) -> Option<&str> {
-    if let Some((num, code)) = s.split_once(" | ") {
-        if num.trim().parse::<usize>().is_ok() {
-            return Some(code);
-        }
-    }
-    None
-}
-
-#[cfg(test)]
-mod tests {
-    use super::utils::regex_utils;
-    use super::{maybe_code_line, Regex};
-
-    #[test]
-    fn test_extract_lint_name_from_note() {
-        let note_str = "= note: `#[warn(clippy::bool_comparison)]` on by default";
-        let note_str_1 = "= note: requested on the command line with `-W non-ascii-idents`";
-
-        let note_regex = Regex::new(r"(`-(?:W|D) (.*?)`)|(\((.*?)\)\])").unwrap();
-
-        let name = regex_utils::capture_last(&note_regex, note_str);
-        let name_1 = regex_utils::capture_last(&note_regex, note_str_1);
-
-        assert_eq!(name, Some("clippy::bool_comparison"));
-        assert_eq!(name_1, Some("non-ascii-idents"));
-    }
-
-    #[test]
-    fn test_extract_lint_name_from_help() {
-        let help_regex = Regex::new(r"rust-clippy/master/index.html#(?P<name>(\w+))").unwrap();
-        let help_str = "= help: for further information visit \
-        https://rust-lang.github.io/rust-clippy/master/index.html#bool_comparison";
-
-        let name = regex_utils::get_named_match("name", &help_regex, help_str);
-        assert_eq!(name, Some("bool_comparison".into()));
-    }
-
-    #[test]
-    fn test_extract_code() {
-        let code_line_1 = "18 |     if flag == true {}";
-        let code_line_2 = "   |        ^^^^^^^^^^^^ help: try simplifying it as shown: `flag`";
-        let code_line_3 = "   |     ";
-
-        assert_eq!(maybe_code_line(code_line_1), Some("    if flag == true {}"));
-        assert_eq!(maybe_code_line(code_line_2), None);
-        assert_eq!(maybe_code_line(code_line_3), None);
-    }
-}
@@ -0,0 +1,435 @@
+//! A checker of `Cargo.toml` and `Cargo.lock`, enforcing policies about package metadata
+//! and dependencies, see [`ManifestCfg`] for the configurable ones.
+//!
+//! Findings are located at the line of the manifest where the problem is, such as the
+//! line declaring a dependency, or the `[package]` header for missing fields.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use semver::{Op, VersionReq};
+use toml::{Table, Value};
+
+use super::{uses_tool, BuiltinChecker, CheckContext, SupportedTool};
+use crate::parser::{CheckInfo, CheckTool, CodeSpan, ManifestCfg, OutputSection, RulesCfg};
+use crate::{utils, Error, Result};
+
+/// Tables of dependencies, which could also be nested in `[target.<cfg>]`.
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// The text of a TOML file along with its parsed table.
+pub(super) struct TomlFile {
+    pub path: PathBuf,
+    pub content: String,
+    pub table: Table,
+}
+
+/// A package in `Cargo.lock`.
+pub(super) struct LockPackage<'a> {
+    pub name: &'a str,
+    pub version: &'a str,
+    /// Where the package comes from, which is `None` for local packages.
+    pub source: Option<&'a str>,
+}
+
+impl TomlFile {
+    pub(super) fn read(path: &Path) -> Result<Self> {
+        let content = utils::read_to_string(path)?;
+        let table = content
+            .parse::<Table>()
+            .map_err(|e| Error::ParseManifest(path.to_path_buf(), e.message().to_string()))?;
+        Ok(TomlFile {
+            path: path.to_path_buf(),
+            content,
+            table,
+        })
+    }
+
+    /// Find the (1-based) line of a key in a table, or the line of the table's header
+    /// if the key could not be found, or the first line if neither could be found.
+    fn line_of(&self, table: &[&str], key: Option<&str>) -> usize {
+        let mut header_line = None;
+        let mut current: Vec<String> = vec![];
+        for (idx, line) in self.content.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                current = parse_header(trimmed);
+                if current == table {
+                    header_line = Some(idx + 1);
+                }
+                // a dotted header such as `[dependencies.foo]`
+                if let Some(key) = key {
+                    if current.len() == table.len() + 1
+                        && current[..table.len()] == *table
+                        && current[table.len()] == key
+                    {
+                        return idx + 1;
+                    }
+                }
+                continue;
+            }
+            if let Some(key) = key {
+                if current == table && line_key(trimmed) == Some(key) {
+                    return idx + 1;
+                }
+            }
+        }
+        header_line.unwrap_or(1)
+    }
+
+    /// Packages of a `Cargo.lock`.
+    pub(super) fn lock_packages(&self) -> Vec<LockPackage<'_>> {
+        self.table
+            .get("package")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|pkg| {
+                Some(LockPackage {
+                    name: pkg.get("name")?.as_str()?,
+                    version: pkg.get("version")?.as_str()?,
+                    source: pkg.get("source").and_then(Value::as_str),
+                })
+            })
+            .collect()
+    }
+
+    /// Find the line of a package in `Cargo.lock`, which looks like:
+    ///
+    /// ```toml
+    /// [[package]]
+    /// name = "serde"
+    /// version = "1.0.0"
+    /// ```
+    pub(super) fn lock_line(&self, name: &str, version: &str) -> usize {
+        let name_line = format!("name = \"{name}\"");
+        let version_line = format!("version = \"{version}\"");
+        let lines = self.content.lines().collect::<Vec<_>>();
+        lines
+            .windows(2)
+            .position(|w| w[0].trim() == name_line && w[1].trim() == version_line)
+            .map_or(1, |idx| idx + 1)
+    }
+
+    pub(super) fn check_info(
+        &self,
+        tool: SupportedTool,
+        line: usize,
+        defect_name: &str,
+        help_info: String,
+    ) -> CheckInfo {
+        CheckInfo {
+            file_path: Some(self.path.clone()),
+            defect_name: defect_name.to_string(),
+            tool,
+            begin_line: Some(line),
+            column: Some(1),
+            code_string: self
+                .content
+                .lines()
+                .nth(line - 1)
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            help_info,
+            span: Some(CodeSpan::lines(line, line)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Split a table header like `[target.'cfg(unix)'.dependencies]` into its keys.
+fn parse_header(header: &str) -> Vec<String> {
+    let inner = header.trim_start_matches('[').trim_end_matches(']');
+    let mut keys = vec![];
+    let mut key = String::new();
+    let mut quote = None;
+    for c in inner.chars() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('.', None) => keys.push(std::mem::take(&mut key).trim().to_string()),
+            _ => key.push(c),
+        }
+    }
+    keys.push(key.trim().to_string());
+    keys
+}
+
+/// Get the key of a line like `foo = "1"`, `"foo" = "1"` or `foo.workspace = true`.
+fn line_key(line: &str) -> Option<&str> {
+    let end = line.find(['=', '.'])?;
+    Some(line[..end].trim().trim_matches(['"', '\'']))
+}
+
+/// A dependency declared in `Cargo.toml`.
+struct Dependency<'a> {
+    /// Keys of the table declaring it, such as `["dependencies"]`.
+    table: Vec<&'a str>,
+    /// The key of the dependency, which could be renamed from the crate's name.
+    key: &'a str,
+    /// Name of the depended crate.
+    name: &'a str,
+    value: &'a Value,
+}
+
+fn dependencies(manifest: &Table) -> Vec<Dependency<'_>> {
+    let mut tables: Vec<(Vec<&str>, &Table)> = vec![];
+    for name in DEPENDENCY_TABLES {
+        if let Some(Value::Table(t)) = manifest.get(*name) {
+            tables.push((vec![name], t));
+        }
+    }
+    if let Some(Value::Table(targets)) = manifest.get("target") {
+        for (cfg, target) in targets {
+            for name in DEPENDENCY_TABLES {
+                if let Some(Value::Table(t)) = target.get(*name) {
+                    tables.push((vec!["target", cfg, name], t));
+                }
+            }
+        }
+    }
+    if let Some(Value::Table(t)) = manifest
+        .get("workspace")
+        .and_then(|ws| ws.get("dependencies"))
+    {
+        tables.push((vec!["workspace", "dependencies"], t));
+    }
+
+    let mut result = vec![];
+    for (table, deps) in tables {
+        for (key, value) in deps {
+            let name = value.get("package").and_then(Value::as_str).unwrap_or(key);
+            result.push(Dependency {
+                table: table.clone(),
+                key,
+                name,
+                value,
+            });
+        }
+    }
+    result
+}
+
+/// The manifest checker.
+#[derive(Debug, Default)]
+pub struct ManifestChecker {
+    pub cfg: ManifestCfg,
+}
+
+impl ManifestChecker {
+    pub fn new(cfg: ManifestCfg) -> Self {
+        ManifestChecker { cfg }
+    }
+
+    /// Check the `Cargo.toml` of the crate containing the given path,
+    /// and the `Cargo.lock` next to it or in its workspace.
+    pub fn check_crate(&self, path: &Path) -> Result<Vec<CheckInfo>> {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            path.parent()
+                .ok_or_else(|| Error::OrphanFilePath(path.to_path_buf()))?
+        };
+        let manifest = utils::find_in_ancestors(dir, "Cargo.toml")
+            .ok_or_else(|| Error::ManifestNotFound(dir.to_path_buf()))?;
+        let lock = manifest
+            .parent()
+            .and_then(|dir| utils::find_in_ancestors(dir, "Cargo.lock"));
+        self.check(&manifest, lock.as_deref())
+    }
+
+    /// Check a `Cargo.toml`, along with its `Cargo.lock` if there's one.
+    pub fn check(&self, manifest: &Path, lock: Option<&Path>) -> Result<Vec<CheckInfo>> {
+        let manifest = TomlFile::read(manifest)?;
+        let mut result = self.check_package(&manifest);
+        result.extend(self.check_dependencies(&manifest));
+        if let Some(lock) = lock {
+            let lock = TomlFile::read(lock)?;
+            result.extend(self.check_lock(&lock, &manifest));
+        }
+        Ok(result)
+    }
+
+    fn check_package(&self, manifest: &TomlFile) -> Vec<CheckInfo> {
+        // virtual manifests of workspaces don't have packages
+        let Some(package) = manifest.table.get("package") else {
+            return vec![];
+        };
+        let line = manifest.line_of(&["package"], None);
+        self.cfg
+            .required_fields
+            .iter()
+            .filter(|field| {
+                package.get(field.as_str()).is_none()
+                    && !(field.as_str() == "license" && package.get("license-file").is_some())
+            })
+            .map(|field| {
+                let help = format!("field `{field}` is missing in `[package]`");
+                manifest.check_info(SupportedTool::Manifest, line, "missing-package-field", help)
+            })
+            .collect()
+    }
+
+    fn check_dependencies(&self, manifest: &TomlFile) -> Vec<CheckInfo> {
+        let mut result = vec![];
+        for dep in dependencies(&manifest.table) {
+            let line = manifest.line_of(&dep.table, Some(dep.key));
+            let name = dep.name;
+            let version = match dep.value {
+                Value::String(v) => Some(v.as_str()),
+                other => other.get("version").and_then(Value::as_str),
+            };
+            if version.is_some_and(has_wildcard) {
+                let help = format!("dependency `{name}` has a wildcard version requirement");
+                result.push(manifest.check_info(
+                    SupportedTool::Manifest,
+                    line,
+                    "wildcard-dependency",
+                    help,
+                ));
+            }
+            if dep.value.get("git").is_some() && dep.value.get("rev").is_none() {
+                let help = format!("git dependency `{name}` is not pinned with a `rev`");
+                result.push(manifest.check_info(
+                    SupportedTool::Manifest,
+                    line,
+                    "unpinned-git-dependency",
+                    help,
+                ));
+            }
+            if self.cfg.banned_crates.iter().any(|c| c == name) {
+                let help = format!("crate `{name}` is banned");
+                result.push(manifest.check_info(
+                    SupportedTool::Manifest,
+                    line,
+                    "banned-crate",
+                    help,
+                ));
+            } else if !self.cfg.allowed_crates.is_empty()
+                && !self.cfg.allowed_crates.iter().any(|c| c == name)
+            {
+                let help = format!("crate `{name}` is not in the list of allowed crates");
+                result.push(manifest.check_info(
+                    SupportedTool::Manifest,
+                    line,
+                    "disallowed-crate",
+                    help,
+                ));
+            }
+        }
+        result
+    }
+
+    fn check_lock(&self, lock: &TomlFile, manifest: &TomlFile) -> Vec<CheckInfo> {
+        let packages = lock.lock_packages();
+        let direct = dependencies(&manifest.table)
+            .into_iter()
+            .map(|dep| dep.name)
+            .collect::<Vec<_>>();
+
+        let mut result = vec![];
+        let mut versions: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for LockPackage { name, version, .. } in &packages {
+            versions.entry(name).or_default().push(version);
+            // direct dependencies are reported at the lines of `Cargo.toml`
+            if self.cfg.banned_crates.iter().any(|c| c == name) && !direct.contains(name) {
+                let help = format!("crate `{name}` is banned, but depended on indirectly");
+                result.push(lock.check_info(
+                    SupportedTool::Manifest,
+                    lock.lock_line(name, version),
+                    "banned-crate",
+                    help,
+                ));
+            }
+        }
+        for (name, versions) in versions {
+            if versions.len() < 2 || self.cfg.allowed_duplicates.iter().any(|c| c == name) {
+                continue;
+            }
+            let help = format!(
+                "crate `{name}` has multiple versions in `Cargo.lock`: {}",
+                versions.join(", ")
+            );
+            let line = lock.lock_line(name, versions[0]);
+            result.push(lock.check_info(
+                SupportedTool::Manifest,
+                line,
+                "duplicate-dependency",
+                help,
+            ));
+        }
+        result
+    }
+}
+
+impl BuiltinChecker for ManifestChecker {
+    fn from_rules(rule_cfg: &RulesCfg, checked_tools: &[&CheckTool]) -> Result<Option<Self>> {
+        let enabled =
+            rule_cfg.manifest.is_some() || uses_tool(checked_tools, SupportedTool::Manifest);
+        Ok(enabled.then(|| ManifestChecker::new(rule_cfg.manifest.clone().unwrap_or_default())))
+    }
+
+    fn tool(&self) -> SupportedTool {
+        SupportedTool::Manifest
+    }
+
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)> {
+        Ok((self.check_crate(ctx.path)?, None))
+    }
+}
+
+/// Whether any comparator of a version requirement contains a wildcard, such as `*`,
+/// `1.*` or `>=1, 0.x`.
+fn has_wildcard(req: &str) -> bool {
+    let parsed = VersionReq::parse(req).is_ok_and(|req| {
+        req.comparators.is_empty() || req.comparators.iter().any(|c| c.op == Op::Wildcard)
+    });
+    // `semver` drops the wildcards after operators, such as `>=1.*` parsed as `>=1`,
+    // and rejects `=*`, so the components of the comparators are checked as well
+    parsed
+        || req.split(',').any(|comparator| {
+            comparator
+                .trim()
+                .trim_start_matches(['=', '>', '<', '~', '^'])
+                .split(['-', '+'])
+                .next()
+                .is_some_and(|version| {
+                    version
+                        .split('.')
+                        .any(|part| matches!(part.trim(), "*" | "x" | "X"))
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_wildcard, line_key, parse_header};
+
+    #[test]
+    fn parse_toml_lines() {
+        assert_eq!(parse_header("[dependencies]"), ["dependencies"]);
+        assert_eq!(
+            parse_header("[target.'cfg(unix)'.dependencies]"),
+            ["target", "cfg(unix)", "dependencies"]
+        );
+        assert_eq!(
+            parse_header("[target.\"x86_64-pc-windows-gnu\".dev-dependencies]"),
+            ["target", "x86_64-pc-windows-gnu", "dev-dependencies"]
+        );
+        assert_eq!(line_key("serde = \"1\""), Some("serde"));
+        assert_eq!(line_key("serde.workspace = true"), Some("serde"));
+        assert_eq!(line_key("\"serde\" = { version = \"1\" }"), Some("serde"));
+        assert_eq!(line_key("# comment"), None);
+    }
+
+    #[test]
+    fn wildcard_requirements() {
+        for req in ["*", "1.*", "1.2.x", ">=1, 0.*", "=*", "=1.*", ">= 1.X, <2"] {
+            assert!(has_wildcard(req), "{req}");
+        }
+        for req in ["1", "^1.2", "~1", ">=1, <2", "=1.0.0-x.1", "0.3.0+x"] {
+            assert!(!has_wildcard(req), "{req}");
+        }
+    }
+}
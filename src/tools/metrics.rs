@@ -0,0 +1,517 @@
+//! A checker measuring the size and complexity of functions.
+//!
+//! The metrics of each function are:
+//!
+//! - `lines`: the number of lines from its signature to the end of its body.
+//! - `params`: the number of parameters, including `self`.
+//! - `nesting_depth`: the maximum depth of nested `if`, `match`, loops and closures.
+//! - `cyclomatic_complexity`: one plus the number of decision points, which are
+//!   `if`, `while` and `for` expressions, `match` arms besides the first one,
+//!   `&&`, `||` and `?` operators.
+//! - `cognitive_complexity`: as described by [SonarSource], control flow structures
+//!   cost more when they are nested, `else` branches, sequences of mixed logical
+//!   operators and labeled jumps cost one each.
+//!
+//! [SonarSource]: https://www.sonarsource.com/docs/CognitiveComplexity.pdf
+
+use std::collections::BTreeMap;
+use std::mem::discriminant;
+use std::path::PathBuf;
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{BinOp, Block, Expr, ExprIf, Signature};
+
+use super::source::SourceFile;
+use super::{uses_tool, BuiltinChecker, CheckContext, SourceChecker, SupportedTool};
+use crate::parser::{CheckInfo, CheckTool, CodeSpan, MetricsCfg, OutputSection, RulesCfg};
+use crate::Result;
+
+/// Metrics of a function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FnMetrics {
+    /// Name of the function, prefixed with its type or trait if it's a method.
+    pub name: String,
+    pub module: String,
+    pub file_path: PathBuf,
+    pub line: usize,
+    pub lines: usize,
+    pub params: usize,
+    pub nesting_depth: usize,
+    pub cyclomatic_complexity: usize,
+    pub cognitive_complexity: usize,
+    #[serde(skip)]
+    column: usize,
+}
+
+/// Metrics of a module, aggregated from its functions.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleMetrics {
+    pub functions: usize,
+    /// Total lines of the functions.
+    pub fn_lines: usize,
+    pub max_nesting_depth: usize,
+    pub max_cyclomatic_complexity: usize,
+    pub max_cognitive_complexity: usize,
+    pub total_cyclomatic_complexity: usize,
+    pub total_cognitive_complexity: usize,
+}
+
+/// Raw metrics of the checked crate.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metrics {
+    pub functions: Vec<FnMetrics>,
+    pub modules: BTreeMap<String, ModuleMetrics>,
+}
+
+impl FromIterator<FnMetrics> for Metrics {
+    fn from_iter<T: IntoIterator<Item = FnMetrics>>(iter: T) -> Self {
+        let functions = iter.into_iter().collect::<Vec<_>>();
+        let mut modules: BTreeMap<String, ModuleMetrics> = BTreeMap::new();
+        for f in &functions {
+            let m = modules.entry(f.module.clone()).or_default();
+            m.functions += 1;
+            m.fn_lines += f.lines;
+            m.max_nesting_depth = m.max_nesting_depth.max(f.nesting_depth);
+            m.max_cyclomatic_complexity = m.max_cyclomatic_complexity.max(f.cyclomatic_complexity);
+            m.max_cognitive_complexity = m.max_cognitive_complexity.max(f.cognitive_complexity);
+            m.total_cyclomatic_complexity += f.cyclomatic_complexity;
+            m.total_cognitive_complexity += f.cognitive_complexity;
+        }
+        Metrics { functions, modules }
+    }
+}
+
+impl Metrics {
+    /// Merge the metrics of another run, such as of another crate.
+    ///
+    /// Functions are identified by their files and lines, so the ones measured by both runs
+    /// are only kept once, and the metrics of modules are aggregated again.
+    pub fn merge(&mut self, other: Metrics) {
+        let mut functions = std::mem::take(&mut self.functions);
+        for f in other.functions {
+            if !functions
+                .iter()
+                .any(|g| g.file_path == f.file_path && g.line == f.line)
+            {
+                functions.push(f);
+            }
+        }
+        *self = functions.into_iter().collect();
+    }
+}
+
+/// The metrics checker, reporting functions that exceed the thresholds.
+#[derive(Debug, Default)]
+pub struct MetricsChecker {
+    pub cfg: MetricsCfg,
+}
+
+impl MetricsChecker {
+    pub fn new(cfg: MetricsCfg) -> Self {
+        MetricsChecker { cfg }
+    }
+
+    /// Measure all the functions in a file.
+    pub fn measure(&self, file: &SourceFile) -> Vec<FnMetrics> {
+        let mut visitor = ItemVisitor {
+            file,
+            modules: vec![file.module.clone()],
+            owners: vec![],
+            result: vec![],
+        };
+        visitor.visit_file(&file.ast);
+        visitor.result
+    }
+
+    /// Collect the metrics of all the functions in a crate.
+    pub fn metrics(&self, files: &[SourceFile]) -> Metrics {
+        files.iter().flat_map(|file| self.measure(file)).collect()
+    }
+}
+
+impl SourceChecker for MetricsChecker {
+    fn check_file(&self, file: &SourceFile) -> Vec<CheckInfo> {
+        let cfg = &self.cfg;
+        let mut result = vec![];
+        for f in self.measure(file) {
+            let exceeded = [
+                (
+                    "long-function",
+                    "number of lines",
+                    f.lines,
+                    cfg.max_fn_lines,
+                ),
+                (
+                    "deep-nesting",
+                    "nesting depth",
+                    f.nesting_depth,
+                    cfg.max_nesting_depth,
+                ),
+                (
+                    "too-many-parameters",
+                    "number of parameters",
+                    f.params,
+                    cfg.max_params,
+                ),
+                (
+                    "high-cyclomatic-complexity",
+                    "cyclomatic complexity",
+                    f.cyclomatic_complexity,
+                    cfg.max_cyclomatic_complexity,
+                ),
+                (
+                    "high-cognitive-complexity",
+                    "cognitive complexity",
+                    f.cognitive_complexity,
+                    cfg.max_cognitive_complexity,
+                ),
+            ];
+            for (defect_name, metric, value, max) in exceeded {
+                if value <= max {
+                    continue;
+                }
+                result.push(CheckInfo {
+                    file_path: Some(f.file_path.clone()),
+                    defect_name: defect_name.to_string(),
+                    tool: SupportedTool::Metrics,
+                    begin_line: Some(f.line),
+                    end_line: Some(f.line + f.lines - 1),
+                    column: Some(f.column),
+                    code_string: file.line(f.line).trim().to_string(),
+                    help_info: format!(
+                        "the {metric} of function `{}` is {value}, exceeding the limit of {max}",
+                        f.name
+                    ),
+                    span: Some(CodeSpan {
+                        begin_column: Some(f.column),
+                        ..CodeSpan::lines(f.line, f.line + f.lines - 1)
+                    }),
+                    ..Default::default()
+                });
+            }
+        }
+        result
+    }
+}
+
+impl BuiltinChecker for MetricsChecker {
+    fn from_rules(rule_cfg: &RulesCfg, checked_tools: &[&CheckTool]) -> Result<Option<Self>> {
+        let enabled =
+            rule_cfg.metrics.is_some() || uses_tool(checked_tools, SupportedTool::Metrics);
+        Ok(enabled.then(|| MetricsChecker::new(rule_cfg.metrics.clone().unwrap_or_default())))
+    }
+
+    fn tool(&self) -> SupportedTool {
+        SupportedTool::Metrics
+    }
+
+    fn needs_sources(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)> {
+        let metrics = self.metrics(ctx.sources);
+        Ok((
+            self.check_sources(ctx.sources),
+            Some(OutputSection::Metrics(metrics)),
+        ))
+    }
+}
+
+/// Visits items to find functions.
+struct ItemVisitor<'a> {
+    file: &'a SourceFile,
+    /// Paths of the module being visited and its parents.
+    modules: Vec<String>,
+    /// Types or traits that the methods being visited belong to.
+    owners: Vec<String>,
+    result: Vec<FnMetrics>,
+}
+
+impl ItemVisitor<'_> {
+    fn measure(&mut self, sig: &Signature, block: &Block) {
+        let mut visitor = FnVisitor::default();
+        visitor.visit_block(block);
+        let end = block.brace_token.span.close();
+        let name = match self.owners.last() {
+            Some(owner) => format!("{owner}::{}", sig.ident),
+            None => sig.ident.to_string(),
+        };
+        let start = sig.span().start();
+        self.result.push(FnMetrics {
+            name,
+            module: self.modules.last().cloned().unwrap_or_default(),
+            file_path: self.file.path.clone(),
+            line: start.line,
+            column: start.column + 1,
+            lines: end.end().line - start.line + 1,
+            params: sig.inputs.len(),
+            nesting_depth: visitor.max_depth,
+            cyclomatic_complexity: 1 + visitor.cyclomatic,
+            cognitive_complexity: visitor.cognitive,
+        });
+    }
+
+    fn with_owner<F: FnOnce(&mut Self)>(&mut self, owner: String, f: F) {
+        self.owners.push(owner);
+        f(self);
+        self.owners.pop();
+    }
+}
+
+impl<'ast> Visit<'ast> for ItemVisitor<'_> {
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        let parent = self.modules.last().cloned().unwrap_or_default();
+        self.modules.push(format!("{parent}::{}", item.ident));
+        visit::visit_item_mod(self, item);
+        self.modules.pop();
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        let owner = item.self_ty.to_token_stream().to_string().replace(' ', "");
+        self.with_owner(owner, |v| visit::visit_item_impl(v, item));
+    }
+
+    fn visit_item_trait(&mut self, item: &'ast syn::ItemTrait) {
+        self.with_owner(item.ident.to_string(), |v| visit::visit_item_trait(v, item));
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        self.measure(&item.sig, &item.block);
+        // functions nested in this one don't belong to the owner of this one
+        let owners = std::mem::take(&mut self.owners);
+        visit::visit_item_fn(self, item);
+        self.owners = owners;
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        self.measure(&item.sig, &item.block);
+        let owners = std::mem::take(&mut self.owners);
+        visit::visit_impl_item_fn(self, item);
+        self.owners = owners;
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast syn::TraitItemFn) {
+        // only provided methods have something to measure
+        if let Some(block) = &item.default {
+            self.measure(&item.sig, block);
+        }
+        let owners = std::mem::take(&mut self.owners);
+        visit::visit_trait_item_fn(self, item);
+        self.owners = owners;
+    }
+}
+
+/// Visits the body of a function to measure its complexity.
+#[derive(Default)]
+struct FnVisitor {
+    depth: usize,
+    max_depth: usize,
+    cyclomatic: usize,
+    cognitive: usize,
+}
+
+impl FnVisitor {
+    fn nested<F: FnOnce(&mut Self)>(&mut self, f: F) {
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+        f(self);
+        self.depth -= 1;
+    }
+
+    /// An `if` expression, and the `else if` branches chained after it,
+    /// which are at the same level of nesting.
+    fn visit_if_chain(&mut self, expr: &ExprIf, is_else_if: bool) {
+        self.cyclomatic += 1;
+        self.cognitive += if is_else_if { 1 } else { 1 + self.depth };
+        for attr in &expr.attrs {
+            self.visit_attribute(attr);
+        }
+        self.visit_expr(&expr.cond);
+        self.nested(|v| v.visit_block(&expr.then_branch));
+        match expr.else_branch.as_ref().map(|(_, e)| e.as_ref()) {
+            Some(Expr::If(else_if)) => self.visit_if_chain(else_if, true),
+            Some(other) => {
+                self.cognitive += 1;
+                self.nested(|v| v.visit_expr(other));
+            }
+            None => (),
+        }
+    }
+}
+
+fn is_logical(op: &BinOp) -> bool {
+    matches!(op, BinOp::And(_) | BinOp::Or(_))
+}
+
+impl<'ast> Visit<'ast> for FnVisitor {
+    // nested items are measured by themselves
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+
+    fn visit_expr_if(&mut self, expr: &'ast ExprIf) {
+        self.visit_if_chain(expr, false);
+    }
+
+    fn visit_expr_match(&mut self, expr: &'ast syn::ExprMatch) {
+        self.cyclomatic += expr.arms.len().saturating_sub(1);
+        self.cognitive += 1 + self.depth;
+        self.visit_expr(&expr.expr);
+        self.nested(|v| {
+            for arm in &expr.arms {
+                v.visit_arm(arm);
+            }
+        });
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast syn::ExprWhile) {
+        self.cyclomatic += 1;
+        self.cognitive += 1 + self.depth;
+        self.visit_expr(&expr.cond);
+        self.nested(|v| v.visit_block(&expr.body));
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.cyclomatic += 1;
+        self.cognitive += 1 + self.depth;
+        self.visit_expr(&expr.expr);
+        self.nested(|v| v.visit_block(&expr.body));
+    }
+
+    fn visit_expr_loop(&mut self, expr: &'ast syn::ExprLoop) {
+        self.cognitive += 1 + self.depth;
+        self.nested(|v| v.visit_block(&expr.body));
+    }
+
+    fn visit_expr_closure(&mut self, expr: &'ast syn::ExprClosure) {
+        self.nested(|v| visit::visit_expr_closure(v, expr));
+    }
+
+    fn visit_expr_binary(&mut self, expr: &'ast syn::ExprBinary) {
+        if is_logical(&expr.op) {
+            self.cyclomatic += 1;
+            // `a && b && c` is a single sequence, parsed as `(a && b) && c`
+            let continues_sequence = matches!(
+                expr.left.as_ref(),
+                Expr::Binary(left) if discriminant(&left.op) == discriminant(&expr.op)
+            );
+            if !continues_sequence {
+                self.cognitive += 1;
+            }
+        }
+        visit::visit_expr_binary(self, expr);
+    }
+
+    fn visit_expr_try(&mut self, expr: &'ast syn::ExprTry) {
+        self.cyclomatic += 1;
+        visit::visit_expr_try(self, expr);
+    }
+
+    fn visit_expr_break(&mut self, expr: &'ast syn::ExprBreak) {
+        if expr.label.is_some() {
+            self.cognitive += 1;
+        }
+        visit::visit_expr_break(self, expr);
+    }
+
+    fn visit_expr_continue(&mut self, expr: &'ast syn::ExprContinue) {
+        if expr.label.is_some() {
+            self.cognitive += 1;
+        }
+        visit::visit_expr_continue(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FnMetrics, MetricsChecker};
+    use crate::tools::source::SourceFile;
+
+    fn measure(content: &str) -> Vec<FnMetrics> {
+        let file = SourceFile {
+            path: "lib.rs".into(),
+            module: "crate".into(),
+            content: content.to_string(),
+            ast: syn::parse_file(content).unwrap(),
+        };
+        MetricsChecker::default().measure(&file)
+    }
+
+    #[test]
+    fn measure_complexity() {
+        let content = "fn sum_primes(max: u32) -> u32 {
+    let mut total = 0;
+    'outer: for i in 1..=max {
+        for j in 2..i {
+            if i % j == 0 {
+                continue 'outer;
+            }
+        }
+        total += i;
+    }
+    total
+}
+
+fn describe(n: i32, verbose: bool) -> &'static str {
+    if n > 0 && verbose || n > 100 {
+        \"positive\"
+    } else if n < 0 {
+        \"negative\"
+    } else {
+        \"zero\"
+    }
+}
+";
+        let res = measure(content);
+        assert_eq!(res.len(), 2);
+
+        assert_eq!(res[0].name, "sum_primes");
+        assert_eq!((res[0].line, res[0].lines, res[0].params), (1, 12, 1));
+        assert_eq!(res[0].nesting_depth, 3);
+        assert_eq!(res[0].cyclomatic_complexity, 4);
+        // for (1) + for (2) + if (3) + labeled continue (1)
+        assert_eq!(res[0].cognitive_complexity, 7);
+
+        assert_eq!(res[1].params, 2);
+        assert_eq!(res[1].nesting_depth, 1);
+        // if, else if, `&&` and `||`
+        assert_eq!(res[1].cyclomatic_complexity, 5);
+        // if (1) + else if (1) + else (1) + `&&` (1) + `||` (1)
+        assert_eq!(res[1].cognitive_complexity, 5);
+    }
+
+    #[test]
+    fn qualified_method_names() {
+        let content = "struct Foo;
+impl Foo {
+    fn new() -> Self {
+        fn helper() {}
+        Foo
+    }
+}
+trait Bar {
+    fn required(&self);
+    fn provided(&self) {}
+}
+mod inner {
+    fn f() {}
+}
+";
+        let res = measure(content);
+        let names = res
+            .iter()
+            .map(|f| (f.module.as_str(), f.name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                ("crate", "Foo::new"),
+                ("crate", "helper"),
+                ("crate", "Bar::provided"),
+                ("crate::inner", "f"),
+            ]
+        );
+    }
+}
@@ -7,7 +7,7 @@ use regex::Regex;
 use crate::parser::CheckInfo;
 use crate::Result;
 
-use super::{Checker, FilteredOutput};
+use super::{rendered, Checker, FilteredOutput};
 
 #[derive(Default, Debug)]
 pub struct MiriOpt {
@@ -53,6 +53,7 @@ impl Checker for MiriOpt {
         }
 
         let mut lines = raw_result.trim().lines();
+        let rendered = rendered::parse(raw_result);
 
         let help_info = lines.next().map_or(String::new(), |s| {
             let cap = RE_HELP_INFO.captures(s).unwrap();
@@ -91,6 +92,10 @@ impl Checker for MiriOpt {
             code_string: code_lines.join("\n"),
             help_info,
             additional_help_info: additional_help_lines.join("\n"),
+            span: rendered.span,
+            secondary_spans: rendered.secondary_spans,
+            children: rendered.children,
+            context: None,
             guideline_list: vec![],
         })
     }
@@ -100,6 +105,7 @@ impl Checker for MiriOpt {
 mod tests {
     use std::path::PathBuf;
 
+    use crate::parser::{ChildMessage, CodeSpan};
     use crate::tools::Checker;
 
     use super::MiriOpt;
@@ -146,5 +152,24 @@ see https://doc.rust-lang.org/nightly/reference/behavior-considered-undefined.ht
 BACKTRACE (of the first span):
 inside closure at src/bin/data_race.rs:7:38: 7:48";
         assert_eq!(&check_info.additional_help_info, expected_help_note);
+
+        assert_eq!(
+            check_info.span,
+            Some(
+                CodeSpan::new((7, 38), (7, 48))
+                    .with_label(expected_help_info.trim_start_matches("Undefined Behavior: "))
+            )
+        );
+        assert_eq!(
+            check_info.secondary_spans,
+            [CodeSpan::new((6, 38), (6, 48)).with_label("and (1) occurred earlier here")]
+        );
+        assert_eq!(
+            check_info.children[2..],
+            [
+                ChildMessage::note("BACKTRACE (of the first span):"),
+                ChildMessage::note("inside closure at src/bin/data_race.rs:7:38: 7:48"),
+            ]
+        );
     }
 }
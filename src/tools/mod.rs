@@ -4,13 +4,39 @@
 //! All relavent definition should be declared here, just in case more tools will be
 //! added or deleted in the future.
 
+pub mod advisory;
+pub mod diagnostics;
+pub mod doc_coverage;
+pub mod fix;
+pub mod license;
+mod lint_list;
 mod lints;
+pub mod manifest;
+pub mod metrics;
 pub mod miri;
+pub mod panics;
+pub mod pattern;
+mod rendered;
+pub mod rustfmt;
+pub mod source;
+pub mod unsafe_audit;
 
+pub use self::advisory::AdvisoryChecker;
+pub use self::doc_coverage::DocChecker;
+pub use self::license::LicenseChecker;
+pub use self::lint_list::{KnownLints, LintIssue, LintStatus, LintValidator};
 pub use self::lints::LintsOpt;
+pub use self::manifest::ManifestChecker;
+pub use self::metrics::MetricsChecker;
+pub use self::panics::PanicChecker;
+pub use self::pattern::PatternOpt;
+pub use self::rustfmt::RustfmtChecker;
+pub use self::unsafe_audit::UnsafeAudit;
 
-use crate::{parser::CheckInfo, Result};
-use std::{fmt::Display, process::Output, str::FromStr};
+use self::source::SourceFile;
+use crate::parser::{CheckInfo, CheckTool, OutputSection, RulesCfg};
+use crate::{utils, Result};
+use std::{fmt::Display, path::Path, process::Output, str::FromStr};
 
 pub trait Checker {
     /// Get output by running commands.
@@ -27,6 +53,86 @@ pub trait Checker {
     fn check_info(&self, raw_result: &str) -> Result<CheckInfo>;
 }
 
+/// Checkers that analyze the syntax tree of the source code by themselves,
+/// instead of running other programs, which makes them fast.
+pub trait SourceChecker {
+    /// Check a parsed source file, and generalize each defect found to a [`CheckInfo`].
+    fn check_file(&self, file: &SourceFile) -> Vec<CheckInfo>;
+
+    /// Check all the source files of a crate.
+    fn check_sources(&self, files: &[SourceFile]) -> Vec<CheckInfo> {
+        files
+            .iter()
+            .flat_map(|file| self.check_file(file))
+            .collect()
+    }
+}
+
+/// Checkers built into eunomia, which are run after the lints by `eunomia check`.
+pub trait BuiltinChecker {
+    /// Create the checker if the checked guidelines need it, or it's configured in the rules.
+    fn from_rules(rule_cfg: &RulesCfg, checked_tools: &[&CheckTool]) -> Result<Option<Self>>
+    where
+        Self: Sized;
+
+    fn tool(&self) -> SupportedTool;
+
+    /// Whether the checker analyzes the parsed source files in [`CheckContext::sources`].
+    fn needs_sources(&self) -> bool {
+        false
+    }
+
+    /// Run the checker, and return its results along with the section of the output
+    /// it fills in, if any.
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)>;
+}
+
+/// What the built-in checkers need to run.
+pub struct CheckContext<'a> {
+    /// The checked crate, or one of its source files.
+    pub path: &'a Path,
+    /// Source files of the crate, which are only parsed if any checker needs them.
+    pub sources: &'a [SourceFile],
+    /// Whether cargo could be used to run other tools.
+    pub has_cargo: bool,
+}
+
+/// Create the built-in checkers needed by the rules, in the order they should run.
+pub fn builtin_checkers(
+    rule_cfg: &RulesCfg,
+    checked_tools: &[&CheckTool],
+) -> Result<Vec<Box<dyn BuiltinChecker>>> {
+    type Load = fn(&RulesCfg, &[&CheckTool]) -> Result<Option<Box<dyn BuiltinChecker>>>;
+    fn load<C: BuiltinChecker + 'static>(
+        rule_cfg: &RulesCfg,
+        checked_tools: &[&CheckTool],
+    ) -> Result<Option<Box<dyn BuiltinChecker>>> {
+        let checker = C::from_rules(rule_cfg, checked_tools)?;
+        Ok(checker.map(|c| Box::new(c) as Box<dyn BuiltinChecker>))
+    }
+    let loads: [Load; 9] = [
+        load::<PatternOpt>,
+        load::<UnsafeAudit>,
+        load::<MetricsChecker>,
+        load::<DocChecker>,
+        load::<PanicChecker>,
+        load::<ManifestChecker>,
+        load::<AdvisoryChecker>,
+        load::<LicenseChecker>,
+        load::<RustfmtChecker>,
+    ];
+    let mut checkers = vec![];
+    for load in loads {
+        checkers.extend(load(rule_cfg, checked_tools)?);
+    }
+    Ok(checkers)
+}
+
+/// Whether any of the tools of the checked guidelines is the given one.
+pub(crate) fn uses_tool(checked_tools: &[&CheckTool], tool: SupportedTool) -> bool {
+    checked_tools.iter().any(|t| t.name == tool)
+}
+
 /// The output of a tool could have multiple sections of checked result,
 /// so we need to split them, and extract only useful information.
 ///
@@ -52,19 +158,68 @@ pub struct FilteredOutput {
     pub stderr: Vec<String>,
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SupportedTool {
     Clippy,
     #[default]
     Rustc,
     Miri,
     Sanitizer,
+    /// The built-in checker matching patterns against the syntax tree,
+    /// see [`pattern`] for the syntax of patterns.
+    Pattern,
+    /// The built-in checker auditing unsafe code, see [`unsafe_audit`].
+    UnsafeAudit,
+    /// The built-in checker measuring complexity and size, see [`metrics`].
+    Metrics,
+    /// The built-in checker of documentation coverage, see [`doc_coverage`].
+    DocCoverage,
+    /// The built-in checker of `Cargo.toml` and `Cargo.lock`, see [`manifest`].
+    Manifest,
+    /// The built-in checker of security advisories of dependencies, see [`advisory`].
+    Advisory,
+    /// The built-in checker of licenses of dependencies, see [`license`].
+    License,
+    /// The checker of formatting backed by rustfmt, see [`rustfmt`].
+    Rustfmt,
+    /// The checker of panic sites in library code, see [`panics`].
+    Panics,
 }
 
 impl SupportedTool {
     // TODO: put this method in a derive macro
     pub fn all() -> Vec<Self> {
-        vec![Self::Clippy, Self::Rustc, Self::Miri, Self::Sanitizer]
+        vec![
+            Self::Clippy,
+            Self::Rustc,
+            Self::Miri,
+            Self::Sanitizer,
+            Self::Pattern,
+            Self::UnsafeAudit,
+            Self::Metrics,
+            Self::DocCoverage,
+            Self::Manifest,
+            Self::Advisory,
+            Self::License,
+            Self::Rustfmt,
+            Self::Panics,
+        ]
+    }
+
+    /// Version of the program this tool runs, or `None` if it's a checker built into
+    /// eunomia, or the program couldn't be found.
+    pub fn version(&self) -> Option<String> {
+        use SupportedTool::*;
+        match self {
+            // the panic checker runs clippy for most of the panic sites.
+            Clippy | Panics => utils::command_version("clippy-driver", &["-V"]),
+            Rustc => utils::command_version("rustc", &["-V"]),
+            Miri => utils::command_version("cargo", &["miri", "--version"]),
+            Rustfmt => utils::command_version("rustfmt", &["-V"]),
+            // licenses are collected with `cargo metadata`.
+            License => utils::command_version("cargo", &["-V"]),
+            Sanitizer | Pattern | UnsafeAudit | Metrics | DocCoverage | Manifest | Advisory => None,
+        }
     }
 }
 
@@ -77,6 +232,15 @@ impl Display for SupportedTool {
             Rustc => "rustc",
             Miri => "miri",
             Sanitizer => "sanitizer",
+            Pattern => "pattern",
+            UnsafeAudit => "unsafe-audit",
+            Metrics => "metrics",
+            DocCoverage => "doc-coverage",
+            Manifest => "manifest",
+            Advisory => "advisory",
+            License => "license",
+            Rustfmt => "rustfmt",
+            Panics => "panics",
         };
         f.write_str(str)
     }
@@ -91,6 +255,15 @@ impl FromStr for SupportedTool {
             "rustc" => Ok(Self::Rustc),
             "miri" => Ok(Self::Miri),
             "sanitizer" => Ok(Self::Sanitizer),
+            "pattern" => Ok(Self::Pattern),
+            "unsafe-audit" => Ok(Self::UnsafeAudit),
+            "metrics" => Ok(Self::Metrics),
+            "doc-coverage" => Ok(Self::DocCoverage),
+            "manifest" => Ok(Self::Manifest),
+            "advisory" => Ok(Self::Advisory),
+            "license" => Ok(Self::License),
+            "rustfmt" => Ok(Self::Rustfmt),
+            "panics" => Ok(Self::Panics),
             _ => Err(crate::Error::ParseUnsupportedEnumVariant(
                 "tool name",
                 s.to_string(),
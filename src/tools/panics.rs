@@ -0,0 +1,484 @@
+//! A checker finding where library code could panic, see [`PanicsCfg`].
+//!
+//! Panic sites are reported by clippy's restriction lints, such as `clippy::unwrap_used`
+//! and `clippy::indexing_slicing`, while assertions are found from the syntax tree,
+//! because no lint reports them. Sites inside `#[cfg(test)]` and `#[test]` items are
+//! ignored, the others are attributed to the public functions containing them, and a
+//! public function that could panic should document it in a `# Panics` section.
+
+use std::path::{Path, PathBuf};
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Attribute, Visibility};
+
+use super::source::{has_doc_section, is_cfg_test, SourceFile};
+use super::{uses_tool, BuiltinChecker, CheckContext, LintsOpt, SupportedTool};
+use crate::parser::{
+    normalize_lint_name, CheckInfo, CheckTool, CodeSpan, OutputSection, PanicsCfg, RulesCfg,
+};
+use crate::{utils, Result};
+
+/// Clippy lints reporting code that could panic, which are checked by default.
+pub const PANIC_LINTS: &[&str] = &[
+    "clippy::unwrap_used",
+    "clippy::expect_used",
+    "clippy::indexing_slicing",
+    "clippy::panic",
+    "clippy::unreachable",
+    "clippy::todo",
+    "clippy::unimplemented",
+    "clippy::arithmetic_side_effects",
+];
+/// Macros that panic when their assertions fail.
+const ASSERT_MACROS: &[&str] = &["assert", "assert_eq", "assert_ne"];
+
+/// A place where a function could panic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicSite {
+    pub line: usize,
+    pub column: Option<usize>,
+    /// The lint reporting this site, such as `clippy::unwrap_used`,
+    /// or the assertion macro, such as `assert_eq!`.
+    pub kind: String,
+}
+
+/// Panic sites of a public function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FnPanics {
+    /// Name of the function, prefixed with its type if it's a method.
+    pub name: String,
+    pub module: String,
+    pub file_path: PathBuf,
+    pub line: usize,
+    pub may_panic: bool,
+    /// Whether the function has a `# Panics` section in its documentation.
+    pub documented: bool,
+    pub panic_sites: Vec<PanicSite>,
+    #[serde(skip)]
+    end_line: usize,
+}
+
+/// Whether each public function of the checked crate could panic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicReport {
+    pub functions: Vec<FnPanics>,
+}
+
+impl PanicReport {
+    /// Merge the report of another run, such as of another crate, functions reported
+    /// by both runs are only kept once.
+    pub fn merge(&mut self, other: PanicReport) {
+        for f in other.functions {
+            if !self
+                .functions
+                .iter()
+                .any(|g| g.file_path == f.file_path && g.line == f.line)
+            {
+                self.functions.push(f);
+            }
+        }
+    }
+}
+
+/// The panic checker.
+#[derive(Debug, Default)]
+pub struct PanicChecker {
+    pub cfg: PanicsCfg,
+}
+
+impl PanicChecker {
+    pub fn new(cfg: PanicsCfg) -> Self {
+        PanicChecker { cfg }
+    }
+
+    /// Run clippy with the configured lints, and collect the panic sites it reports.
+    pub fn lint_sites(&self, path: &Path, use_cargo: bool) -> Result<Vec<CheckInfo>> {
+        if self.cfg.lints.is_empty() {
+            return Ok(vec![]);
+        }
+        let lints = self
+            .cfg
+            .lints
+            .iter()
+            .map(|lint| normalize_lint_name(lint))
+            .collect::<Vec<_>>();
+        let opt = LintsOpt {
+            is_clippy: true,
+            use_cargo,
+            lint_names: self.cfg.lints.clone(),
+            path: path.to_path_buf(),
+            ..Default::default()
+        };
+        Ok(opt
+            .diagnostics()?
+            .into_iter()
+            .filter(|diag| lints.contains(&normalize_lint_name(&diag.lint)))
+            .map(|diag| diag.check_info())
+            .collect())
+    }
+
+    /// Find the panic sites of a crate, and whether its public functions could panic.
+    ///
+    /// Returns the panic sites outside of tests, along with the public functions that
+    /// could panic but don't document it.
+    pub fn check(
+        &self,
+        path: &Path,
+        files: &[SourceFile],
+        use_cargo: bool,
+    ) -> Result<(Vec<CheckInfo>, PanicReport)> {
+        let sites = self.lint_sites(path, use_cargo)?;
+        Ok(self.analyze(files, sites))
+    }
+
+    /// Attribute the panic sites found by lints, and the assertions in the files,
+    /// to the public functions containing them.
+    pub fn analyze(
+        &self,
+        files: &[SourceFile],
+        sites: Vec<CheckInfo>,
+    ) -> (Vec<CheckInfo>, PanicReport) {
+        let mut sites = sites
+            .into_iter()
+            .map(|site| {
+                let kind = site.defect_name.clone();
+                (site, kind)
+            })
+            .collect::<Vec<_>>();
+        let mut scopes = vec![];
+        for file in files {
+            let mut visitor = ScopeVisitor {
+                file,
+                modules: vec![file.module.clone()],
+                owners: vec![],
+                tests: vec![],
+                functions: vec![],
+                assertions: vec![],
+            };
+            visitor.visit_file(&file.ast);
+            if self.cfg.assertions {
+                sites.append(&mut visitor.assertions);
+            }
+            scopes.push((utils::canonical(&file.path), visitor));
+        }
+
+        let mut result = vec![];
+        for (site, kind) in sites {
+            let (Some(file_path), Some(line)) = (&site.file_path, site.begin_line) else {
+                result.push(site);
+                continue;
+            };
+            let file_path = utils::canonical(file_path);
+            let Some((_, scope)) = scopes.iter_mut().find(|(path, _)| *path == file_path) else {
+                result.push(site);
+                continue;
+            };
+            if scope
+                .tests
+                .iter()
+                .any(|(begin, end)| (*begin..=*end).contains(&line))
+            {
+                continue;
+            }
+            // the innermost function is the one that begins last
+            if let Some(func) = scope
+                .functions
+                .iter_mut()
+                .filter(|f| (f.line..=f.end_line).contains(&line))
+                .max_by_key(|f| f.line)
+            {
+                func.may_panic = true;
+                func.panic_sites.push(PanicSite {
+                    line,
+                    column: site.column,
+                    kind,
+                });
+            }
+            result.push(site);
+        }
+
+        let mut functions = vec![];
+        for (_, scope) in scopes {
+            for func in scope.functions {
+                if func.may_panic && !func.documented {
+                    result.push(CheckInfo {
+                        file_path: Some(func.file_path.clone()),
+                        defect_name: "missing-panics-doc".to_string(),
+                        tool: SupportedTool::Panics,
+                        begin_line: Some(func.line),
+                        code_string: scope.file.line(func.line).trim().to_string(),
+                        help_info: format!(
+                            "public function `{}` could panic at {} site(s), \
+                             but has no `# Panics` section",
+                            func.name,
+                            func.panic_sites.len()
+                        ),
+                        secondary_spans: func
+                            .panic_sites
+                            .iter()
+                            .map(|site| CodeSpan {
+                                begin_column: site.column,
+                                ..CodeSpan::lines(site.line, site.line)
+                                    .with_label(format!("could panic here ({})", site.kind))
+                            })
+                            .collect(),
+                        ..Default::default()
+                    });
+                }
+                functions.push(func);
+            }
+        }
+        (result, PanicReport { functions })
+    }
+}
+
+impl BuiltinChecker for PanicChecker {
+    fn from_rules(rule_cfg: &RulesCfg, checked_tools: &[&CheckTool]) -> Result<Option<Self>> {
+        let enabled = rule_cfg.panics.is_some() || uses_tool(checked_tools, SupportedTool::Panics);
+        Ok(enabled.then(|| PanicChecker::new(rule_cfg.panics.clone().unwrap_or_default())))
+    }
+
+    fn tool(&self) -> SupportedTool {
+        SupportedTool::Panics
+    }
+
+    fn needs_sources(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)> {
+        let (sites, report) = self.check(ctx.path, ctx.sources, ctx.has_cargo)?;
+        Ok((sites, Some(OutputSection::Panics(report))))
+    }
+}
+
+fn is_test(attrs: &[Attribute]) -> bool {
+    is_cfg_test(attrs) || attrs.iter().any(|attr| attr.path().is_ident("test"))
+}
+
+fn is_public(vis: &Visibility) -> bool {
+    matches!(vis, Visibility::Public(_))
+}
+
+/// Collects the tests, public functions and assertions of a file.
+struct ScopeVisitor<'a> {
+    file: &'a SourceFile,
+    modules: Vec<String>,
+    /// Types of the inherent impl blocks being visited.
+    owners: Vec<String>,
+    /// Line ranges of test items.
+    tests: Vec<(usize, usize)>,
+    functions: Vec<FnPanics>,
+    /// Assertions along with the names of their macros.
+    assertions: Vec<(CheckInfo, String)>,
+}
+
+impl ScopeVisitor<'_> {
+    /// Record the line range of an item if it's a test, and tell whether it is.
+    fn skip_test<T: Spanned>(&mut self, attrs: &[Attribute], item: &T) -> bool {
+        if !is_test(attrs) {
+            return false;
+        }
+        let span = item.span();
+        self.tests.push((span.start().line, span.end().line));
+        true
+    }
+
+    fn add_fn<T: Spanned>(&mut self, ident: &syn::Ident, attrs: &[Attribute], item: &T) {
+        let name = match self.owners.last() {
+            Some(owner) => format!("{owner}::{ident}"),
+            None => ident.to_string(),
+        };
+        self.functions.push(FnPanics {
+            name,
+            module: self.modules.last().cloned().unwrap_or_default(),
+            file_path: self.file.path.clone(),
+            line: ident.span().start().line,
+            may_panic: false,
+            documented: has_doc_section(attrs, "Panics"),
+            panic_sites: vec![],
+            end_line: item.span().end().line,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for ScopeVisitor<'_> {
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        if self.skip_test(&item.attrs, item) {
+            return;
+        }
+        let parent = self.modules.last().cloned().unwrap_or_default();
+        self.modules.push(format!("{parent}::{}", item.ident));
+        visit::visit_item_mod(self, item);
+        self.modules.pop();
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        if self.skip_test(&item.attrs, item) {
+            return;
+        }
+        // methods of trait impls are as public as the trait, which can't be told here
+        if item.trait_.is_some() {
+            let owners = std::mem::take(&mut self.owners);
+            visit::visit_item_impl(self, item);
+            self.owners = owners;
+            return;
+        }
+        let owner = item.self_ty.to_token_stream().to_string().replace(' ', "");
+        self.owners.push(owner);
+        visit::visit_item_impl(self, item);
+        self.owners.pop();
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        if self.skip_test(&item.attrs, item) {
+            return;
+        }
+        if is_public(&item.vis) {
+            self.add_fn(&item.sig.ident, &item.attrs, item);
+        }
+        // functions nested in this one don't belong to the owner of this one
+        let owners = std::mem::take(&mut self.owners);
+        visit::visit_item_fn(self, item);
+        self.owners = owners;
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        if self.skip_test(&item.attrs, item) {
+            return;
+        }
+        if is_public(&item.vis) && !self.owners.is_empty() {
+            self.add_fn(&item.sig.ident, &item.attrs, item);
+        }
+        let owners = std::mem::take(&mut self.owners);
+        visit::visit_impl_item_fn(self, item);
+        self.owners = owners;
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if let Some(name) = mac
+            .path
+            .segments
+            .last()
+            .map(|seg| seg.ident.to_string())
+            .filter(|name| ASSERT_MACROS.contains(&name.as_str()))
+        {
+            let info = self.file.check_info(
+                SupportedTool::Panics,
+                "assertion",
+                mac.path.span(),
+                format!("`{name}!` panics when the assertion fails"),
+            );
+            self.assertions.push((info, format!("{name}!")));
+        }
+        visit::visit_macro(self, mac);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PanicChecker;
+    use crate::parser::CheckInfo;
+    use crate::tools::source::SourceFile;
+    use crate::tools::SupportedTool;
+
+    #[test]
+    fn attribute_panic_sites() {
+        let content = "pub fn first(v: &[u8]) -> u8 {
+    assert!(!v.is_empty());
+    v[0]
+}
+
+/// # Panics
+///
+/// Panics on `None`.
+pub fn value(x: Option<u8>) -> u8 {
+    x.unwrap()
+}
+
+pub struct S;
+
+impl S {
+    pub fn check(&self) {
+        assert_eq!(1, 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn first() {
+        assert_eq!(super::first(&[1]), 1);
+    }
+}
+";
+        let file = SourceFile {
+            path: "lib.rs".into(),
+            module: "crate".into(),
+            content: content.to_string(),
+            ast: syn::parse_file(content).unwrap(),
+        };
+        let lint_site = |line, defect_name: &str| CheckInfo {
+            file_path: Some("lib.rs".into()),
+            defect_name: defect_name.to_string(),
+            tool: SupportedTool::Clippy,
+            begin_line: Some(line),
+            column: Some(5),
+            ..Default::default()
+        };
+        let sites = vec![
+            lint_site(3, "clippy::indexing_slicing"),
+            lint_site(10, "clippy::unwrap_used"),
+            lint_site(25, "clippy::unwrap_used"),
+        ];
+        let (result, report) = PanicChecker::default().analyze(&[file], sites);
+
+        let functions = report
+            .functions
+            .iter()
+            .map(|f| {
+                let kinds = f.panic_sites.iter().map(|s| s.kind.as_str());
+                (f.name.as_str(), f.documented, kinds.collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            [
+                ("first", false, vec!["clippy::indexing_slicing", "assert!"]),
+                ("value", true, vec!["clippy::unwrap_used"]),
+                ("S::check", false, vec!["assert_eq!"]),
+            ]
+        );
+        // the site in the test is dropped
+        let found = result
+            .iter()
+            .map(|info| (info.begin_line.unwrap(), info.defect_name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                (3, "clippy::indexing_slicing"),
+                (10, "clippy::unwrap_used"),
+                (2, "assertion"),
+                (17, "assertion"),
+                (1, "missing-panics-doc"),
+                (16, "missing-panics-doc"),
+            ]
+        );
+        let labels = result[4]
+            .secondary_spans
+            .iter()
+            .map(|span| (span.begin_line, span.label.as_deref().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            [
+                (3, "could panic here (clippy::indexing_slicing)"),
+                (2, "could panic here (assert!)"),
+            ]
+        );
+    }
+}
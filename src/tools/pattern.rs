@@ -0,0 +1,572 @@
+//! A checker matching declarative patterns against the syntax tree of the source code,
+//! for guidelines that no lint covers.
+//!
+//! Patterns are written as the `ident` of a guideline's [`CheckTool`](crate::parser::CheckTool)
+//! in one of these forms:
+//!
+//! - `path:<path>`: the path is forbidden, such as `path:std::process::exit`, or `path:dbg`
+//!   for the `dbg!` macro. Paths used in expressions, types, macros and `use` declarations
+//!   are matched, and a path written partially (like `process::exit`) matches as well.
+//! - `item:<kind>:<regex>`: names of the items of that kind must match the regex,
+//!   such as `item:const:^[A-Z][A-Z0-9_]*$`. The regex could be prefixed with `!`,
+//!   meaning that names must *not* match it.
+//! - `attr:<kind>:<attribute>`: items of that kind must have the attribute, such as
+//!   `attr:struct:derive(Debug)`, which means a `#[derive]` attribute containing `Debug`.
+//!   The attribute could be prefixed with `!`, meaning that it's forbidden.
+//!
+//! Item kinds are `fn`, `struct`, `enum`, `union`, `trait`, `type`, `const`, `static`,
+//! `mod`, `macro`, `field` and `variant`, add a `pub-` prefix (like `pub-fn`) to only
+//! match public items.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use proc_macro2::Span;
+use regex::Regex;
+use syn::ext::IdentExt;
+use syn::visit::{self, Visit};
+use syn::{Attribute, Ident, UseTree, Visibility};
+
+use super::source::SourceFile;
+use super::{BuiltinChecker, CheckContext, SourceChecker, SupportedTool};
+use crate::parser::{CheckInfo, CheckTool, OutputSection, RulesCfg};
+use crate::{Error, Result};
+
+/// Kinds of items that could be matched by patterns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Fn,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    Type,
+    Const,
+    Static,
+    Mod,
+    Macro,
+    Field,
+    Variant,
+}
+
+impl ItemKind {
+    pub fn all() -> Vec<Self> {
+        use ItemKind::*;
+        vec![
+            Fn, Struct, Enum, Union, Trait, Type, Const, Static, Mod, Macro, Field, Variant,
+        ]
+    }
+}
+
+impl Display for ItemKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use ItemKind::*;
+        let str = match self {
+            Fn => "fn",
+            Struct => "struct",
+            Enum => "enum",
+            Union => "union",
+            Trait => "trait",
+            Type => "type",
+            Const => "const",
+            Static => "static",
+            Mod => "mod",
+            Macro => "macro",
+            Field => "field",
+            Variant => "variant",
+        };
+        f.write_str(str)
+    }
+}
+
+impl FromStr for ItemKind {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ItemKind::all()
+            .into_iter()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| {
+                Error::ParseUnsupportedEnumVariant(
+                    "item kind",
+                    s.to_string(),
+                    ItemKind::all().iter().map(ToString::to_string).collect(),
+                )
+            })
+    }
+}
+
+/// Items of a kind that a pattern applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemFilter {
+    pub kind: ItemKind,
+    pub public_only: bool,
+}
+
+impl ItemFilter {
+    fn matches(&self, kind: ItemKind, vis: Option<&Visibility>) -> bool {
+        self.kind == kind && (!self.public_only || matches!(vis, Some(Visibility::Public(_))))
+    }
+}
+
+impl FromStr for ItemFilter {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (public_only, kind) = match s.strip_prefix("pub-") {
+            Some(kind) => (true, kind),
+            None => (false, s),
+        };
+        Ok(ItemFilter {
+            kind: kind.parse()?,
+            public_only,
+        })
+    }
+}
+
+/// A pattern described in the declarative form, see [the module documentation](self).
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    ForbiddenPath(Vec<String>),
+    ItemName {
+        items: ItemFilter,
+        regex: Regex,
+        negated: bool,
+    },
+    Attribute {
+        items: ItemFilter,
+        name: String,
+        /// A text that the arguments of the attribute should contain.
+        args: Option<String>,
+        negated: bool,
+    },
+}
+
+impl FromStr for Pattern {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| Error::InvalidPattern(s.to_string(), reason.to_string());
+        let (ty, rest) = s
+            .split_once(':')
+            .ok_or_else(|| invalid("missing pattern type"))?;
+        match ty {
+            "path" => {
+                let segments = rest
+                    .trim()
+                    .trim_start_matches("::")
+                    .split("::")
+                    .map(|seg| seg.trim().to_string())
+                    .collect::<Vec<_>>();
+                if segments.iter().any(String::is_empty) {
+                    return Err(invalid("empty path segment"));
+                }
+                Ok(Pattern::ForbiddenPath(segments))
+            }
+            "item" | "attr" => {
+                let (items, rest) = rest
+                    .split_once(':')
+                    .ok_or_else(|| invalid("missing item kind"))?;
+                let items = items.trim().parse()?;
+                let (negated, rest) = match rest.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, rest),
+                };
+                if ty == "item" {
+                    let regex = Regex::new(rest).map_err(|e| invalid(&e.to_string()))?;
+                    return Ok(Pattern::ItemName {
+                        items,
+                        regex,
+                        negated,
+                    });
+                }
+                let (name, args) = match rest.split_once('(') {
+                    Some((name, args)) => (
+                        name,
+                        Some(
+                            args.strip_suffix(')')
+                                .ok_or_else(|| invalid("unclosed parenthesis"))?
+                                .trim()
+                                .to_string(),
+                        ),
+                    ),
+                    None => (rest, None),
+                };
+                if name.trim().is_empty() {
+                    return Err(invalid("missing attribute name"));
+                }
+                Ok(Pattern::Attribute {
+                    items,
+                    name: name.trim().to_string(),
+                    args,
+                    negated,
+                })
+            }
+            _ => Err(invalid(
+                "pattern type should be one of 'path', 'item' or 'attr'",
+            )),
+        }
+    }
+}
+
+/// Options of the pattern checker, containing patterns along with their idents.
+#[derive(Debug, Default)]
+pub struct PatternOpt {
+    pub patterns: Vec<(String, Pattern)>,
+}
+
+impl PatternOpt {
+    /// Add a pattern written in the declarative form.
+    pub fn add(&mut self, ident: &str) -> crate::Result<()> {
+        self.patterns.push((ident.to_string(), ident.parse()?));
+        Ok(())
+    }
+}
+
+impl SourceChecker for PatternOpt {
+    fn check_file(&self, file: &SourceFile) -> Vec<CheckInfo> {
+        let mut visitor = PatternVisitor {
+            opt: self,
+            file,
+            result: vec![],
+        };
+        visitor.visit_file(&file.ast);
+        visitor.result
+    }
+}
+
+impl BuiltinChecker for PatternOpt {
+    fn from_rules(_: &RulesCfg, checked_tools: &[&CheckTool]) -> Result<Option<Self>> {
+        let mut opt = PatternOpt::default();
+        for t in checked_tools
+            .iter()
+            .filter(|t| t.name == SupportedTool::Pattern)
+        {
+            if opt.patterns.iter().all(|(ident, _)| ident != t.ident) {
+                opt.add(t.ident)?;
+            }
+        }
+        Ok((!opt.patterns.is_empty()).then_some(opt))
+    }
+
+    fn tool(&self) -> SupportedTool {
+        SupportedTool::Pattern
+    }
+
+    fn needs_sources(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)> {
+        Ok((self.check_sources(ctx.sources), None))
+    }
+}
+
+struct PatternVisitor<'a> {
+    opt: &'a PatternOpt,
+    file: &'a SourceFile,
+    result: Vec<CheckInfo>,
+}
+
+impl PatternVisitor<'_> {
+    fn report(&mut self, ident: &str, span: Span, help_info: String) {
+        let info = self
+            .file
+            .check_info(SupportedTool::Pattern, ident, span, help_info);
+        self.result.push(info);
+    }
+
+    fn check_path(&mut self, segments: &[String], span: Span) {
+        for (ident, pattern) in &self.opt.patterns {
+            let Pattern::ForbiddenPath(forbidden) = pattern else {
+                continue;
+            };
+            if path_matches(forbidden, segments) {
+                let help = format!("use of forbidden path `{}`", forbidden.join("::"));
+                self.report(ident, span, help);
+            }
+        }
+    }
+
+    fn check_item(
+        &mut self,
+        kind: ItemKind,
+        vis: Option<&Visibility>,
+        name: &Ident,
+        attrs: &[Attribute],
+    ) {
+        let name_str = name.unraw().to_string();
+        for (ident, pattern) in &self.opt.patterns {
+            match pattern {
+                Pattern::ItemName {
+                    items,
+                    regex,
+                    negated,
+                } if items.matches(kind, vis) => {
+                    if regex.is_match(&name_str) == *negated {
+                        let not = if *negated { "" } else { " not" };
+                        let help = format!(
+                            "name of {kind} `{name_str}` does{not} match `{}`",
+                            regex.as_str()
+                        );
+                        self.report(ident, name.span(), help);
+                    }
+                }
+                Pattern::Attribute {
+                    items,
+                    name: attr_name,
+                    args,
+                    negated,
+                } if items.matches(kind, vis) => {
+                    let has_attr = attrs
+                        .iter()
+                        .any(|attr| attr_matches(attr, attr_name, args.as_deref()));
+                    if has_attr == *negated {
+                        let attr_str = match args {
+                            Some(args) => format!("{attr_name}({args})"),
+                            None => attr_name.clone(),
+                        };
+                        let help = if *negated {
+                            format!("{kind} `{name_str}` has forbidden attribute `{attr_str}`")
+                        } else {
+                            format!("{kind} `{name_str}` is missing attribute `{attr_str}`")
+                        };
+                        self.report(ident, name.span(), help);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Whether a path in the source refers to the forbidden one, the shorter one of them
+/// should be a suffix of the other, and it should have at least 2 segments
+/// unless the forbidden path itself has only 1 segment.
+fn path_matches(forbidden: &[String], segments: &[String]) -> bool {
+    let (shorter, longer) = if forbidden.len() <= segments.len() {
+        (forbidden, segments)
+    } else {
+        (segments, forbidden)
+    };
+    !shorter.is_empty() && shorter.len() >= forbidden.len().min(2) && longer.ends_with(shorter)
+}
+
+fn attr_matches(attr: &Attribute, name: &str, args: Option<&str>) -> bool {
+    let path = attr
+        .path()
+        .segments
+        .iter()
+        .map(|seg| seg.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    if path != name {
+        return false;
+    }
+    let Some(args) = args else {
+        return true;
+    };
+    match &attr.meta {
+        syn::Meta::List(list) => {
+            // compare without whitespaces, as they are inserted between tokens
+            let tokens = list.tokens.to_string().replace(' ', "");
+            tokens.contains(&args.replace(' ', ""))
+        }
+        syn::Meta::NameValue(nv) => {
+            let value = &nv.value;
+            quote_tokens(value).contains(&args.replace(' ', ""))
+        }
+        syn::Meta::Path(_) => false,
+    }
+}
+
+fn quote_tokens<T: quote::ToTokens>(t: &T) -> String {
+    t.to_token_stream().to_string().replace(' ', "")
+}
+
+/// Flatten a use tree into the full paths it imports, along with their spans.
+fn flatten_use_tree(
+    tree: &UseTree,
+    prefix: &mut Vec<String>,
+    result: &mut Vec<(Vec<String>, Span)>,
+) {
+    match tree {
+        UseTree::Path(p) => {
+            prefix.push(p.ident.to_string());
+            flatten_use_tree(&p.tree, prefix, result);
+            prefix.pop();
+        }
+        UseTree::Name(n) => {
+            let mut path = prefix.clone();
+            path.push(n.ident.to_string());
+            result.push((path, n.ident.span()));
+        }
+        UseTree::Rename(r) => {
+            let mut path = prefix.clone();
+            path.push(r.ident.to_string());
+            result.push((path, r.ident.span()));
+        }
+        UseTree::Group(g) => {
+            for tree in &g.items {
+                flatten_use_tree(tree, prefix, result);
+            }
+        }
+        UseTree::Glob(_) => (),
+    }
+}
+
+impl<'ast> Visit<'ast> for PatternVisitor<'_> {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        let segments = path
+            .segments
+            .iter()
+            .map(|seg| seg.ident.to_string())
+            .collect::<Vec<_>>();
+        let span = path
+            .segments
+            .last()
+            .map_or_else(Span::call_site, |seg| seg.ident.span());
+        self.check_path(&segments, span);
+        visit::visit_path(self, path);
+    }
+
+    fn visit_item_use(&mut self, item: &'ast syn::ItemUse) {
+        let mut paths = vec![];
+        flatten_use_tree(&item.tree, &mut vec![], &mut paths);
+        for (segments, span) in paths {
+            self.check_path(&segments, span);
+        }
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        self.check_item(ItemKind::Fn, Some(&item.vis), &item.sig.ident, &item.attrs);
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        self.check_item(ItemKind::Fn, Some(&item.vis), &item.sig.ident, &item.attrs);
+        visit::visit_impl_item_fn(self, item);
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast syn::TraitItemFn) {
+        self.check_item(ItemKind::Fn, None, &item.sig.ident, &item.attrs);
+        visit::visit_trait_item_fn(self, item);
+    }
+
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        self.check_item(ItemKind::Struct, Some(&item.vis), &item.ident, &item.attrs);
+        visit::visit_item_struct(self, item);
+    }
+
+    fn visit_item_enum(&mut self, item: &'ast syn::ItemEnum) {
+        self.check_item(ItemKind::Enum, Some(&item.vis), &item.ident, &item.attrs);
+        visit::visit_item_enum(self, item);
+    }
+
+    fn visit_item_union(&mut self, item: &'ast syn::ItemUnion) {
+        self.check_item(ItemKind::Union, Some(&item.vis), &item.ident, &item.attrs);
+        visit::visit_item_union(self, item);
+    }
+
+    fn visit_item_trait(&mut self, item: &'ast syn::ItemTrait) {
+        self.check_item(ItemKind::Trait, Some(&item.vis), &item.ident, &item.attrs);
+        visit::visit_item_trait(self, item);
+    }
+
+    fn visit_item_type(&mut self, item: &'ast syn::ItemType) {
+        self.check_item(ItemKind::Type, Some(&item.vis), &item.ident, &item.attrs);
+        visit::visit_item_type(self, item);
+    }
+
+    fn visit_item_const(&mut self, item: &'ast syn::ItemConst) {
+        self.check_item(ItemKind::Const, Some(&item.vis), &item.ident, &item.attrs);
+        visit::visit_item_const(self, item);
+    }
+
+    fn visit_impl_item_const(&mut self, item: &'ast syn::ImplItemConst) {
+        self.check_item(ItemKind::Const, Some(&item.vis), &item.ident, &item.attrs);
+        visit::visit_impl_item_const(self, item);
+    }
+
+    fn visit_item_static(&mut self, item: &'ast syn::ItemStatic) {
+        self.check_item(ItemKind::Static, Some(&item.vis), &item.ident, &item.attrs);
+        visit::visit_item_static(self, item);
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        self.check_item(ItemKind::Mod, Some(&item.vis), &item.ident, &item.attrs);
+        visit::visit_item_mod(self, item);
+    }
+
+    fn visit_item_macro(&mut self, item: &'ast syn::ItemMacro) {
+        if let Some(ident) = &item.ident {
+            self.check_item(ItemKind::Macro, None, ident, &item.attrs);
+        }
+        visit::visit_item_macro(self, item);
+    }
+
+    fn visit_field(&mut self, field: &'ast syn::Field) {
+        if let Some(ident) = &field.ident {
+            self.check_item(ItemKind::Field, Some(&field.vis), ident, &field.attrs);
+        }
+        visit::visit_field(self, field);
+    }
+
+    fn visit_variant(&mut self, variant: &'ast syn::Variant) {
+        self.check_item(ItemKind::Variant, None, &variant.ident, &variant.attrs);
+        visit::visit_variant(self, variant);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{path_matches, ItemKind, Pattern};
+
+    fn segments(path: &str) -> Vec<String> {
+        path.split("::").map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_patterns() {
+        let Ok(Pattern::ForbiddenPath(path)) = "path:::std::process::exit".parse() else {
+            panic!("expecting a forbidden path");
+        };
+        assert_eq!(path, segments("std::process::exit"));
+
+        let Ok(Pattern::ItemName {
+            items,
+            regex,
+            negated,
+        }) = "item:pub-fn:!^get_".parse()
+        else {
+            panic!("expecting an item name pattern");
+        };
+        assert_eq!(items.kind, ItemKind::Fn);
+        assert!(items.public_only);
+        assert_eq!(regex.as_str(), "^get_");
+        assert!(negated);
+
+        let Ok(Pattern::Attribute { name, args, .. }) = "attr:struct:derive(Debug)".parse() else {
+            panic!("expecting an attribute pattern");
+        };
+        assert_eq!(name, "derive");
+        assert_eq!(args.as_deref(), Some("Debug"));
+
+        assert!("path:std::::exit".parse::<Pattern>().is_err());
+        assert!("item:function:^a".parse::<Pattern>().is_err());
+        assert!("item:fn:(".parse::<Pattern>().is_err());
+        assert!("attr:fn:inline(always".parse::<Pattern>().is_err());
+        assert!("unknown".parse::<Pattern>().is_err());
+    }
+
+    #[test]
+    fn match_paths() {
+        let exit = segments("std::process::exit");
+        assert!(path_matches(&exit, &segments("std::process::exit")));
+        assert!(path_matches(&exit, &segments("process::exit")));
+        assert!(!path_matches(&exit, &segments("exit")));
+        assert!(!path_matches(&exit, &segments("std::process::abort")));
+
+        let dbg = segments("dbg");
+        assert!(path_matches(&dbg, &segments("dbg")));
+        assert!(path_matches(&dbg, &segments("std::dbg")));
+        assert!(!path_matches(&dbg, &segments("debug")));
+    }
+}
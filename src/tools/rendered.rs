@@ -0,0 +1,380 @@
+//! Parsing the structure of diagnostics rendered for humans, which is how miri prints
+//! the undefined behavior it finds while running the program, such as:
+//!
+//! ```text
+//! error: Undefined Behavior: Data race detected between (1) Write on thread `<unnamed>` ...
+//!  --> src/bin/data_race.rs:7:38
+//!   |
+//! 7 |   let t2 = thread::spawn(|| unsafe { UNSAFE = 2 });
+//!   |                                      ^^^^^^^^^^ Data race detected between ...
+//!   |
+//! help: and (1) occurred earlier here
+//!  --> src/bin/data_race.rs:6:38
+//!   |
+//! 6 |   let t1 = thread::spawn(|| unsafe { UNSAFE = 1 });
+//!   |                                      ^^^^^^^^^^
+//!   = help: this indicates a bug in the program: it performed an invalid operation, ...
+//! ```
+//!
+//! Spans marked with `^` are primary, and the ones marked with `-` are secondary,
+//! spans of a `help:` or `note:` with its own location are secondary as well,
+//! labelled with the message. Other `help:` and `note:` lines are child messages,
+//! so are the suggestions following a marker, such as `^^^ help: try: `*x``.
+//!
+//! Diagnostics of clippy and rustc are parsed from their JSON output instead,
+//! see [`diagnostics`](super::diagnostics).
+
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::parser::{ChildMessage, CodeSpan};
+
+lazy_static! {
+    static ref RE_LOCATION: Regex = Regex::new(r"^\s*(?:-->|:::) (.+):(\d+):(\d+)\s*$").unwrap();
+    static ref RE_CODE_LINE: Regex = Regex::new(r"^\s*(\d+)\s*\|(.*)$").unwrap();
+    static ref RE_MARKER_LINE: Regex = Regex::new(r"^\s*\|(.*)$").unwrap();
+    static ref RE_CHILD: Regex = Regex::new(r"^\s*=\s*(help|note): (.*)$").unwrap();
+    static ref RE_SUB_DIAGNOSTIC: Regex = Regex::new(r"^(help|note): (.*)$").unwrap();
+}
+
+/// Spans and child messages of a rendered diagnostic.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Rendered {
+    pub span: Option<CodeSpan>,
+    pub secondary_spans: Vec<CodeSpan>,
+    pub children: Vec<ChildMessage>,
+}
+
+/// Which part of the diagnostic the snippet lines belong to.
+#[derive(Debug, PartialEq, Eq)]
+enum Section {
+    /// The snippet of the diagnostic itself.
+    Main,
+    /// The snippet of a `help:` or `note:` with its own location, and its message.
+    Sub(String),
+    /// Snippets of suggestions, which are not spans of the diagnostic.
+    Ignored,
+}
+
+#[derive(Debug, Default)]
+struct Parser {
+    result: Rendered,
+    primary_file: Option<PathBuf>,
+    file: Option<PathBuf>,
+    location: Option<(usize, usize)>,
+    /// The last line of code shown in the snippet.
+    code_line: Option<usize>,
+    /// Whether the code lines are prefixed with the bars of multi-line spans.
+    multi_line: bool,
+    /// Where the multi-line span being drawn begins.
+    multi_line_begin: Option<(usize, usize)>,
+}
+
+/// Parse the spans and child messages of a diagnostic, the first line of it,
+/// such as `warning: ...`, should be included.
+pub(crate) fn parse(raw: &str) -> Rendered {
+    let mut parser = Parser::default();
+    let mut section = Section::Main;
+    // a `help:` or `note:` line, which is a child message, unless a location follows
+    let mut pending_sub: Option<ChildMessage> = None;
+
+    let lines = raw.trim().lines().skip(1).collect::<Vec<_>>();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(cap) = RE_LOCATION.captures(line) {
+            let file = PathBuf::from(&cap[1]);
+            if parser.primary_file.is_none() {
+                parser.primary_file = Some(file.clone());
+            }
+            parser.file = Some(file);
+            parser.location = Some((cap[2].parse().unwrap_or(1), cap[3].parse().unwrap_or(1)));
+            parser.code_line = None;
+            parser.multi_line = has_multi_line_span(&lines[i + 1..]);
+            if let Some(sub) = pending_sub.take() {
+                section = Section::Sub(sub.message);
+            }
+            continue;
+        }
+        if let Some(sub) = pending_sub.take() {
+            parser.result.children.push(sub);
+            section = Section::Ignored;
+        }
+
+        if let Some(cap) = RE_CHILD.captures(line) {
+            parser.result.children.push(child(&cap[1], &cap[2]));
+        } else if let Some(cap) = RE_SUB_DIAGNOSTIC.captures(line) {
+            pending_sub = Some(child(&cap[1], &cap[2]));
+        } else if section == Section::Ignored {
+            continue;
+        } else if let Some(cap) = RE_CODE_LINE.captures(line) {
+            parser.code_line(cap[1].parse().unwrap_or(1), content(&cap[2]));
+        } else if let Some(cap) = RE_MARKER_LINE.captures(line) {
+            parser.marker_line(content(&cap[1]), &section);
+        }
+    }
+    if let Some(sub) = pending_sub {
+        parser.result.children.push(sub);
+    }
+    parser.finish()
+}
+
+fn child(kind: &str, message: &str) -> ChildMessage {
+    if kind == "help" {
+        ChildMessage::help(message.trim())
+    } else {
+        ChildMessage::note(message.trim())
+    }
+}
+
+/// The content after the `|` of a snippet line, without the space following it.
+fn content(after_bar: &str) -> &str {
+    after_bar.strip_prefix(' ').unwrap_or(after_bar)
+}
+
+impl Parser {
+    fn code_line(&mut self, line: usize, content: &str) {
+        self.code_line = Some(line);
+        if let Some(code) = content.strip_prefix("/ ") {
+            // a multi-line span begins at the first character of this line
+            let indent = code.len() - code.trim_start().len();
+            self.multi_line_begin = Some((line, indent + 1));
+        }
+    }
+
+    fn marker_line(&mut self, content: &str, section: &Section) {
+        let Some(line) = self.code_line else {
+            return;
+        };
+        let trimmed = content.trim_start();
+        if let Some(rest) = trimmed.strip_prefix("|_") {
+            // the end of a multi-line span, such as `|_____^ label`
+            let Some(begin) = self.multi_line_begin.take() else {
+                return;
+            };
+            let underscores = rest.len() - rest.trim_start_matches('_').len();
+            let marker_idx = content.len() - trimmed.len() + 2 + underscores;
+            let Some(marker) = content[marker_idx..].chars().next() else {
+                return;
+            };
+            let label = content[marker_idx + 1..].trim();
+            // the marker points at the last character, and code is prefixed with 2 chars
+            let span = CodeSpan::new(begin, (line, marker_idx));
+            self.add_span(span, marker == '^', label, section);
+            return;
+        }
+        if let Some(rest) = trimmed.strip_prefix('_') {
+            // the beginning of a multi-line span, such as ` _____^`
+            let underscores = rest.len() - rest.trim_start_matches('_').len();
+            let marker_idx = content.len() - trimmed.len() + 1 + underscores;
+            self.multi_line_begin = Some((line, marker_idx - 1));
+            return;
+        }
+
+        let prefix = if self.multi_line { 2 } else { 0 };
+        let Some(markers) = content.get(prefix..) else {
+            return;
+        };
+        let runs = marker_runs(markers);
+        let last = runs.len().saturating_sub(1);
+        for (i, (start, len, marker)) in runs.iter().copied().enumerate() {
+            let span = CodeSpan::new((line, start + 1), (line, start + len + 1));
+            let label = if i == last {
+                markers[start + len..].trim()
+            } else {
+                ""
+            };
+            self.add_span(span, marker == '^', label, section);
+        }
+    }
+
+    fn add_span(&mut self, mut span: CodeSpan, is_primary: bool, label: &str, section: &Section) {
+        // inline suggestions, such as `^^^ help: try: `*x``, are not labels
+        if let Some(help) = label.strip_prefix("help: ") {
+            self.result.children.push(ChildMessage::help(help));
+        } else if !label.is_empty() {
+            span.label = Some(label.to_string());
+        }
+        if self.file != self.primary_file {
+            span.file_path = self.file.clone();
+        }
+        match section {
+            Section::Main if is_primary && self.result.span.is_none() => {
+                self.result.span = Some(span);
+            }
+            Section::Sub(message) => {
+                if span.label.is_none() {
+                    span.label = Some(message.clone());
+                }
+                self.result.secondary_spans.push(span);
+            }
+            _ => self.result.secondary_spans.push(span),
+        }
+    }
+
+    fn finish(mut self) -> Rendered {
+        if self.result.span.is_none() {
+            // the diagnostic has a location, but its snippet isn't marked
+            if let Some((line, column)) = self.location {
+                self.result.span = Some(CodeSpan {
+                    begin_column: Some(column),
+                    ..CodeSpan::lines(line, line)
+                });
+            }
+        }
+        self.result
+    }
+}
+
+/// Whether the snippet starting from the given lines draws a multi-line span,
+/// so that all of its lines are prefixed with 2 more characters for the bars.
+fn has_multi_line_span(lines: &[&str]) -> bool {
+    lines
+        .iter()
+        .take_while(|line| !RE_LOCATION.is_match(line) && !RE_SUB_DIAGNOSTIC.is_match(line))
+        .any(|line| {
+            if let Some(cap) = RE_CODE_LINE.captures(line) {
+                let content = content(&cap[2]);
+                content.starts_with("/ ") || content.starts_with("| ")
+            } else if let Some(cap) = RE_MARKER_LINE.captures(line) {
+                let content = content(&cap[1]);
+                content.starts_with("|_") || content.trim_start().starts_with('_')
+            } else {
+                false
+            }
+        })
+}
+
+/// Runs of `^` or `-` at the beginning of a marker line, which are followed by a label,
+/// as their starting indices, lengths and the marker characters.
+fn marker_runs(markers: &str) -> Vec<(usize, usize, char)> {
+    let bytes = markers.as_bytes();
+    let mut runs = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' => i += 1,
+            marker @ (b'^' | b'-') => {
+                let start = i;
+                while i < bytes.len() && bytes[i] == marker {
+                    i += 1;
+                }
+                runs.push((start, i - start, char::from(marker)));
+            }
+            _ => break,
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Rendered};
+    use crate::parser::{ChildMessage, CodeSpan};
+
+    #[test]
+    fn single_line_spans() {
+        let raw = "warning: this creates an owned instance just for comparison
+  --> src/lib.rs:12:8
+   |
+12 |     if x.to_owned() == y {
+   |        ^^^^^^^^^^^^ help: try: `*x`
+   |
+   = help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#cmp_owned
+   = note: `-W clippy::cmp-owned` implied by `-W clippy::all`";
+
+        assert_eq!(
+            parse(raw),
+            Rendered {
+                span: Some(CodeSpan::new((12, 8), (12, 20))),
+                secondary_spans: vec![],
+                children: vec![
+                    ChildMessage::help("try: `*x`"),
+                    ChildMessage::help(
+                        "for further information visit \
+                        https://rust-lang.github.io/rust-clippy/master/index.html#cmp_owned"
+                    ),
+                    ChildMessage::note("`-W clippy::cmp-owned` implied by `-W clippy::all`"),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn secondary_and_multi_line_spans() {
+        let raw = "warning: unused variable: `x`
+ --> src/main.rs:3:9
+  |
+3 |       let x = 1;
+  |       ----^----- assigned here
+4 |       fn foo() {
+  |  ______________^
+5 | |         bar();
+6 | |     }
+  | |_____- the body
+  |
+help: if this is intentional, prefix it with an underscore
+  |
+3 |     let _x = 1;
+  |         ~~";
+
+        assert_eq!(
+            parse(raw),
+            Rendered {
+                span: Some(CodeSpan::new((3, 9), (3, 10))),
+                secondary_spans: vec![
+                    CodeSpan::new((3, 5), (3, 9)),
+                    CodeSpan::new((3, 10), (3, 15)).with_label("assigned here"),
+                    CodeSpan::new((4, 14), (6, 6)).with_label("the body"),
+                ],
+                children: vec![ChildMessage::help(
+                    "if this is intentional, prefix it with an underscore"
+                )],
+            }
+        );
+    }
+
+    #[test]
+    fn located_sub_diagnostic() {
+        let raw = "error: Undefined Behavior: Data race detected
+ --> src/bin/data_race.rs:7:38
+  |
+7 |   let t2 = thread::spawn(|| unsafe { UNSAFE = 2 });
+  |                                      ^^^^^^^^^^ Data race detected
+  |
+help: and (1) occurred earlier here
+ --> src/bin/other.rs:6:38
+  |
+6 |   let t1 = thread::spawn(|| unsafe { UNSAFE = 1 });
+  |                                      ^^^^^^^^^^
+  = note: BACKTRACE (of the first span):";
+
+        let rendered = parse(raw);
+        assert_eq!(
+            rendered.span,
+            Some(CodeSpan::new((7, 38), (7, 48)).with_label("Data race detected"))
+        );
+        assert_eq!(
+            rendered.secondary_spans,
+            [CodeSpan::new((6, 38), (6, 48))
+                .with_file("src/bin/other.rs")
+                .with_label("and (1) occurred earlier here")]
+        );
+        assert_eq!(
+            rendered.children,
+            [ChildMessage::note("BACKTRACE (of the first span):")]
+        );
+    }
+
+    #[test]
+    fn unmarked_location() {
+        let rendered = parse("warning: missing docs\n --> src/lib.rs:1:1");
+        assert_eq!(
+            rendered.span,
+            Some(CodeSpan {
+                begin_column: Some(1),
+                ..CodeSpan::lines(1, 1)
+            })
+        );
+    }
+}
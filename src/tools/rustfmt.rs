@@ -0,0 +1,298 @@
+//! A checker of the formatting of source code, backed by rustfmt, see [`RustfmtCfg`].
+//!
+//! Misformatted regions are taken from the output of `rustfmt --emit json`, which is only
+//! available on nightly, the diff output of `rustfmt --check` is parsed on stable instead.
+//! Each region is reported with the original text, and the text rustfmt expects.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+
+use super::{uses_tool, BuiltinChecker, CheckContext, SupportedTool};
+use crate::parser::{CheckInfo, CheckTool, CodeSpan, OutputSection, RulesCfg, RustfmtCfg};
+use crate::{utils, Error, Result};
+
+lazy_static! {
+    /// The header of each chunk in the output of `rustfmt --check`, such as
+    /// `Diff in src/lib.rs:12:`, or `Diff in src/lib.rs at line 12:` of older versions.
+    static ref DIFF_HEADER: Regex =
+        Regex::new(r"^Diff in (?P<path>.+?)(?::(?P<line>\d+):| at line (?P<old_line>\d+):)$")
+            .unwrap();
+}
+
+/// A region that is not formatted as rustfmt expects.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Mismatch {
+    pub original_begin_line: usize,
+    pub original_end_line: usize,
+    /// The original text, which is empty if rustfmt expects new lines to be inserted.
+    pub original: String,
+    /// The expected text, which is empty if rustfmt expects the lines to be removed.
+    pub expected: String,
+}
+
+/// Misformatted regions of a file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FileMismatches {
+    pub name: PathBuf,
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// The rustfmt checker.
+#[derive(Debug, Default)]
+pub struct RustfmtChecker {
+    pub cfg: RustfmtCfg,
+}
+
+impl RustfmtChecker {
+    pub fn new(cfg: RustfmtCfg) -> Self {
+        RustfmtChecker { cfg }
+    }
+
+    /// Arguments passed to rustfmt, other than the ones choosing the output.
+    fn args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--edition".to_string(),
+            self.cfg
+                .edition
+                .clone()
+                .unwrap_or_else(|| "2021".to_string()),
+        ];
+        if !self.cfg.config.is_empty() {
+            let config = self
+                .cfg
+                .config
+                .iter()
+                .map(|(key, value)| match value {
+                    serde_json::Value::String(s) => format!("{key}={s}"),
+                    other => format!("{key}={other}"),
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            args.extend(["--config".to_string(), config]);
+        }
+        args
+    }
+
+    /// Find misformatted regions of the files of a crate,
+    /// a path of file is treated the same as its parent directory.
+    pub fn mismatches(&self, path: &Path) -> Result<Vec<FileMismatches>> {
+        let root = if path.is_dir() {
+            path
+        } else {
+            path.parent()
+                .ok_or_else(|| Error::OrphanFilePath(path.to_path_buf()))?
+        };
+        let files = utils::files_with_extension(root, "rs")?;
+        if files.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let output = Command::new("rustfmt")
+            .args(["--unstable-features", "--emit", "json"])
+            .args(self.args())
+            .args(&files)
+            .output()?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let result = if output.status.success() {
+            serde_json::from_slice::<Vec<FileMismatches>>(&output.stdout)?
+        } else if stderr.contains("unstable-features") {
+            // rustfmt of a stable toolchain, which can't emit JSON
+            let output = Command::new("rustfmt")
+                .args(["--check", "--color", "never"])
+                .args(self.args())
+                .args(&files)
+                .output()?;
+            // the exit code is 1 for both misformatted files and other errors
+            if output.stdout.is_empty() && !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                return Err(Error::ToolFailed(SupportedTool::Rustfmt, stderr).into());
+            }
+            parse_check_diff(&String::from_utf8_lossy(&output.stdout))
+        } else {
+            let reason = stderr.trim().to_string();
+            return Err(Error::ToolFailed(SupportedTool::Rustfmt, reason).into());
+        };
+
+        // out-of-line modules are formatted along with their parents,
+        // so they could be reported more than once.
+        let mut seen = HashSet::new();
+        Ok(result
+            .into_iter()
+            .filter(|file| seen.insert(file.name.clone()))
+            .collect())
+    }
+
+    /// Check the formatting of the files of a crate.
+    pub fn check(&self, path: &Path) -> Result<Vec<CheckInfo>> {
+        let mut result = vec![];
+        for file in self.mismatches(path)? {
+            for mismatch in file.mismatches {
+                let help = if mismatch.original.is_empty() {
+                    "rustfmt expects lines to be inserted here"
+                } else if mismatch.expected.is_empty() {
+                    "rustfmt expects these lines to be removed"
+                } else {
+                    "code is not formatted as rustfmt expects"
+                };
+                result.push(CheckInfo {
+                    file_path: Some(file.name.clone()),
+                    defect_name: "misformatted-code".to_string(),
+                    tool: SupportedTool::Rustfmt,
+                    begin_line: Some(mismatch.original_begin_line),
+                    end_line: Some(mismatch.original_end_line),
+                    column: Some(1),
+                    code_string: mismatch.original,
+                    help_info: help.to_string(),
+                    additional_help_info: mismatch.expected,
+                    span: Some(CodeSpan::lines(
+                        mismatch.original_begin_line,
+                        mismatch.original_end_line,
+                    )),
+                    ..Default::default()
+                });
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl BuiltinChecker for RustfmtChecker {
+    fn from_rules(rule_cfg: &RulesCfg, checked_tools: &[&CheckTool]) -> Result<Option<Self>> {
+        let enabled =
+            rule_cfg.rustfmt.is_some() || uses_tool(checked_tools, SupportedTool::Rustfmt);
+        Ok(enabled.then(|| RustfmtChecker::new(rule_cfg.rustfmt.clone().unwrap_or_default())))
+    }
+
+    fn tool(&self) -> SupportedTool {
+        SupportedTool::Rustfmt
+    }
+
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)> {
+        Ok((self.check(ctx.path)?, None))
+    }
+}
+
+/// Parse the diff output of `rustfmt --check`, which looks like:
+///
+/// ```text
+/// Diff in src/lib.rs:1:
+/// -fn main(){}
+/// +fn main() {}
+///  use std::fmt;
+/// +use std::io;
+/// ```
+///
+/// The line of each chunk is the line of its first line in the original file.
+pub fn parse_check_diff(diff: &str) -> Vec<FileMismatches> {
+    let mut result: Vec<FileMismatches> = vec![];
+    let mut line = 0;
+    let mut current: Option<Mismatch> = None;
+
+    let flush = |result: &mut Vec<FileMismatches>, current: &mut Option<Mismatch>| {
+        if let (Some(mismatch), Some(file)) = (current.take(), result.last_mut()) {
+            file.mismatches.push(mismatch);
+        }
+    };
+
+    for text in diff.lines() {
+        if let Some(cap) = DIFF_HEADER.captures(text) {
+            flush(&mut result, &mut current);
+            let path = PathBuf::from(&cap["path"]);
+            line = cap
+                .name("line")
+                .or_else(|| cap.name("old_line"))
+                .and_then(|l| l.as_str().parse().ok())
+                .unwrap_or(1);
+            if result.last().is_none_or(|file| file.name != path) {
+                result.push(FileMismatches {
+                    name: path,
+                    mismatches: vec![],
+                });
+            }
+            continue;
+        }
+        let (marker, content) = text.split_at(text.len().min(1));
+        match marker {
+            "-" | "+" => {
+                let mismatch = current.get_or_insert_with(|| Mismatch {
+                    original_begin_line: line,
+                    original_end_line: line,
+                    original: String::new(),
+                    expected: String::new(),
+                });
+                if marker == "-" {
+                    mismatch.original_end_line = line;
+                    mismatch.original.push_str(content);
+                    mismatch.original.push('\n');
+                    line += 1;
+                } else {
+                    mismatch.expected.push_str(content);
+                    mismatch.expected.push('\n');
+                }
+            }
+            // context lines, including empty ones
+            _ => {
+                flush(&mut result, &mut current);
+                line += 1;
+            }
+        }
+    }
+    flush(&mut result, &mut current);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_check_diff, Mismatch};
+
+    #[test]
+    fn parse_diffs() {
+        let diff = "Diff in /tmp/lib.rs:1:
+-fn main(){let x=1;
+-    println!(\"{}\",x);}
++fn main() {
++    let x = 1;
++    println!(\"{}\", x);
++}
+ use std::fmt;
++use std::io;
+
+Diff in /tmp/lib.rs at line 20:
+ fn f() {
+-
+ }
+";
+        let files = parse_check_diff(diff);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name.to_str(), Some("/tmp/lib.rs"));
+        assert_eq!(
+            files[0].mismatches,
+            [
+                Mismatch {
+                    original_begin_line: 1,
+                    original_end_line: 2,
+                    original: "fn main(){let x=1;\n    println!(\"{}\",x);}\n".to_string(),
+                    expected: "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n"
+                        .to_string(),
+                },
+                Mismatch {
+                    original_begin_line: 4,
+                    original_end_line: 4,
+                    original: String::new(),
+                    expected: "use std::io;\n".to_string(),
+                },
+                Mismatch {
+                    original_begin_line: 21,
+                    original_end_line: 21,
+                    original: "\n".to_string(),
+                    expected: String::new(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Parsing source files into syntax trees, for the checkers that analyze
+//! the source code by themselves, without invoking the compiler.
+
+use std::path::{Path, PathBuf};
+
+use proc_macro2::Span;
+use syn::{Attribute, Expr, Lit, Meta};
+
+use crate::parser::{CheckInfo, CodeSpan};
+use crate::{utils, Error, Result};
+
+use super::SupportedTool;
+
+/// A parsed Rust source file.
+pub struct SourceFile {
+    pub path: PathBuf,
+    /// Path of the module this file defines, such as `crate::parser::guideline`.
+    pub module: String,
+    pub content: String,
+    pub ast: syn::File,
+}
+
+impl SourceFile {
+    pub fn parse(path: &Path, module: String) -> Result<Self> {
+        let content = utils::read_to_string(path)?;
+        let ast = syn::parse_file(&content)
+            .map_err(|e| Error::ParseSource(path.to_path_buf(), e.to_string()))?;
+        Ok(SourceFile {
+            path: path.to_path_buf(),
+            module,
+            content,
+            ast,
+        })
+    }
+
+    /// Get the text of a (1-based) line.
+    pub fn line(&self, line: usize) -> &str {
+        line.checked_sub(1)
+            .and_then(|idx| self.content.lines().nth(idx))
+            .unwrap_or_default()
+    }
+
+    /// Build a [`CheckInfo`] located at the given span of this file.
+    pub fn check_info(
+        &self,
+        tool: SupportedTool,
+        defect_name: &str,
+        span: Span,
+        help_info: String,
+    ) -> CheckInfo {
+        let (start, end) = (span.start(), span.end());
+        CheckInfo {
+            file_path: Some(self.path.clone()),
+            defect_name: defect_name.to_string(),
+            tool,
+            code_string: self.line(start.line).trim().to_string(),
+            help_info,
+            ..Default::default()
+        }
+        // columns of spans are 0-based, while compilers report 1-based ones.
+        .with_span(CodeSpan::new(
+            (start.line, start.column + 1),
+            (end.line, end.column + 1),
+        ))
+    }
+}
+
+/// The parsed source files of a crate.
+#[derive(Default)]
+pub struct Sources {
+    pub files: Vec<SourceFile>,
+    /// Files that couldn't be parsed, along with the reason, which are left out.
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+/// Parse all the Rust source files of a crate.
+///
+/// If the path is a file, such as `src/lib.rs`, the files under its directory are parsed,
+/// because the other modules of the crate are usually there. If it's the directory of
+/// a crate, the files under its `src` directory are parsed, leaving out the tests,
+/// benchmarks and build scripts.
+pub fn parse_sources(path: &Path) -> Result<Sources> {
+    let root = if path.is_dir() {
+        let src = path.join("src");
+        if src.is_dir() {
+            src
+        } else {
+            path.to_path_buf()
+        }
+    } else {
+        path.parent()
+            .ok_or_else(|| Error::OrphanFilePath(path.to_path_buf()))?
+            .to_path_buf()
+    };
+    let mut sources = Sources::default();
+    for file in utils::files_with_extension(&root, "rs")? {
+        let module = module_path(&root, &file);
+        match SourceFile::parse(&file, module) {
+            Ok(parsed) => sources.files.push(parsed),
+            Err(e) => sources.skipped.push((file, e.to_string())),
+        }
+    }
+    Ok(sources)
+}
+
+/// Get the documentation from the `#[doc]` attributes, which doc comments are turned into,
+/// or `None` if there's no documentation.
+pub fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(lit) => match &lit.lit {
+                    Lit::Str(doc) => Some(doc.value()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Whether the attributes contain `#[cfg(test)]`.
+pub fn is_cfg_test(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("cfg")
+            && attr
+                .parse_args::<syn::Ident>()
+                .is_ok_and(|ident| ident == "test")
+    })
+}
+
+/// Whether the documentation has a section with the given heading, such as `# Safety`.
+pub fn has_doc_section(attrs: &[Attribute], heading: &str) -> bool {
+    doc_comment(attrs).is_some_and(|doc| {
+        doc.lines().any(|line| {
+            let line = line.trim();
+            line.starts_with('#') && line.trim_start_matches('#').trim() == heading
+        })
+    })
+}
+
+/// Guess the module path of a file from its path relative to the source root.
+fn module_path(root: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(root).unwrap_or(file).with_extension("");
+    let mut segments = vec!["crate".to_string()];
+    segments.extend(
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+    if matches!(
+        segments.last().map(String::as_str),
+        Some("lib" | "main" | "mod")
+    ) {
+        segments.pop();
+    }
+    segments.join("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_doc_section, module_path};
+    use std::path::Path;
+
+    #[test]
+    fn guess_module_path() {
+        let root = Path::new("/src");
+        assert_eq!(module_path(root, Path::new("/src/lib.rs")), "crate");
+        assert_eq!(module_path(root, Path::new("/src/cli.rs")), "crate::cli");
+        assert_eq!(
+            module_path(root, Path::new("/src/parser/mod.rs")),
+            "crate::parser"
+        );
+        assert_eq!(
+            module_path(root, Path::new("/src/parser/book.rs")),
+            "crate::parser::book"
+        );
+    }
+
+    #[test]
+    fn doc_sections() {
+        let attrs: Vec<syn::Attribute> = syn::parse_quote! {
+            /// Read a byte.
+            ///
+            /// # Safety
+            ///
+            /// `p` must be valid.
+        };
+        assert!(has_doc_section(&attrs, "Safety"));
+        assert!(!has_doc_section(&attrs, "Panics"));
+        let attrs: Vec<syn::Attribute> = syn::parse_quote! {
+            /// Safety is not a section here.
+        };
+        assert!(!has_doc_section(&attrs, "Safety"));
+    }
+}
@@ -0,0 +1,449 @@
+//! A checker auditing the usage of unsafe code.
+//!
+//! Every `unsafe` block, `unsafe fn`, `unsafe impl` and `extern` block is located,
+//! and is expected to be justified by an adjacent `// SAFETY:` comment, an `unsafe fn`
+//! could have a `# Safety` section in its documentation instead.
+//! Sites without any justification are reported, while all sites are counted
+//! in an [`UnsafeInventory`].
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use proc_macro2::Span;
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+use super::source::{has_doc_section, SourceFile};
+use super::{uses_tool, BuiltinChecker, CheckContext, SourceChecker, SupportedTool};
+use crate::parser::{CheckInfo, CheckTool, CodeSpan, OutputSection, RulesCfg};
+use crate::Result;
+
+/// Kinds of unsafe code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsafeKind {
+    Block,
+    Fn,
+    Impl,
+    ExternBlock,
+}
+
+impl UnsafeKind {
+    /// Name of the defect reported when a site of this kind is not justified.
+    pub fn defect_name(&self) -> &'static str {
+        match self {
+            UnsafeKind::Block => "undocumented-unsafe-block",
+            UnsafeKind::Fn => "undocumented-unsafe-fn",
+            UnsafeKind::Impl => "undocumented-unsafe-impl",
+            UnsafeKind::ExternBlock => "undocumented-extern-block",
+        }
+    }
+}
+
+impl Display for UnsafeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            UnsafeKind::Block => "unsafe block",
+            UnsafeKind::Fn => "unsafe fn",
+            UnsafeKind::Impl => "unsafe impl",
+            UnsafeKind::ExternBlock => "extern block",
+        };
+        f.write_str(str)
+    }
+}
+
+/// A place where unsafe code is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsafeSite {
+    pub file_path: PathBuf,
+    /// Path of the module containing this site, including inline modules.
+    pub module: String,
+    pub kind: UnsafeKind,
+    pub line: usize,
+    /// The 1-based column of the `unsafe` (or `extern`) keyword.
+    pub column: usize,
+    pub end_line: usize,
+    /// Whether it's justified by a `// SAFETY:` comment or a `# Safety` section.
+    pub documented: bool,
+}
+
+/// Number of unsafe sites of each kind.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsafeCount {
+    pub blocks: usize,
+    pub functions: usize,
+    pub impls: usize,
+    pub extern_blocks: usize,
+    /// Number of the sites above without justification.
+    pub undocumented: usize,
+}
+
+impl UnsafeCount {
+    pub fn total(&self) -> usize {
+        self.blocks + self.functions + self.impls + self.extern_blocks
+    }
+
+    fn add(&mut self, site: &UnsafeSite) {
+        match site.kind {
+            UnsafeKind::Block => self.blocks += 1,
+            UnsafeKind::Fn => self.functions += 1,
+            UnsafeKind::Impl => self.impls += 1,
+            UnsafeKind::ExternBlock => self.extern_blocks += 1,
+        }
+        if !site.documented {
+            self.undocumented += 1;
+        }
+    }
+
+    fn add_count(&mut self, other: &UnsafeCount) {
+        self.blocks += other.blocks;
+        self.functions += other.functions;
+        self.impls += other.impls;
+        self.extern_blocks += other.extern_blocks;
+        self.undocumented += other.undocumented;
+    }
+}
+
+/// Counts of unsafe sites in the checked crate, per file and per module.
+///
+/// Files and modules are sorted by their paths, so that the inventories of
+/// different runs could be compared line by line.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsafeInventory {
+    pub total: UnsafeCount,
+    pub files: BTreeMap<PathBuf, UnsafeCount>,
+    pub modules: BTreeMap<String, UnsafeCount>,
+}
+
+impl<'a> FromIterator<&'a UnsafeSite> for UnsafeInventory {
+    fn from_iter<T: IntoIterator<Item = &'a UnsafeSite>>(iter: T) -> Self {
+        let mut inventory = UnsafeInventory::default();
+        for site in iter {
+            inventory.total.add(site);
+            inventory
+                .files
+                .entry(site.file_path.clone())
+                .or_default()
+                .add(site);
+            inventory
+                .modules
+                .entry(site.module.clone())
+                .or_default()
+                .add(site);
+        }
+        inventory
+    }
+}
+
+impl UnsafeInventory {
+    /// Merge the inventory of another run, such as of another crate.
+    ///
+    /// Module paths are relative to their crates, such as `crate::foo`, so counts of
+    /// the same file or module are added up, as well as the totals.
+    pub fn merge(&mut self, other: UnsafeInventory) {
+        for (file, count) in other.files {
+            self.files.entry(file).or_default().add_count(&count);
+        }
+        for (module, count) in other.modules {
+            self.modules.entry(module).or_default().add_count(&count);
+        }
+        self.total.add_count(&other.total);
+    }
+}
+
+/// The unsafe audit checker.
+#[derive(Debug, Default)]
+pub struct UnsafeAudit;
+
+impl UnsafeAudit {
+    /// Find all unsafe sites in a file.
+    pub fn sites(&self, file: &SourceFile) -> Vec<UnsafeSite> {
+        let mut visitor = UnsafeVisitor {
+            file,
+            modules: vec![file.module.clone()],
+            anchors: vec![],
+            sites: vec![],
+        };
+        visitor.visit_file(&file.ast);
+        visitor.sites
+    }
+
+    /// Take an inventory of all unsafe sites in a crate.
+    pub fn inventory(&self, files: &[SourceFile]) -> UnsafeInventory {
+        let sites = files
+            .iter()
+            .flat_map(|file| self.sites(file))
+            .collect::<Vec<_>>();
+        sites.iter().collect()
+    }
+}
+
+impl SourceChecker for UnsafeAudit {
+    fn check_file(&self, file: &SourceFile) -> Vec<CheckInfo> {
+        self.sites(file)
+            .into_iter()
+            .filter(|site| !site.documented)
+            .map(|site| {
+                let help = match site.kind {
+                    UnsafeKind::Fn => format!(
+                        "{} without a `# Safety` section or a `// SAFETY:` comment",
+                        site.kind
+                    ),
+                    _ => format!("{} without a `// SAFETY:` comment", site.kind),
+                };
+                let line = file.line(site.line);
+                CheckInfo {
+                    file_path: Some(site.file_path),
+                    defect_name: site.kind.defect_name().to_string(),
+                    tool: SupportedTool::UnsafeAudit,
+                    begin_line: Some(site.line),
+                    end_line: Some(site.end_line),
+                    column: Some(site.column),
+                    code_string: line.trim().to_string(),
+                    help_info: help,
+                    span: Some(CodeSpan {
+                        begin_column: Some(site.column),
+                        ..CodeSpan::lines(site.line, site.end_line)
+                    }),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+impl BuiltinChecker for UnsafeAudit {
+    fn from_rules(_: &RulesCfg, checked_tools: &[&CheckTool]) -> Result<Option<Self>> {
+        Ok(uses_tool(checked_tools, SupportedTool::UnsafeAudit).then_some(UnsafeAudit))
+    }
+
+    fn tool(&self) -> SupportedTool {
+        SupportedTool::UnsafeAudit
+    }
+
+    fn needs_sources(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &CheckContext) -> Result<(Vec<CheckInfo>, Option<OutputSection>)> {
+        let inventory = self.inventory(ctx.sources);
+        Ok((
+            self.check_sources(ctx.sources),
+            Some(OutputSection::UnsafeInventory(inventory)),
+        ))
+    }
+}
+
+struct UnsafeVisitor<'a> {
+    file: &'a SourceFile,
+    /// Paths of the module being visited and its parents.
+    modules: Vec<String>,
+    /// Start lines of the statements and items being visited, the innermost one comes last.
+    /// A comment above them also justifies the unsafe blocks inside, such as
+    /// `let x = unsafe { .. };`.
+    anchors: Vec<usize>,
+    sites: Vec<UnsafeSite>,
+}
+
+impl UnsafeVisitor<'_> {
+    fn add(&mut self, kind: UnsafeKind, keyword: Span, end: Span, documented: bool) {
+        let start = keyword.start();
+        let documented = documented
+            || has_safety_comment(self.file, start.line, start.column)
+            || self
+                .anchors
+                .last()
+                .is_some_and(|line| has_safety_comment(self.file, *line, 0));
+        self.sites.push(UnsafeSite {
+            file_path: self.file.path.clone(),
+            module: self.modules.last().cloned().unwrap_or_default(),
+            kind,
+            line: start.line,
+            column: start.column + 1,
+            end_line: end.end().line,
+            documented,
+        });
+    }
+
+    fn with_anchor<F: FnOnce(&mut Self)>(&mut self, span: Span, f: F) {
+        self.anchors.push(span.start().line);
+        f(self);
+        self.anchors.pop();
+    }
+}
+
+/// Whether the code at the given (1-based) line, and 0-based column, is preceded by
+/// a `SAFETY:` comment, either on the lines right above it, or on the same line.
+///
+/// Attributes and other comments between them are allowed, but blank lines are not.
+/// Lines inside a `/* ... */` block only count once the start of the block is found,
+/// as the lines are scanned upwards.
+fn has_safety_comment(file: &SourceFile, line: usize, column: usize) -> bool {
+    let current = file.line(line);
+    let before = current
+        .char_indices()
+        .nth(column)
+        .map_or(current, |(idx, _)| &current[..idx]);
+    if before.contains("SAFETY:") {
+        return true;
+    }
+    // whether a `SAFETY:` was found inside a block comment, once it's open
+    let mut in_block: Option<bool> = None;
+    for n in (1..line).rev() {
+        let text = file.line(n).trim();
+        if let Some(found) = in_block {
+            let found = found || text.contains("SAFETY:");
+            if text.contains("/*") {
+                if found {
+                    return true;
+                }
+                in_block = None;
+            } else {
+                in_block = Some(found);
+            }
+            continue;
+        }
+        if text.ends_with("*/") && !text.starts_with("/*") {
+            in_block = Some(text.contains("SAFETY:"));
+            continue;
+        }
+        let is_comment = text.starts_with("//") || text.starts_with("/*");
+        if is_comment && text.contains("SAFETY:") {
+            return true;
+        }
+        if !is_comment && !text.starts_with("#[") {
+            return false;
+        }
+    }
+    false
+}
+
+impl<'ast> Visit<'ast> for UnsafeVisitor<'_> {
+    fn visit_item(&mut self, item: &'ast syn::Item) {
+        self.with_anchor(item.span(), |v| visit::visit_item(v, item));
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast syn::Stmt) {
+        self.with_anchor(stmt.span(), |v| visit::visit_stmt(v, stmt));
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        let parent = self.modules.last().cloned().unwrap_or_default();
+        self.modules.push(format!("{parent}::{}", item.ident));
+        visit::visit_item_mod(self, item);
+        self.modules.pop();
+    }
+
+    fn visit_expr_unsafe(&mut self, expr: &'ast syn::ExprUnsafe) {
+        let end = expr.block.brace_token.span.close();
+        self.add(UnsafeKind::Block, expr.unsafe_token.span, end, false);
+        visit::visit_expr_unsafe(self, expr);
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        if let Some(unsafety) = &item.sig.unsafety {
+            let end = item.block.brace_token.span.close();
+            let documented = has_doc_section(&item.attrs, "Safety");
+            self.add(UnsafeKind::Fn, unsafety.span, end, documented);
+        }
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        if let Some(unsafety) = &item.sig.unsafety {
+            let end = item.block.brace_token.span.close();
+            let documented = has_doc_section(&item.attrs, "Safety");
+            self.add(UnsafeKind::Fn, unsafety.span, end, documented);
+        }
+        self.with_anchor(item.span(), |v| visit::visit_impl_item_fn(v, item));
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast syn::TraitItemFn) {
+        if let Some(unsafety) = &item.sig.unsafety {
+            let documented = has_doc_section(&item.attrs, "Safety");
+            self.add(UnsafeKind::Fn, unsafety.span, item.span(), documented);
+        }
+        self.with_anchor(item.span(), |v| visit::visit_trait_item_fn(v, item));
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        if let Some(unsafety) = &item.unsafety {
+            let end = item.brace_token.span.close();
+            self.add(UnsafeKind::Impl, unsafety.span, end, false);
+        }
+        visit::visit_item_impl(self, item);
+    }
+
+    fn visit_item_foreign_mod(&mut self, item: &'ast syn::ItemForeignMod) {
+        let keyword = item
+            .unsafety
+            .map_or(item.abi.extern_token.span, |unsafety| unsafety.span);
+        let end = item.brace_token.span.close();
+        self.add(UnsafeKind::ExternBlock, keyword, end, false);
+        visit::visit_item_foreign_mod(self, item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UnsafeAudit, UnsafeKind};
+    use crate::tools::source::SourceFile;
+
+    fn audit(content: &str) -> Vec<(UnsafeKind, usize, bool)> {
+        let file = SourceFile {
+            path: "lib.rs".into(),
+            module: "crate".into(),
+            content: content.to_string(),
+            ast: syn::parse_file(content).unwrap(),
+        };
+        UnsafeAudit
+            .sites(&file)
+            .into_iter()
+            .map(|site| (site.kind, site.line, site.documented))
+            .collect()
+    }
+
+    #[test]
+    fn safety_comments() {
+        let content = "fn f(p: *const u8) -> u8 {
+    // SAFETY: the caller guarantees `p` is valid.
+    let a = unsafe { *p };
+
+    // the comment is not a justification.
+    let b = unsafe { *p };
+    let c = foo(
+        unsafe { *p },
+    );
+    /* SAFETY: `p` is valid. */ unsafe { *p }
+}
+";
+        assert_eq!(
+            audit(content),
+            [
+                (UnsafeKind::Block, 3, true),
+                (UnsafeKind::Block, 6, false),
+                (UnsafeKind::Block, 8, false),
+                (UnsafeKind::Block, 10, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn safety_comments_before_code() {
+        let content = "fn f(p: *mut u8) {
+    /*
+     * SAFETY: the caller guarantees `p` is valid.
+     */
+    unsafe { *p = 0 };
+    // SAFETY: `p` is still valid.
+    *p = 1;
+    unsafe { *p = 2 };
+}
+";
+        assert_eq!(
+            audit(content),
+            [(UnsafeKind::Block, 5, true), (UnsafeKind::Block, 8, false)]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::Result;
 use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// A wrapper to `std::fs::read_to_string`, which will try to read a file,
 /// then return its content as a `String`.
@@ -42,3 +42,61 @@ where
 {
     write_(content, path, false)
 }
+
+/// Recursively collect the paths of files with the given extension under a directory.
+///
+/// Hidden directories and `target` directories are skipped, and the result is
+/// sorted, so that callers will get a stable order.
+pub fn files_with_extension<P: AsRef<Path>>(dir: P, ext: &str) -> Result<Vec<PathBuf>> {
+    let mut result = vec![];
+    let mut dirs = vec![dir.as_ref().to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if path.is_dir() {
+                if !name.starts_with('.') && name != "target" {
+                    dirs.push(path);
+                }
+            } else if path.extension().is_some_and(|e| e == ext) {
+                result.push(path);
+            }
+        }
+    }
+
+    result.sort();
+    Ok(result)
+}
+
+/// Find a file with the given name in a directory or its closest parent,
+/// for example, finding the `Cargo.toml` of the crate that a source file belongs to.
+pub fn find_in_ancestors<P: AsRef<Path>>(start: P, file_name: &str) -> Option<PathBuf> {
+    start
+        .as_ref()
+        .ancestors()
+        .map(|dir| dir.join(file_name))
+        .find(|path| path.is_file())
+}
+
+/// Resolve a relative path against a directory or its closest parent where the file exists,
+/// because tools report paths relative to the workspace, which could be a parent of the
+/// checked crate. Absolute paths are returned as they are, if the file exists.
+pub fn resolve_in_ancestors<P: AsRef<Path>, F: AsRef<Path>>(start: P, path: F) -> Option<PathBuf> {
+    let path = path.as_ref();
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    start
+        .as_ref()
+        .ancestors()
+        .map(|dir| dir.join(path))
+        .find(|file| file.is_file())
+}
+
+/// Canonicalize a path if possible, so that the same file compares equal when tools
+/// report it relative to different directories. Otherwise the path is returned as it is.
+pub fn canonical<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
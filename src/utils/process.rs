@@ -1,4 +1,8 @@
-use std::{ffi::OsStr, io::ErrorKind, process::Command};
+use std::{
+    ffi::OsStr,
+    io::ErrorKind,
+    process::{Command, Stdio},
+};
 
 use crate::Result;
 
@@ -6,10 +10,16 @@ use crate::Result;
 ///
 /// - If the command does not exist, this will return `Ok(false)`;
 /// - If the command exists but failed to execute, likely due to its file permission,
-/// this will return the actual error;
+///   this will return the actual error;
 /// - If the command exists and successfully executed, this will return `Ok(true)`;
 pub fn command_exist<A: AsRef<OsStr>, V: AsRef<OsStr>>(app: A, args: &[V]) -> Result<bool> {
-    if let Some(err) = Command::new(app).args(args).spawn().err() {
+    if let Some(err) = Command::new(app)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .err()
+    {
         if err.kind() == ErrorKind::NotFound {
             Ok(false)
         } else {
@@ -19,3 +29,19 @@ pub fn command_exist<A: AsRef<OsStr>, V: AsRef<OsStr>>(app: A, args: &[V]) -> Re
         Ok(true)
     }
 }
+
+/// Get the version of a program by running it with the given arguments, such as `-V`,
+/// which is the first line of its output, or `None` if it couldn't be run successfully.
+pub fn command_version<A: AsRef<OsStr>, V: AsRef<OsStr>>(app: A, args: &[V]) -> Option<String> {
+    let output = Command::new(app)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = stdout.lines().next()?.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
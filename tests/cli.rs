@@ -29,11 +29,1176 @@ fn run_with_rules_and_cli_paths() {
                 "--output",
                 output_file.to_str().unwrap(),
             ])
+            .env_remove("LANG")
             .status()
             .unwrap();
-        let expected_output = fs::read_to_string(expected_output_file).unwrap();
-        let output_file_content = fs::read_to_string(output_file).unwrap();
         assert!(st.success());
-        assert_eq!(expected_output, output_file_content);
+        let expected_output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(expected_output_file).unwrap()).unwrap();
+        let mut output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+
+        // metadata changes in every run
+        let metadata = output.as_object_mut().unwrap().remove("metadata").unwrap();
+        assert_eq!(metadata["eunomia_version"], env!("CARGO_PKG_VERSION"));
+        assert!(metadata["rules_file"]
+            .as_str()
+            .unwrap()
+            .ends_with("default_rules.json"));
+        assert_eq!(metadata["rules_hash"].as_str().unwrap().len(), 16);
+        assert!(
+            metadata["started_at"].as_str().unwrap() <= metadata["finished_at"].as_str().unwrap()
+        );
+        let tools = metadata["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert!(tools.contains(&"clippy"));
+        let clippy = &metadata["tools"][tools.iter().position(|t| *t == "clippy").unwrap()];
+        assert!(clippy["duration_ms"].is_u64());
+        assert_eq!(clippy["exit_code"], 0);
+
+        // so does the time in the summary
+        let summary = output.as_object_mut().unwrap().remove("summary").unwrap();
+        assert_eq!(summary["total"], 16);
+        assert_eq!(summary["by_file"].as_object().unwrap().len(), 1);
+        assert!(summary["compliance"].as_f64().unwrap() < 1.0);
+
+        // file paths are resolved against the checked crate
+        let mock_dir = cfg.test_dir.join("mock");
+        for info in output["check_info"].as_array_mut().unwrap() {
+            let path = std::path::PathBuf::from(info["file_path"].as_str().unwrap());
+            let relative = path.strip_prefix(&mock_dir).unwrap();
+            info["file_path"] = relative.to_str().unwrap().into();
+        }
+        assert_eq!(expected_output, output);
+    });
+}
+
+#[test]
+fn explain_guidelines() {
+    setup(|cfg| {
+        let res = Command::new(&cfg.bin_path)
+            .args(["explain", "G.TYP.BOL.01", "g.var.02"])
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output_str = String::from_utf8(res.stdout).unwrap();
+        assert!(
+            output_str.starts_with("G.TYP.BOL.01: Do not compare booleans with boolean literals\n")
+        );
+        assert!(output_str.contains("\nG.VAR.02: Do not use non-ASCII characters as identifiers\n"));
+        assert!(output_str.contains("tools: clippy (clippy::bool_comparison)\n"));
+
+        let res = Command::new(&cfg.bin_path)
+            .args(["explain", "G.NOT.EXIST.01"])
+            .output()
+            .unwrap();
+        assert!(!res.status.success());
+    });
+}
+
+#[test]
+fn explain_guidelines_localized() {
+    setup(|cfg| {
+        let res = Command::new(&cfg.bin_path)
+            .args(["explain", "--lang", "zh", "G.VAR.02"])
+            .env("LANG", "en_US.UTF-8")
+            .output()
+            .unwrap();
+        let output_str = String::from_utf8(res.stdout).unwrap();
+        assert!(output_str.starts_with("G.VAR.02: 不应使用非 ASCII 字符作为标识符\n"));
+        assert!(output_str.contains("\n检测工具: rustc (non-ascii-idents)\n"));
+
+        let res = Command::new(&cfg.bin_path)
+            .args(["explain", "G.VAR.02"])
+            .env("LANG", "zh_CN.UTF-8")
+            .output()
+            .unwrap();
+        let output_str = String::from_utf8(res.stdout).unwrap();
+        assert!(output_str.starts_with("G.VAR.02: 不应使用非 ASCII 字符作为标识符\n"));
+    });
+}
+
+#[test]
+fn import_guidelines_from_book() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("imported_guidelines.json");
+        let _ = fs::remove_file(&output_file);
+        let import = || {
+            Command::new(&cfg.bin_path)
+                .args(["guidelines", "import", "--output"])
+                .arg(&output_file)
+                .arg(cfg.test_dir.join("data").join("guideline_book"))
+                .env_remove("LANG")
+                .output()
+                .unwrap()
+        };
+
+        let res = import();
+        assert!(res.status.success());
+        let output_str = String::from_utf8(res.stdout).unwrap();
+        assert!(output_str.starts_with("4 added, 0 removed, 0 changed\n"));
+        assert!(output_str.contains("+ G.TYP.BOL.01\n"));
+
+        // importing again doesn't change anything
+        let res = import();
+        assert_eq!(
+            String::from_utf8(res.stdout).unwrap(),
+            "0 added, 0 removed, 0 changed\n"
+        );
+
+        // the generated catalogue can be used in place of the built-in one
+        let res = Command::new(&cfg.bin_path)
+            .args(["--guidelines"])
+            .arg(&output_file)
+            .args(["--lang", "zh", "explain", "G.NAM.01"])
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output_str = String::from_utf8(res.stdout).unwrap();
+        assert!(output_str.starts_with("G.NAM.01: 使用统一的命名风格\n"));
+    });
+}
+
+#[test]
+fn validate_guideline_lints() {
+    setup(|cfg| {
+        let res = Command::new(&cfg.bin_path)
+            .args(["validate", "--guidelines"])
+            .arg(
+                cfg.test_dir
+                    .join("data")
+                    .join("guidelines_invalid_lints.json"),
+            )
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(!res.status.success());
+        let output_str = String::from_utf8(res.stdout).unwrap();
+        assert!(output_str.contains(
+            "G.TYP.BOL.01: unknown clippy lint `clippy::bool_comparsion`, \
+            did you mean: `clippy::bool_comparison`?\n"
+        ));
+        assert!(output_str.contains(
+            "G.EXP.04: clippy lint `clippy::double_neg` has been renamed to `double_negations`\n"
+        ));
+        assert!(output_str.contains("G.MEM.BOX.01: rustc lint `box_pointers` has been removed: "));
+        // lints written with dashes are still valid
+        assert!(!output_str.contains("G.NAM.01"));
+        assert!(output_str.ends_with("found 3 problem(s) in the guidelines\n"));
+
+        let res = Command::new(&cfg.bin_path)
+            .args(["validate"])
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+    });
+}
+
+#[test]
+fn fix_guideline_violations() {
+    setup(|cfg| {
+        // work on a copy of the mocked crate, which is not under version control
+        let crate_dir = cfg.output_dir.join("fix_mock");
+        let _ = fs::remove_dir_all(&crate_dir);
+        fs::create_dir_all(crate_dir.join("src")).unwrap();
+        let mock_dir = cfg.test_dir.join("mock");
+        for file in ["Cargo.toml", "src/lib.rs", "src/lints.rs"] {
+            fs::copy(mock_dir.join(file), crate_dir.join(file)).unwrap();
+        }
+        let rule_file = crate_dir.join("rules.json");
+        fs::write(
+            &rule_file,
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["G.TYP.BOL.01", "G.EXP.08"]}"#,
+        )
+        .unwrap();
+        let fix = |extra_args: &[&str]| {
+            Command::new(&cfg.bin_path)
+                .args(["fix", "-r"])
+                .arg(&rule_file)
+                .arg("-s")
+                .arg(crate_dir.join("src").join("lib.rs"))
+                .args(extra_args)
+                .env_remove("LANG")
+                .output()
+                .unwrap()
+        };
+
+        let res = fix(&["--dry-run"]);
+        assert!(res.status.success());
+        let output_str = String::from_utf8(res.stdout).unwrap();
+        assert!(output_str.contains("\n-    if flag == true {}\n+    if flag {}\n"));
+        assert!(output_str.contains("\n-    if s == \"\" {}\n+    if s.is_empty() {}\n"));
+        // other lints are left untouched
+        assert!(!output_str.contains("std::mem::swap"));
+
+        let res = fix(&[]);
+        assert!(!res.status.success());
+        let lints_file = crate_dir.join("src").join("lints.rs");
+        let original = fs::read_to_string(mock_dir.join("src").join("lints.rs")).unwrap();
+        assert_eq!(fs::read_to_string(&lints_file).unwrap(), original);
+
+        let res = fix(&["--force"]);
+        assert!(res.status.success());
+        let output_str = String::from_utf8(res.stdout).unwrap();
+        assert!(output_str.contains("applied 2 fix(es) in 1 file(s)\n"));
+        assert!(output_str.ends_with("all violations of the guidelines are gone\n"));
+        let fixed = fs::read_to_string(&lints_file).unwrap();
+        assert!(fixed.contains("    if flag {}\n") && fixed.contains("    if s.is_empty() {}\n"));
+    });
+}
+
+#[test]
+fn check_guidelines_with_patterns() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("output_patterns.json");
+        let rule_file = cfg.output_dir.join("rules_patterns.json");
+        fs::write(
+            &rule_file,
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["P.NAM.05", "P.NAM.09"]}"#,
+        )
+        .unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/source_patterns/src/lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+        let mut found = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|info| {
+                (
+                    info["defect_name"].as_str().unwrap(),
+                    info["begin_line"].as_u64().unwrap(),
+                    info["guideline_list"][0]["id"].as_str().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("item:pub-fn:!^get_", 10, "p.nam.05"),
+                ("item:static:^G_", 8, "p.nam.09"),
+            ]
+        );
+    });
+}
+
+#[test]
+fn audit_unsafe_code() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("output_unsafe_audit.json");
+        let rule_file = cfg.output_dir.join("rules_unsafe_audit.json");
+        fs::write(
+            &rule_file,
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["G.UNS.SAS.01", "G.UNS.SAS.02"]}"#,
+        )
+        .unwrap();
+        let src_dir = cfg.test_dir.join("data/unsafe_audit/src");
+        let res = Command::new(&cfg.bin_path)
+            .arg("--guidelines")
+            .arg(cfg.test_dir.join("data/guidelines_unsafe_audit.json"))
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(src_dir.join("lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+
+        let mut found = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|info| {
+                (
+                    info["defect_name"].as_str().unwrap(),
+                    info["begin_line"].as_u64().unwrap(),
+                    info["column"].as_u64().unwrap(),
+                    info["guideline_list"].as_array().unwrap().len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        found.sort_by_key(|f| f.1);
+        assert_eq!(
+            found,
+            [
+                ("undocumented-extern-block", 1, 1, 0),
+                ("undocumented-unsafe-fn", 15, 5, 1),
+                ("undocumented-unsafe-block", 16, 5, 1),
+                ("undocumented-unsafe-impl", 24, 1, 0),
+                ("undocumented-unsafe-block", 28, 20, 1),
+            ]
+        );
+
+        let inventory = &output["unsafe_inventory"];
+        assert_eq!(
+            inventory["total"],
+            serde_json::json!({
+                "blocks": 4,
+                "functions": 2,
+                "impls": 2,
+                "extern_blocks": 1,
+                "undocumented": 5,
+            })
+        );
+        let ffi = src_dir.join("ffi.rs");
+        assert_eq!(
+            inventory["files"][ffi.to_str().unwrap()]["extern_blocks"],
+            1
+        );
+        assert_eq!(inventory["modules"]["crate::ffi"]["blocks"], 1);
+        assert_eq!(inventory["modules"]["crate::io"]["undocumented"], 1);
+        assert_eq!(inventory["modules"]["crate"]["undocumented"], 3);
+    });
+}
+
+#[test]
+fn check_complexity_metrics() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("output_metrics.json");
+        let rule_file = cfg.output_dir.join("rules_metrics.json");
+        fs::write(
+            &rule_file,
+            r#"{"file_path": "src/lib.rs", "metrics": {"max_nesting_depth": 3, "max_cognitive_complexity": 12}}"#,
+        )
+        .unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/metrics/src/lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+
+        let found = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|info| {
+                (
+                    info["defect_name"].as_str().unwrap(),
+                    info["begin_line"].as_u64().unwrap(),
+                    info["help_info"].as_str().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                (
+                    "deep-nesting",
+                    7,
+                    "the nesting depth of function `classify` is 5, exceeding the limit of 3"
+                ),
+                (
+                    "high-cognitive-complexity",
+                    7,
+                    "the cognitive complexity of function `classify` is 16, exceeding the limit of 12"
+                ),
+                (
+                    "too-many-parameters",
+                    27,
+                    "the number of parameters of function `configure` is 6, exceeding the limit of 5"
+                ),
+            ]
+        );
+
+        let metrics = &output["metrics"];
+        assert_eq!(
+            metrics["functions"][1],
+            serde_json::json!({
+                "name": "classify",
+                "module": "crate",
+                "file_path": cfg.test_dir.join("data/metrics/src/lib.rs"),
+                "line": 7,
+                "lines": 19,
+                "params": 2,
+                "nesting_depth": 5,
+                "cyclomatic_complexity": 8,
+                "cognitive_complexity": 16,
+            })
+        );
+        assert_eq!(metrics["modules"]["crate"]["functions"], 3);
+        assert_eq!(metrics["modules"]["crate"]["max_cyclomatic_complexity"], 8);
+    });
+}
+
+#[test]
+fn check_documentation_coverage() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("output_doc_coverage.json");
+        let rule_file = cfg.output_dir.join("rules_doc_coverage.json");
+        fs::write(
+            &rule_file,
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["G.CMT.01"]}"#,
+        )
+        .unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .arg("--guidelines")
+            .arg(cfg.test_dir.join("data/guidelines_doc_coverage.json"))
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/doc_coverage/src/lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+
+        let mut found = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|info| {
+                let path = info["file_path"].as_str().unwrap();
+                (
+                    path.rsplit('/').next().unwrap(),
+                    info["begin_line"].as_u64().unwrap(),
+                    info["defect_name"].as_str().unwrap(),
+                    info["guideline_list"][0]["id"].as_str().unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("lib.rs", 7, "missing-errors-doc", "g.cmt.01"),
+                ("lib.rs", 26, "missing-safety-doc", ""),
+                ("lib.rs", 30, "missing-docs", ""),
+                ("lib.rs", 33, "missing-docs", ""),
+                ("shapes.rs", 10, "missing-docs", ""),
+            ]
+        );
+
+        let coverage = &output["doc_coverage"];
+        assert_eq!(
+            coverage["total"],
+            serde_json::json!({"public_items": 12, "documented": 9, "percentage": 75.0})
+        );
+        assert_eq!(coverage["modules"]["crate"]["public_items"], 8);
+        assert_eq!(coverage["modules"]["crate::shapes"]["documented"], 3);
+        assert!(coverage["modules"].get("crate::private").is_none());
+    });
+}
+
+#[test]
+fn check_manifest_policies() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("output_manifest.json");
+        let rule_file = cfg.output_dir.join("rules_manifest.json");
+        fs::write(
+            &rule_file,
+            r#"{
+    "file_path": "src/lib.rs",
+    "coding_guidelines": ["G.CAR.02", "G.CAR.04"],
+    "manifest": {
+        "banned_crates": ["openssl", "openssl-sys"],
+        "allowed_crates": ["serde", "regex", "openssl", "libc", "tempfile"],
+        "allowed_duplicates": []
+    }
+}"#,
+        )
+        .unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .arg("--guidelines")
+            .arg(cfg.test_dir.join("data/guidelines_manifest.json"))
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/manifest/src/lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+
+        let mut found = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|info| {
+                let path = info["file_path"].as_str().unwrap();
+                (
+                    path.rsplit('/').next().unwrap(),
+                    info["begin_line"].as_u64().unwrap(),
+                    info["defect_name"].as_str().unwrap(),
+                    info["guideline_list"][0]["id"].as_str().unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("Cargo.lock", 25, "banned-crate", ""),
+                ("Cargo.lock", 40, "duplicate-dependency", ""),
+                ("Cargo.toml", 1, "missing-package-field", "g.car.02"),
+                ("Cargo.toml", 1, "missing-package-field", "g.car.02"),
+                ("Cargo.toml", 9, "wildcard-dependency", "g.car.04"),
+                ("Cargo.toml", 10, "banned-crate", ""),
+                ("Cargo.toml", 11, "disallowed-crate", ""),
+                ("Cargo.toml", 11, "unpinned-git-dependency", ""),
+            ]
+        );
+        let duplicate = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|info| info["defect_name"] == "duplicate-dependency")
+            .unwrap();
+        assert_eq!(
+            duplicate["help_info"],
+            "crate `syn` has multiple versions in `Cargo.lock`: 1.0.109, 2.0.39"
+        );
+    });
+}
+
+#[test]
+fn check_security_advisories() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("output_advisory.json");
+        let rule_file = cfg.output_dir.join("rules_advisory.json");
+        let fixture_dir = cfg.test_dir.join("data/advisory");
+        let rules = serde_json::json!({
+            "file_path": "src/lib.rs",
+            "coding_guidelines": ["G.SUP.01", "G.SUP.02"],
+            "advisory": {
+                "db_path": fixture_dir.join("advisory-db"),
+                "index_path": fixture_dir.join("crates-index"),
+                "ignore": ["RUSTSEC-2021-0003"]
+            }
+        });
+        fs::write(&rule_file, rules.to_string()).unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .arg("--guidelines")
+            .arg(cfg.test_dir.join("data/guidelines_advisory.json"))
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(fixture_dir.join("src/lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+
+        let found = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|info| {
+                (
+                    info["begin_line"].as_u64().unwrap(),
+                    info["defect_name"].as_str().unwrap(),
+                    info["guideline_list"][0]["id"].as_str().unwrap(),
+                    info["help_info"].as_str().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                (
+                    17,
+                    "unmaintained-dependency",
+                    "g.sup.02",
+                    "`ansi_term 0.12.1` is affected by RUSTSEC-2021-0139: ansi_term is Unmaintained"
+                ),
+                (22, "yanked-dependency", "g.sup.02", "`libc 0.2.150` is yanked"),
+                (
+                    37,
+                    "vulnerable-dependency",
+                    "g.sup.01",
+                    "`time 0.1.45` is affected by RUSTSEC-2020-0071: Potential segfault in the time crate"
+                ),
+            ]
+        );
+        assert_eq!(
+            output["check_info"][0]["additional_help_info"],
+            "affected: all versions; patched: none; \
+            see https://github.com/ogham/rust-ansi-term/issues/72"
+        );
+        assert!(output["check_info"][2]["additional_help_info"]
+            .as_str()
+            .unwrap()
+            .starts_with("affected: versions not matching `>=0.2.23`, `=0.2.0`"));
+    });
+}
+
+#[test]
+fn check_dependency_licenses() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("output_license.json");
+        let rule_file = cfg.output_dir.join("rules_license.json");
+        fs::write(
+            &rule_file,
+            r#"{
+    "file_path": "src/lib.rs",
+    "coding_guidelines": ["G.LIC.01"],
+    "license": {
+        "allowed": ["MIT", "Apache-2.0"],
+        "denied": ["GPL-3.0-only"]
+    }
+}"#,
+        )
+        .unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .arg("--guidelines")
+            .arg(cfg.test_dir.join("data/guidelines_license.json"))
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/license/src/lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+
+        let mut found = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|info| {
+                assert_eq!(info["guideline_list"][0]["id"], "g.lic.01");
+                (
+                    info["begin_line"].as_u64().unwrap(),
+                    info["defect_name"].as_str().unwrap(),
+                    info["help_info"].as_str().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(found.len(), 4);
+        assert_eq!(
+            found[0],
+            (
+                6,
+                "denied-license",
+                "license `GPL-3.0-only AND MIT` of `copyleft 0.3.1` is denied"
+            )
+        );
+        assert_eq!(found[1].0, 10);
+        assert!(found[1]
+            .2
+            .starts_with("`custom 0.1.0` only has a license file"));
+        assert_eq!(
+            found[2],
+            (
+                29,
+                "unknown-license",
+                "`unlicensed 2.0.0` does not have a license"
+            )
+        );
+        assert_eq!(
+            found[3],
+            (
+                33,
+                "unknown-license",
+                "license `Zlib` of `zlib 1.2.0` is not in the allowed list"
+            )
+        );
+    });
+}
+
+#[test]
+fn check_formatting() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("output_rustfmt.json");
+        let rule_file = cfg.output_dir.join("rules_rustfmt.json");
+        fs::write(
+            &rule_file,
+            r#"{
+    "file_path": "src/lib.rs",
+    "coding_guidelines": ["P.FMT.01"],
+    "rustfmt": {"config": {"max_width": 40, "reorder_imports": true}}
+}"#,
+        )
+        .unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/rustfmt/src/lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+
+        let found = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|info| {
+                assert_eq!(info["defect_name"], "misformatted-code");
+                assert_eq!(info["guideline_list"][0]["id"], "p.fmt.01");
+                (
+                    info["begin_line"].as_u64().unwrap(),
+                    info["code_string"].as_str().unwrap(),
+                    info["additional_help_info"].as_str().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                (1, "use std::io;\n", ""),
+                (3, "", "use std::io;\n"),
+                (
+                    4,
+                    "pub fn add(a:u32,b:u32)->u32{a+b}\n",
+                    "pub fn add(a: u32, b: u32) -> u32 {\n    a + b\n}\n"
+                ),
+                (
+                    7,
+                    "    format!(\"{} {}\", \"a fairly long argument\", 42)\n",
+                    "    format!(\n        \"{} {}\",\n        \"a fairly long argument\", 42\n    )\n"
+                ),
+            ]
+        );
+        assert_eq!(
+            output["check_info"][1]["help_info"],
+            "rustfmt expects lines to be inserted here"
+        );
+    });
+}
+
+#[test]
+fn check_panic_sites() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("output_panics.json");
+        let rule_file = cfg.output_dir.join("rules_panics.json");
+        fs::write(
+            &rule_file,
+            r#"{
+    "file_path": "src/lib.rs",
+    "coding_guidelines": ["G.CMT.02"],
+    "panics": {}
+}"#,
+        )
+        .unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/panics/src/lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+
+        let functions = output["panics"]["functions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| {
+                let sites = f["panic_sites"].as_array().unwrap().iter();
+                (
+                    f["name"].as_str().unwrap(),
+                    f["may_panic"].as_bool().unwrap(),
+                    f["documented"].as_bool().unwrap(),
+                    sites
+                        .map(|s| s["kind"].as_str().unwrap())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            [
+                ("first", true, false, vec!["clippy::indexing_slicing"]),
+                ("parse", true, true, vec!["clippy::unwrap_used"]),
+                ("checked_half", true, false, vec!["assert_ne!"]),
+                ("get", false, false, vec![]),
+                ("Stack::pop", true, false, vec!["clippy::expect_used"]),
+            ]
+        );
+
+        // each undocumented function is reported once, by whichever tool
+        let undocumented = output["check_info"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|info| {
+                let defect = info["defect_name"].as_str().unwrap();
+                defect.replace('_', "-").ends_with("missing-panics-doc")
+            })
+            .map(|info| {
+                assert_eq!(info["tool"], "panics");
+                assert_eq!(info["guideline_list"][0]["id"], "g.cmt.02");
+                info["begin_line"].as_u64().unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(undocumented, [1, 14, 26]);
+    });
+}
+
+#[test]
+fn check_with_gitlab_format() {
+    setup(|cfg| {
+        let output_file = cfg.output_dir.join("gl-code-quality-report.json");
+        let rule_file = cfg.output_dir.join("rules_gitlab.json");
+        fs::write(
+            &rule_file,
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["P.FMT.01"]}"#,
+        )
+        .unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/rustfmt/src/lib.rs"))
+            .args(["--format", "gitlab", "--output"])
+            .arg(&output_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let issues: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+        let issues = issues.as_array().unwrap();
+        assert!(!issues.is_empty());
+        for issue in issues {
+            assert_eq!(issue["check_name"], "rustfmt/misformatted-code");
+            assert!(issue["location"]["path"]
+                .as_str()
+                .unwrap()
+                .ends_with("data/rustfmt/src/lib.rs"));
+            assert!(issue["location"]["lines"]["begin"].as_u64().is_some());
+            assert!(!issue["fingerprint"].as_str().unwrap().is_empty());
+        }
+    });
+}
+
+#[test]
+fn check_with_github_format() {
+    setup(|cfg| {
+        let rule_file = cfg.output_dir.join("rules_github.json");
+        let summary_file = cfg.output_dir.join("step_summary.md");
+        fs::write(
+            &rule_file,
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["P.FMT.01"]}"#,
+        )
+        .unwrap();
+        let _ = fs::remove_file(&summary_file);
+        let res = Command::new(&cfg.bin_path)
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/rustfmt/src/lib.rs"))
+            .args(["--format", "github"])
+            .env("GITHUB_STEP_SUMMARY", &summary_file)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+        let stdout = String::from_utf8_lossy(&res.stdout);
+        let annotations = stdout
+            .lines()
+            .filter(|line| line.starts_with("::"))
+            .collect::<Vec<_>>();
+        assert!(!annotations.is_empty());
+        for line in &annotations {
+            assert!(line.starts_with("::notice file="));
+            assert!(line.contains(",title=P.FMT.01::"));
+        }
+
+        let summary = fs::read_to_string(&summary_file).unwrap();
+        assert!(summary.contains(&format!(
+            "| P.FMT.01 | Format code automatically with rustfmt | prompt | {} |",
+            annotations.len()
+        )));
+    });
+}
+
+#[test]
+fn merge_and_render_reports() {
+    setup(|cfg| {
+        let shard_a = cfg.test_dir.join("data/output_lints_expected.json");
+        let shard_b = cfg.output_dir.join("output_shard_b.json");
+        let merged_file = cfg.output_dir.join("output_merged.json");
+        let csv_file = cfg.output_dir.join("output_merged.csv");
+
+        // the other shard found two of the same results, and a new one
+        let mut output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&shard_a).unwrap()).unwrap();
+        let check_info = output["check_info"].as_array_mut().unwrap();
+        let mut new_info = check_info[0].clone();
+        new_info["begin_line"] = 99.into();
+        check_info.truncate(2);
+        check_info.push(new_info);
+        fs::write(&shard_b, output.to_string()).unwrap();
+
+        let st = Command::new(&cfg.bin_path)
+            .args(["report", "merge"])
+            .arg(&shard_a)
+            .arg(&shard_b)
+            .arg("--output")
+            .arg(&merged_file)
+            .status()
+            .unwrap();
+        assert!(st.success());
+        let merged: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&merged_file).unwrap()).unwrap();
+        let merged = merged["check_info"].as_array().unwrap();
+        assert_eq!(merged.len(), 17);
+        assert_eq!(merged[16]["begin_line"], 99);
+
+        let st = Command::new(&cfg.bin_path)
+            .args([
+                "report",
+                "render",
+                "--format",
+                "csv",
+                "--columns",
+                "begin_line",
+            ])
+            .arg(&merged_file)
+            .arg("--output")
+            .arg(&csv_file)
+            .status()
+            .unwrap();
+        assert!(st.success());
+        let csv = fs::read_to_string(&csv_file).unwrap();
+        assert_eq!(csv.lines().count(), 18);
+        assert_eq!(csv.lines().last(), Some("99"));
+
+        let res = Command::new(&cfg.bin_path)
+            .args(["report", "render"])
+            .arg(cfg.test_dir.join("data/default_rules.json"))
+            .output()
+            .unwrap();
+        assert!(!res.status.success());
+        assert!(String::from_utf8_lossy(&res.stderr).contains("failed to parse output"));
+    });
+}
+
+#[test]
+fn diff_reports() {
+    setup(|cfg| {
+        let old_file = cfg.test_dir.join("data/output_lints_expected.json");
+        let new_file = cfg.output_dir.join("output_diff_new.json");
+
+        // fix the first result, and move the second one to another line
+        let mut output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&old_file).unwrap()).unwrap();
+        let check_info = output["check_info"].as_array_mut().unwrap();
+        let fixed = check_info.remove(0);
+        check_info[0]["begin_line"] = 100.into();
+        fs::write(&new_file, output.to_string()).unwrap();
+
+        let diff = |old: &std::path::Path, new: &std::path::Path, fail_on: &str| {
+            Command::new(&cfg.bin_path)
+                .args(["report", "diff", "--fail-on", fail_on])
+                .arg(old)
+                .arg(new)
+                .env_remove("LANG")
+                .output()
+                .unwrap()
+        };
+
+        let res = diff(&old_file, &new_file, "info");
+        assert!(res.status.success());
+        let stdout = String::from_utf8_lossy(&res.stdout);
+        let fixed_id = fixed["guideline_list"][0]["id"]
+            .as_str()
+            .unwrap()
+            .to_uppercase();
+        assert!(stdout
+            .lines()
+            .any(|line| line.starts_with(&fixed_id) && line.contains("(+0, -1)")));
+        assert!(stdout.contains(&format!(
+            "- {}:{}: ",
+            fixed["file_path"].as_str().unwrap(),
+            fixed["begin_line"]
+        )));
+        assert!(stdout.ends_with("0 new, 1 fixed, 15 unchanged\n"));
+
+        // the other way around, the fixed result is new
+        let level = fixed["guideline_list"][0]["level"].as_str().unwrap();
+        let res = diff(&new_file, &old_file, level);
+        assert_eq!(res.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&res.stdout);
+        assert!(stdout.contains("1 new, 0 fixed, 15 unchanged"));
+        assert!(stdout.contains(&format!(
+            "found 1 new result(s) of level '{level}' or above"
+        )));
+
+        let res = diff(&new_file, &old_file, "fatal");
+        assert!(res.status.success());
+    });
+}
+
+#[test]
+fn render_source_context() {
+    setup(|cfg| {
+        let rule_file = cfg.output_dir.join("rules_context.json");
+        let output_file = cfg.output_dir.join("output_context.json");
+        let report_file = cfg.output_dir.join("output_context.md");
+        fs::write(
+            &rule_file,
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["G.UNS.SAS.02"]}"#,
+        )
+        .unwrap();
+        let st = Command::new(&cfg.bin_path)
+            .arg("--guidelines")
+            .arg(cfg.test_dir.join("data/guidelines_unsafe_audit.json"))
+            .arg("--rule-file")
+            .arg(&rule_file)
+            .arg("--src-file")
+            .arg(cfg.test_dir.join("data/unsafe_audit/src/lib.rs"))
+            .arg("--output")
+            .arg(&output_file)
+            .status()
+            .unwrap();
+        assert!(st.success());
+        let output = fs::read_to_string(&output_file).unwrap();
+        assert!(!output.contains("\"highlight\""));
+
+        // files are found through the checked paths in the metadata
+        let st = Command::new(&cfg.bin_path)
+            .args(["report", "render", "--format", "markdown"])
+            .args(["--context-lines", "1", "--output"])
+            .arg(&report_file)
+            .arg(&output_file)
+            .status()
+            .unwrap();
+        assert!(st.success());
+        let report = fs::read_to_string(&report_file).unwrap();
+        assert!(report.contains(
+            "  ```text
+  27 |     pub fn read(p: *const u8) -> u8 {
+  28 |         let byte = unsafe { *p };
+     |                    ^^^^^^^^^^^^^^
+  29 |         byte
+  ```
+"
+        ));
+    });
+}
+
+#[test]
+fn write_summary_only() {
+    setup(|cfg| {
+        let rule_file = cfg.output_dir.join("rules_summary.json");
+        let output_file = cfg.output_dir.join("output_summary.json");
+        fs::write(
+            &rule_file,
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["G.UNS.SAS.01", "G.UNS.SAS.02"]}"#,
+        )
+        .unwrap();
+        let check = |format: &str| {
+            Command::new(&cfg.bin_path)
+                .arg("--guidelines")
+                .arg(cfg.test_dir.join("data/guidelines_unsafe_audit.json"))
+                .arg("--rule-file")
+                .arg(&rule_file)
+                .arg("--src-file")
+                .arg(cfg.test_dir.join("data/unsafe_audit/src/lib.rs"))
+                .arg("--output")
+                .arg(&output_file)
+                .args(["--summary-only", "--format", format])
+                .env_remove("LANG")
+                .output()
+                .unwrap()
+        };
+        assert!(check("json").status.success());
+        let output: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output_file).unwrap()).unwrap();
+        assert_eq!(output["check_info"], serde_json::json!([]));
+        assert!(output["unsafe_inventory"].is_null());
+        assert!(output["metadata"].is_object());
+
+        let summary = &output["summary"];
+        let total = summary["total"].as_u64().unwrap();
+        assert!(total > 0);
+        assert_eq!(summary["by_guideline"]["g.uns.sas.02"], 2);
+        assert_eq!(summary["tools"][0]["name"], "unsafe-audit");
+        assert_eq!(summary["tools"][0]["findings"], total);
+        assert_eq!(
+            summary["guidelines"],
+            serde_json::json!({"requested": 2, "enforceable": 2, "violated": 2})
+        );
+        assert_eq!(summary["compliance"], 0.0);
+
+        // rendering the summary again keeps it, even though its results are gone
+        let rendered_file = cfg.output_dir.join("output_summary_rendered.json");
+        let report = |args: &[&str]| {
+            Command::new(&cfg.bin_path)
+                .arg("report")
+                .args(args)
+                .arg("--output")
+                .arg(&rendered_file)
+                .arg(&output_file)
+                .env_remove("LANG")
+                .output()
+                .unwrap()
+        };
+        assert!(report(&["render"]).status.success());
+        let rendered: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&rendered_file).unwrap()).unwrap();
+        assert_eq!(&rendered["summary"], summary);
+
+        // but it can't be merged, or rendered without its results
+        for args in [&["merge"][..], &["render", "--format", "markdown"]] {
+            let res = report(args);
+            assert!(!res.status.success());
+            assert!(String::from_utf8_lossy(&res.stderr)
+                .contains("only holds the summary of its results"));
+        }
+
+        let res = check("markdown");
+        assert!(!res.status.success());
+        assert!(String::from_utf8_lossy(&res.stderr)
+            .contains("`--summary-only` only works with the \"json\" format"));
     });
 }
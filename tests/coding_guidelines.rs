@@ -1,4 +1,10 @@
-use eunomia::parser::{CheckLevel, CheckTool, CodingGuidelines, Guideline, JsonStruct};
+use std::collections::HashSet;
+use std::process::Command;
+
+use eunomia::locale::Lang;
+use eunomia::parser::{
+    CheckLevel, CheckTool, CodingGuidelines, Enforcement, Examples, Guideline, JsonStruct,
+};
 use eunomia::tools::SupportedTool;
 
 /// Manually comparing two CodingGuidelines struct without impl PartialEq for that
@@ -14,6 +20,13 @@ fn guidlines_eq(lhs: &CodingGuidelines, rhs: &CodingGuidelines) {
         assert_eq!(lhs_gl.name, rhs_gl.name);
         assert_eq!(lhs_gl.level, rhs_gl.level);
         assert_eq!(lhs_gl.tool.len(), rhs_gl.tool.len());
+        assert_eq!(lhs_gl.description, rhs_gl.description);
+        assert_eq!(lhs_gl.rationale, rhs_gl.rationale);
+        assert_eq!(lhs_gl.examples, rhs_gl.examples);
+        assert_eq!(lhs_gl.references, rhs_gl.references);
+        assert_eq!(lhs_gl.versions, rhs_gl.versions);
+        assert_eq!(lhs_gl.tags, rhs_gl.tags);
+        assert_eq!(lhs_gl.enforcement(), rhs_gl.enforcement());
 
         for j in 0..lhs_gl.tool.len() {
             let lhs_tool = &lhs_gl.tool[j];
@@ -25,6 +38,28 @@ fn guidlines_eq(lhs: &CodingGuidelines, rhs: &CodingGuidelines) {
     }
 }
 
+/// Create a guideline with only the required fields.
+fn guideline<'g>(
+    id: &str,
+    name: &'g str,
+    level: CheckLevel,
+    tool: Vec<CheckTool<'g>>,
+) -> Guideline<'g> {
+    Guideline {
+        id: id.parse().unwrap(),
+        name: name.into(),
+        level,
+        tool,
+        description: None,
+        rationale: None,
+        examples: Examples::default(),
+        references: vec![],
+        versions: None,
+        tags: vec![],
+        enforcement: None,
+    }
+}
+
 #[test]
 fn regular_guidelines() {
     let gl_str = r#"
@@ -84,29 +119,29 @@ fn regular_guidelines() {
 
     let expected = CodingGuidelines {
         coding_guidelines: vec![
-            Guideline {
-                id: "p.var.01".parse().unwrap(),
-                name: "xxxxx",
-                level: CheckLevel::Warn,
-                tool: vec![CheckTool {
+            guideline(
+                "p.var.01",
+                "xxxxx",
+                CheckLevel::Warn,
+                vec![CheckTool {
                     name: SupportedTool::Clippy,
                     ident: "some_clippy_lint",
                 }],
-            },
-            Guideline {
-                id: "p.var.02".parse().unwrap(),
-                name: "yyyyy",
-                level: CheckLevel::Info,
-                tool: vec![CheckTool {
+            ),
+            guideline(
+                "p.var.02",
+                "yyyyy",
+                CheckLevel::Info,
+                vec![CheckTool {
                     name: SupportedTool::Rustc,
                     ident: "some_rustc_lint",
                 }],
-            },
-            Guideline {
-                id: "p.var.03".parse().unwrap(),
-                name: "aaaaa",
-                level: CheckLevel::Fatal,
-                tool: vec![
+            ),
+            guideline(
+                "p.var.03",
+                "aaaaa",
+                CheckLevel::Fatal,
+                vec![
                     CheckTool {
                         name: SupportedTool::Miri,
                         ident: "some_miri_output_keyword",
@@ -116,16 +151,16 @@ fn regular_guidelines() {
                         ident: "some_sanitizer_output_keyword",
                     },
                 ],
-            },
-            Guideline {
-                id: "p.var.04".parse().unwrap(),
-                name: "bbbbb",
-                level: CheckLevel::Severe,
-                tool: vec![CheckTool {
+            ),
+            guideline(
+                "p.var.04",
+                "bbbbb",
+                CheckLevel::Severe,
+                vec![CheckTool {
                     name: SupportedTool::Sanitizer,
                     ident: "some_sanitizer_output_keyword",
                 }],
-            },
+            ),
         ],
     };
 
@@ -134,6 +169,158 @@ fn regular_guidelines() {
     guidlines_eq(&gl.unwrap(), &expected);
 }
 
+#[test]
+fn guideline_documentations() {
+    let gl_str = r#"
+{
+    "coding_guidelines": [
+        {
+            "id": "G.TYP.BOL.01",
+            "name": "Do not compare booleans with boolean literals",
+            "tool": [
+                {
+                    "name": "clippy",
+                    "ident": "clippy::bool_comparison"
+                }
+            ],
+            "description": "xxxxx",
+            "rationale": "yyyyy\nzzzzz",
+            "examples": {
+                "positive": ["if flag {}"],
+                "negative": ["if flag == true {}", "if flag == false {}"]
+            },
+            "references": ["https://rust-lang.github.io/rust-clippy/master/index.html#bool_comparison"],
+            "versions": ">=1.0",
+            "tags": ["types", "booleans"],
+            "enforcement": "partial"
+        }
+    ]
+}
+    "#;
+
+    let gl = CodingGuidelines::deserialize(gl_str).unwrap();
+    let gl = &gl.coding_guidelines[0];
+
+    assert_eq!(gl.description, Some("xxxxx".into()));
+    assert_eq!(gl.rationale, Some("yyyyy\nzzzzz".into()));
+    assert_eq!(gl.examples.positive, vec!["if flag {}"]);
+    assert_eq!(
+        gl.examples.negative,
+        vec!["if flag == true {}", "if flag == false {}"]
+    );
+    assert_eq!(
+        gl.references,
+        vec!["https://rust-lang.github.io/rust-clippy/master/index.html#bool_comparison"]
+    );
+    assert_eq!(gl.versions.as_deref(), Some(">=1.0"));
+    assert_eq!(gl.tags, vec!["types", "booleans"]);
+    assert_eq!(gl.enforcement(), Enforcement::Partial);
+
+    let explanation = gl.explain(&Lang::default());
+    assert!(explanation.starts_with("G.TYP.BOL.01: Do not compare booleans with boolean literals"));
+    assert!(explanation.contains("enforcement: partial\n"));
+    assert!(explanation.contains("\nRationale:\nyyyyy\nzzzzz\n"));
+    assert!(explanation.contains("\nNegative example:\nif flag == false {}\n"));
+}
+
+#[test]
+fn localized_guideline() {
+    let gl_str = r#"
+{
+    "coding_guidelines": [
+        {
+            "id": "G.VAR.02",
+            "name": {
+                "en": "Do not use non-ASCII characters as identifiers",
+                "zh": "不应使用非 ASCII 字符作为标识符"
+            },
+            "tool": [],
+            "description": "xxxxx",
+            "rationale": {
+                "zh-TW": "yyyyy",
+                "zh": "zzzzz"
+            }
+        }
+    ]
+}
+    "#;
+
+    let gl = CodingGuidelines::deserialize(gl_str).unwrap();
+    let gl = &gl.coding_guidelines[0];
+    let zh = Lang::new("zh_CN.UTF-8");
+    let zh_tw = Lang::new("zh-TW");
+    let fr = Lang::new("fr");
+
+    assert_eq!(gl.name.get(&zh), "不应使用非 ASCII 字符作为标识符");
+    assert_eq!(gl.name.get(&zh_tw), "不应使用非 ASCII 字符作为标识符");
+    assert_eq!(
+        gl.name.get(&fr),
+        "Do not use non-ASCII characters as identifiers"
+    );
+    assert_eq!(
+        gl.name.to_string(),
+        "Do not use non-ASCII characters as identifiers"
+    );
+    // plain strings are used for every language
+    assert_eq!(gl.description.as_ref().unwrap().get(&zh), "xxxxx");
+
+    let rationale = gl.rationale.as_ref().unwrap();
+    assert_eq!(rationale.get(&zh), "zzzzz");
+    assert_eq!(rationale.get(&zh_tw), "yyyyy");
+    // falls back to the first text when there's no default language
+    assert_eq!(rationale.get(&fr), "yyyyy");
+
+    assert_eq!(gl.summary(&zh).name, "不应使用非 ASCII 字符作为标识符");
+    assert!(gl
+        .explain(&zh)
+        .contains("检测方式: manual\n\n说明:\nxxxxx\n\n理由:\nzzzzz\n"));
+
+    let faulty_str = r#"
+{
+    "coding_guidelines": [
+        {
+            "id": "G.VAR.02",
+            "name": { "en": ["xxxxx"] },
+            "tool": []
+        }
+    ]
+}
+    "#;
+    assert!(CodingGuidelines::deserialize(faulty_str).is_err());
+}
+
+#[test]
+fn default_enforcement() {
+    let gl_str = r#"
+{
+    "coding_guidelines": [
+        {
+            "id": "P.VAR.01",
+            "name": "xxxxx",
+            "tool": []
+        },
+        {
+            "id": "P.VAR.02",
+            "name": "yyyyy",
+            "tool": [
+                {
+                    "name": "rustc",
+                    "ident": "some_rustc_lint"
+                }
+            ]
+        }
+    ]
+}
+    "#;
+
+    let gl = CodingGuidelines::deserialize(gl_str).unwrap();
+    assert_eq!(gl.coding_guidelines[0].enforcement(), Enforcement::Manual);
+    assert_eq!(
+        gl.coding_guidelines[1].enforcement(),
+        Enforcement::Automatic
+    );
+}
+
 #[test]
 fn default_check_level() {
     let gl_str = r#"
@@ -255,3 +442,59 @@ fn faulty_guidelines() {
     assert!(CodingGuidelines::deserialize(gl_1_str).is_err());
     assert!(CodingGuidelines::deserialize(gl_2_str).is_err());
 }
+
+#[test]
+fn builtin_guidelines() {
+    let content = include_str!("../src/guidelines.json");
+    let guidelines = CodingGuidelines::deserialize(content).unwrap();
+
+    // guideline ids should be unique
+    assert_eq!(
+        guidelines.to_hashmap().len(),
+        guidelines.coding_guidelines.len()
+    );
+    assert!(guidelines
+        .coding_guidelines
+        .iter()
+        .any(|gl| !gl.is_enforceable()));
+}
+
+#[test]
+fn builtin_guidelines_with_known_clippy_lints() {
+    let output = Command::new("clippy-driver")
+        .args(["-W", "help"])
+        .output()
+        .unwrap();
+    let known_lints = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| name.starts_with("clippy::"))
+        .map(String::from)
+        .collect::<HashSet<_>>();
+    assert!(!known_lints.is_empty());
+
+    let content = include_str!("../src/guidelines.json");
+    let guidelines = CodingGuidelines::deserialize(content).unwrap();
+    for gl in &guidelines.coding_guidelines {
+        for tool in gl.tool.iter().filter(|t| t.name == SupportedTool::Clippy) {
+            let lint_name = tool.ident.replace('_', "-");
+            assert!(
+                known_lints.contains(&lint_name),
+                "'{}' of guideline '{}' is not a known clippy lint",
+                tool.ident,
+                gl.id
+            );
+        }
+    }
+}
+
+#[test]
+fn builtin_guidelines_round_trip() {
+    let content = include_str!("../src/guidelines.json");
+    let guidelines = CodingGuidelines::deserialize(content).unwrap();
+
+    assert_eq!(
+        guidelines.to_json_string_pretty().unwrap().trim_end(),
+        content.trim_end()
+    );
+}
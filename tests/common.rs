@@ -1,19 +1,12 @@
 use lazy_static::lazy_static;
 use std::path::PathBuf;
-use std::{env, fs, panic};
+use std::{fs, panic};
 
 lazy_static! {
     static ref TEST_CFG: TestCfg = {
-        #[cfg(windows)]
-        let ext = ".exe";
-        #[cfg(not(windows))]
-        let ext = "";
+        let bin_path = PathBuf::from(env!("CARGO_BIN_EXE_eunomia"));
 
-        let cur_exe = env::current_exe().unwrap();
-        let debug_dir = cur_exe.parent().and_then(|dep| dep.parent()).unwrap();
-        let bin_path = debug_dir.join(format!("{}{ext}", env!("CARGO_PKG_NAME")));
-
-        let manifest_dir = PathBuf::from(format!("{}", env!("CARGO_MANIFEST_DIR")));
+        let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         let output_dir = manifest_dir.join("target").join("output");
         fs::create_dir_all(&output_dir).unwrap();
 
@@ -27,6 +20,7 @@ lazy_static! {
     };
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct TestCfg {
     /// Path to the actual program's binary, not the unit test binary.
@@ -37,7 +31,7 @@ pub struct TestCfg {
 
 pub fn setup<F>(f: F)
 where
-    F: FnOnce(&TestCfg) -> () + panic::UnwindSafe,
+    F: FnOnce(&TestCfg) + panic::UnwindSafe,
 {
     panic::catch_unwind(|| {
         f(&TEST_CFG);
@@ -0,0 +1,44 @@
+//! Fixture of the documentation coverage checker.
+
+pub mod shapes;
+mod private;
+
+/// Parse a number.
+pub fn parse(s: &str) -> Result<u32, std::num::ParseIntError> {
+    s.parse()
+}
+
+/// Get the first element.
+///
+/// # Panics
+///
+/// Panics if the slice is empty.
+pub fn first(v: &[u32]) -> u32 {
+    v[0]
+}
+
+/// Get the last element.
+pub fn last(v: &[u32]) -> u32 {
+    *v.last().unwrap()
+}
+
+/// Read a byte.
+pub unsafe fn read(p: *const u8) -> u8 {
+    *p
+}
+
+pub struct Undocumented;
+
+impl Undocumented {
+    pub fn new() -> Self {
+        Undocumented
+    }
+
+    fn helper(&self) {}
+}
+
+impl Default for Undocumented {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,26 @@
+//! Shapes.
+
+/// A circle.
+pub struct Circle {
+    pub radius: f64,
+}
+
+/// Shapes that have an area.
+pub trait Area {
+    fn area(&self) -> f64;
+
+    /// The area, or an error if it's negative.
+    ///
+    /// # Errors
+    ///
+    /// Returns the area if it's negative.
+    fn checked_area(&self) -> Result<f64, f64> {
+        let area = self.area();
+        assert!(!area.is_nan());
+        if area < 0.0 {
+            Err(area)
+        } else {
+            Ok(area)
+        }
+    }
+}
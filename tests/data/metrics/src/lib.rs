@@ -0,0 +1,29 @@
+//! Fixture of the metrics checker, the metrics of functions are asserted by tests.
+
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+pub fn classify(grid: &[Vec<i32>], threshold: i32) -> usize {
+    let mut count = 0;
+    for row in grid {
+        for cell in row {
+            if *cell > threshold {
+                match cell % 3 {
+                    0 => count += 3,
+                    1 => count += 1,
+                    _ => {
+                        if *cell > threshold * 2 || *cell < 0 {
+                            count += 2;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    count
+}
+
+pub fn configure(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8) -> u8 {
+    a + b + c + d + e + f
+}
@@ -0,0 +1,40 @@
+pub fn first(items: &[u32]) -> u32 {
+    items[0]
+}
+
+/// Parse a number.
+///
+/// # Panics
+///
+/// Panics if `s` is not a number.
+pub fn parse(s: &str) -> u32 {
+    s.parse().unwrap()
+}
+
+pub fn checked_half(n: u32) -> u32 {
+    assert_ne!(n % 2, 1);
+    n / 2
+}
+
+pub fn get(items: &[u32], idx: usize) -> Option<u32> {
+    items.get(idx).copied()
+}
+
+pub struct Stack(Vec<u32>);
+
+impl Stack {
+    pub fn pop(&mut self) -> u32 {
+        self.0.pop().expect("empty stack")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn first() {
+        assert_eq!(super::first(&[1]), 1);
+        let v: Option<u32> = None;
+        assert!(v.is_none());
+        Some(1).unwrap();
+    }
+}
@@ -0,0 +1,12 @@
+use std::io;
+use std::fmt;
+
+pub fn add(a:u32,b:u32)->u32{a+b}
+
+pub fn describe() -> String {
+    format!("{} {}", "a fairly long argument", 42)
+}
+
+pub fn formatted(x: u32) -> u32 {
+    x * 2
+}
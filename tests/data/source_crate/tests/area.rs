@@ -0,0 +1,4 @@
+#[test]
+fn area() {
+    assert_eq!(source_crate::area(2, 3), 6);
+}
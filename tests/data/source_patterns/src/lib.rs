@@ -0,0 +1,20 @@
+//! Fixture of the pattern checker, the positions of items are asserted by tests.
+
+mod shapes;
+
+use std::process::exit;
+
+pub static G_COUNTER: u32 = 0;
+pub static TOTAL: u32 = 0;
+
+pub fn get_total() -> u32 {
+    TOTAL
+}
+
+pub fn quit() {
+    exit(1);
+}
+
+fn get_private() -> u32 {
+    dbg!(G_COUNTER)
+}
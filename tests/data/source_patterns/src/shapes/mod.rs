@@ -0,0 +1,15 @@
+#[derive(Debug, Clone)]
+pub struct Circle {
+    pub radius: f64,
+}
+
+#[derive(Clone)]
+pub struct Square {
+    pub side: f64,
+}
+
+struct Hidden;
+
+pub fn area(c: &Circle) -> f64 {
+    std::process::exit(c.radius as i32)
+}
@@ -0,0 +1,8 @@
+extern "C" {
+    pub fn abs(x: i32) -> i32;
+}
+
+pub fn call_abs(x: i32) -> i32 {
+    // SAFETY: `abs` is defined for every input except `i32::MIN`.
+    unsafe { abs(x) }
+}
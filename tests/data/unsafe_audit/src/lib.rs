@@ -0,0 +1,31 @@
+//! Fixture of the unsafe audit, the positions of unsafe code are asserted by tests.
+
+mod ffi;
+
+/// Read the first byte.
+///
+/// # Safety
+///
+/// `p` must be valid for reads.
+pub unsafe fn first(p: *const u8) -> u8 {
+    // SAFETY: the caller guarantees `p` is valid.
+    unsafe { *p }
+}
+
+pub unsafe fn second(p: *const u8) -> u8 {
+    unsafe { *p.add(1) }
+}
+
+pub struct Handle(*mut u8);
+
+// SAFETY: the pointer is never shared between handles.
+unsafe impl Send for Handle {}
+
+unsafe impl Sync for Handle {}
+
+pub mod io {
+    pub fn read(p: *const u8) -> u8 {
+        let byte = unsafe { *p };
+        byte
+    }
+}
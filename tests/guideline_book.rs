@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+
+use eunomia::locale::Lang;
+use eunomia::parser::{
+    import_guidelines, parse_book, parse_book_chapter, BookLevel, CheckLevel, CodingGuidelines,
+    GuidelineID, ImportReport, JsonStruct,
+};
+use eunomia::tools::SupportedTool;
+
+fn book_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("data")
+        .join("guideline_book")
+}
+
+fn id(s: &str) -> GuidelineID {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parse_book_chapter_with_lint_table() {
+    let content =
+        std::fs::read_to_string(book_dir().join("src/safe-guides/coding_practice/data-type.md"))
+            .unwrap();
+    let gls = parse_book_chapter(&content);
+    assert_eq!(gls.len(), 2);
+
+    assert_eq!(gls[0].id, id("G.TYP.BOL.01"));
+    assert_eq!(gls[0].title, "不应将布尔值和布尔字面量进行比较");
+    assert_eq!(gls[0].level, Some(BookLevel::Requirement));
+    assert_eq!(
+        gls[0].description.as_deref(),
+        Some("在 Rust 中，返回为布尔值的表达式或函数值可以直接当作布尔值使用。")
+    );
+    assert_eq!(
+        gls[0].lints,
+        [(SupportedTool::Clippy, "clippy::bool_comparison".to_string())]
+    );
+
+    assert_eq!(gls[1].id, id("G.TYP.INT.01"));
+    assert_eq!(gls[1].level, Some(BookLevel::Suggestion));
+    // lints that can't be detected by either tool are ignored
+    assert_eq!(
+        gls[1].lints,
+        [
+            (
+                SupportedTool::Clippy,
+                "clippy::integer_arithmetic".to_string()
+            ),
+            (
+                SupportedTool::Clippy,
+                "clippy::cast_possible_truncation".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn parse_book_dir() {
+    let gls = parse_book(book_dir()).unwrap();
+    let ids = gls.iter().map(|gl| gl.id.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        ["g.typ.bol.01", "g.typ.int.01", "p.nam.01", "g.nam.01"]
+    );
+
+    let nam = &gls[3];
+    assert_eq!(
+        nam.description.as_deref(),
+        Some("遵循 RFC 430 的命名风格。")
+    );
+    assert_eq!(
+        nam.lints,
+        [
+            (SupportedTool::Rustc, "non_camel_case_types".to_string()),
+            (SupportedTool::Rustc, "non_snake_case".to_string()),
+            (
+                SupportedTool::Clippy,
+                "clippy::module_name_repetitions".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn import_into_empty_catalogue() {
+    let book = parse_book(book_dir()).unwrap();
+    let mut catalogue = CodingGuidelines::default();
+    let report = import_guidelines(&mut catalogue, &book, &Lang::new("zh"), true);
+
+    assert_eq!(report.added.len(), 4);
+    assert!(report.removed.is_empty() && report.changed.is_empty());
+
+    let gls = &catalogue.coding_guidelines;
+    // principles default to `prompt` when the book doesn't specify the level
+    assert_eq!(gls[2].level, CheckLevel::Prompt);
+    assert_eq!(gls[3].level, CheckLevel::Warn);
+    assert_eq!(gls[1].level, CheckLevel::Prompt);
+    assert_eq!(gls[3].name.get(&Lang::new("zh")), "使用统一的命名风格");
+    assert_eq!(gls[3].tool.len(), 3);
+}
+
+#[test]
+fn import_into_existing_catalogue() {
+    let content =
+        std::fs::read_to_string(book_dir().join("../guidelines_before_import.json")).unwrap();
+    let mut catalogue = CodingGuidelines::deserialize(&content).unwrap();
+    let book = parse_book(book_dir()).unwrap();
+    let report = import_guidelines(&mut catalogue, &book, &Lang::new("zh"), true);
+
+    assert_eq!(
+        report,
+        ImportReport {
+            added: vec![id("G.TYP.INT.01"), id("P.NAM.01")],
+            removed: vec![id("G.VAR.01")],
+            changed: vec![
+                (id("G.TYP.BOL.01"), vec!["name", "description"]),
+                (id("G.NAM.01"), vec!["tool"]),
+            ],
+        }
+    );
+
+    let map = catalogue.to_hashmap();
+    let nam = map[&id("G.NAM.01")];
+    // the deliberately chosen level agrees with the book, so it's kept
+    assert_eq!(nam.level, CheckLevel::Severe);
+    // hand-edited tool mappings are kept
+    let idents = nam.tool.iter().map(|t| t.ident).collect::<Vec<_>>();
+    assert_eq!(
+        idents,
+        [
+            "non_snake_case",
+            "non_upper_case_globals",
+            "non_camel_case_types",
+            "clippy::module_name_repetitions"
+        ]
+    );
+
+    let bol = map[&id("G.TYP.BOL.01")];
+    // texts in other languages are preserved
+    assert_eq!(
+        bol.name.get(&Lang::default()),
+        "Boolean values should not be compared with boolean literals"
+    );
+    assert_eq!(
+        bol.name.get(&Lang::new("zh")),
+        "不应将布尔值和布尔字面量进行比较"
+    );
+    assert_eq!(bol.tool.len(), 1);
+
+    // importing again changes nothing
+    let json = catalogue.to_json_string_pretty().unwrap();
+    let mut catalogue = CodingGuidelines::deserialize(&json).unwrap();
+    assert!(import_guidelines(&mut catalogue, &book, &Lang::new("zh"), true).is_empty());
+}
+
+#[test]
+fn import_part_of_book() {
+    let content =
+        std::fs::read_to_string(book_dir().join("../guidelines_before_import.json")).unwrap();
+    let mut catalogue = CodingGuidelines::deserialize(&content).unwrap();
+    let book = parse_book(book_dir().join("src/safe-guides/coding_practice")).unwrap();
+    let report = import_guidelines(&mut catalogue, &book, &Lang::new("zh"), false);
+
+    // guidelines of the other chapters are kept as they were
+    assert_eq!(
+        report,
+        ImportReport {
+            added: vec![id("G.TYP.INT.01")],
+            removed: vec![],
+            changed: vec![(id("G.TYP.BOL.01"), vec!["name", "description"])],
+        }
+    );
+    let original = CodingGuidelines::deserialize(&content).unwrap();
+    let (before, after) = (original.to_hashmap(), catalogue.to_hashmap());
+    for kept in ["G.NAM.01", "G.VAR.01"] {
+        assert_eq!(
+            format!("{:?}", after[&id(kept)]),
+            format!("{:?}", before[&id(kept)])
+        );
+    }
+}
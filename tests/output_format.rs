@@ -0,0 +1,306 @@
+use eunomia::format::{Column, OutputFormat, RenderOpts};
+use eunomia::parser::{CheckInfo, CheckLevel, ChildMessage, CodeSpan, GuidelineSummary, Output};
+use eunomia::tools::SupportedTool;
+use std::path::PathBuf;
+
+fn output() -> Output {
+    let unwrap = |line| CheckInfo {
+        file_path: Some(PathBuf::from("./src/lib.rs")),
+        defect_name: "clippy::unwrap_used".to_string(),
+        tool: SupportedTool::Clippy,
+        begin_line: Some(line),
+        column: Some(7),
+        code_string: "x.unwrap()".to_string(),
+        help_info: "used `unwrap()` on an `Option` value".to_string(),
+        guideline_list: vec![
+            GuidelineSummary {
+                id: "G.ERR.01".parse().unwrap(),
+                name: "Handle `Option` and `Result` properly".to_string(),
+                level: CheckLevel::Warn,
+            },
+            GuidelineSummary {
+                id: "G.ERR.02".parse().unwrap(),
+                name: "Don't panic".to_string(),
+                level: CheckLevel::Severe,
+            },
+        ],
+        ..Default::default()
+    };
+    Output::from(vec![
+        unwrap(3),
+        unwrap(10),
+        CheckInfo {
+            file_path: Some(PathBuf::from("Cargo.toml")),
+            defect_name: "wildcard-dependency".to_string(),
+            tool: SupportedTool::Manifest,
+            begin_line: Some(8),
+            help_info: "dependency `regex` uses \"*\" as its version".to_string(),
+            ..Default::default()
+        },
+    ])
+}
+
+#[test]
+fn parse_output_formats() {
+    assert_eq!(
+        "GitLab".parse::<OutputFormat>().unwrap(),
+        OutputFormat::Gitlab
+    );
+    assert_eq!(
+        "checkstyle".parse::<OutputFormat>().unwrap(),
+        OutputFormat::Checkstyle
+    );
+    assert!("sarif".parse::<OutputFormat>().is_err());
+}
+
+#[test]
+fn fingerprints_ignore_lines() {
+    let output = output();
+    let infos = &output.check_info;
+    assert_eq!(infos[0].fingerprint(), infos[1].fingerprint());
+    assert_ne!(infos[0].fingerprint(), infos[2].fingerprint());
+
+    let fingerprints = eunomia::parser::fingerprints(infos);
+    assert_eq!(fingerprints[1], format!("{}-2", fingerprints[0]));
+    assert_eq!(infos[0].level(), CheckLevel::Severe);
+    assert_eq!(infos[2].level(), CheckLevel::Warn);
+}
+
+#[test]
+fn render_gitlab_code_quality() {
+    let output = output();
+    let rendered = OutputFormat::Gitlab.render(&output).unwrap();
+    let issues: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+    let fingerprint = output.check_info[0].fingerprint();
+
+    assert_eq!(
+        issues[0],
+        serde_json::json!({
+            "description": "used `unwrap()` on an `Option` value (violates: \
+                G.ERR.01 Handle `Option` and `Result` properly; G.ERR.02 Don't panic)",
+            "check_name": "clippy::unwrap_used",
+            "fingerprint": fingerprint,
+            "severity": "critical",
+            "location": {"path": "src/lib.rs", "lines": {"begin": 3}},
+        })
+    );
+    assert_eq!(issues[1]["fingerprint"], format!("{fingerprint}-2"));
+    assert_eq!(issues[2]["check_name"], "manifest/wildcard-dependency");
+    assert_eq!(issues[2]["severity"], "major");
+}
+
+#[test]
+fn render_checkstyle() {
+    let rendered = OutputFormat::Checkstyle.render(&output()).unwrap();
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="Cargo.toml">
+    <error line="8" severity="warning" message="dependency `regex` uses &quot;*&quot; as its version" source="manifest/wildcard-dependency"/>
+  </file>
+  <file name="src/lib.rs">
+    <error line="3" column="7" severity="error" message="used `unwrap()` on an `Option` value (violates: G.ERR.01 Handle `Option` and `Result` properly; G.ERR.02 Don&apos;t panic)" source="clippy::unwrap_used"/>
+    <error line="10" column="7" severity="error" message="used `unwrap()` on an `Option` value (violates: G.ERR.01 Handle `Option` and `Result` properly; G.ERR.02 Don&apos;t panic)" source="clippy::unwrap_used"/>
+  </file>
+</checkstyle>
+"#;
+    assert_eq!(rendered, expected);
+}
+
+#[test]
+fn render_github_annotations() {
+    let mut output = output();
+    output.check_info[1].end_line = Some(12);
+    let rendered = OutputFormat::Github.render(&output).unwrap();
+    let lines = rendered.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "::error file=src/lib.rs,line=3,col=7,title=G.ERR.02::used `unwrap()` on an \
+             `Option` value (violates: G.ERR.01 Handle `Option` and `Result` properly; \
+             G.ERR.02 Don't panic)",
+            "::error file=src/lib.rs,line=10,col=7,endLine=12,title=G.ERR.02::used `unwrap()` \
+             on an `Option` value (violates: G.ERR.01 Handle `Option` and `Result` properly; \
+             G.ERR.02 Don't panic)",
+            "::warning file=Cargo.toml,line=8,title=manifest/wildcard-dependency::dependency \
+             `regex` uses \"*\" as its version",
+        ]
+    );
+}
+
+#[test]
+fn github_step_summary() {
+    let summary = eunomia::format::github_step_summary(&output());
+    assert_eq!(
+        summary,
+        "## Eunomia
+
+Found 3 result(s), violating 2 guideline(s).
+
+| Guideline | Name | Level | Count |
+| --- | --- | --- | ---: |
+| G.ERR.02 | Don't panic | severe | 2 |
+| G.ERR.01 | Handle `Option` and `Result` properly | warn | 2 |
+
+1 result(s) are not related to any checked guideline.
+"
+    );
+    assert_eq!(
+        eunomia::format::github_step_summary(&Output::from(vec![])),
+        "## Eunomia\n\nNo violation was found.\n"
+    );
+}
+
+#[test]
+fn render_markdown() {
+    let rendered = OutputFormat::Markdown.render(&output()).unwrap();
+    let expected = r#"# Eunomia report
+
+Found 3 result(s), violating 2 guideline(s).
+
+| Guideline | Name | Level | Count |
+| --- | --- | --- | ---: |
+| G.ERR.02 | Don't panic | severe | 2 |
+| G.ERR.01 | Handle `Option` and `Result` properly | warn | 2 |
+
+| Level | Count |
+| --- | ---: |
+| severe | 2 |
+| warn | 1 |
+
+## Results
+
+<details>
+<summary><b>G.ERR.02</b> Don't panic (severe, 2 result(s))</summary>
+
+- `src/lib.rs:3:7` used `unwrap()` on an `Option` value (`clippy::unwrap_used`)
+
+  ```rust
+  x.unwrap()
+  ```
+
+- `src/lib.rs:10:7` used `unwrap()` on an `Option` value (`clippy::unwrap_used`)
+
+  ```rust
+  x.unwrap()
+  ```
+
+</details>
+"#;
+    assert!(rendered.starts_with(expected), "{rendered}");
+    assert!(rendered.ends_with(
+        r#"<details>
+<summary>Not related to any checked guideline (1 result(s))</summary>
+
+- `Cargo.toml:8` dependency `regex` uses "*" as its version (`manifest/wildcard-dependency`)
+
+</details>
+"#
+    ));
+}
+
+#[test]
+fn render_capped_markdown() {
+    let opts = RenderOpts {
+        max_per_guideline: Some(1),
+        ..Default::default()
+    };
+    let rendered = OutputFormat::Markdown
+        .render_with(&output(), &opts)
+        .unwrap();
+    assert_eq!(rendered.matches("- `src/lib.rs:3:7`").count(), 2);
+    assert!(!rendered.contains("src/lib.rs:10:7"));
+    assert_eq!(rendered.matches("- ...and 1 more\n").count(), 2);
+}
+
+#[test]
+fn render_csv() {
+    let mut output = output();
+    output.check_info[2].help_info = "uses \"*\",\nwhich is unpinned".to_string();
+    let rendered = OutputFormat::Csv.render(&output).unwrap();
+    let rows = rendered.split("\r\n").collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            "guideline_id,guideline_name,level,tool,defect_name,file,begin_line,end_line,\
+             column,end_column,message,notes",
+            "G.ERR.01; G.ERR.02,Handle `Option` and `Result` properly; Don't panic,severe,\
+             clippy,clippy::unwrap_used,src/lib.rs,3,,7,,used `unwrap()` on an `Option` value,",
+            "G.ERR.01; G.ERR.02,Handle `Option` and `Result` properly; Don't panic,severe,\
+             clippy,clippy::unwrap_used,src/lib.rs,10,,7,,used `unwrap()` on an `Option` value,",
+            ",,warn,manifest,wildcard-dependency,Cargo.toml,8,,,,\"uses \"\"*\"\",\nwhich is \
+             unpinned\",",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn render_tsv_per_guideline() {
+    let opts = RenderOpts {
+        columns: vec![
+            Column::GuidelineId,
+            Column::Level,
+            Column::File,
+            Column::BeginLine,
+        ],
+        row_per_guideline: true,
+        ..Default::default()
+    };
+    let rendered = OutputFormat::Tsv.render_with(&output(), &opts).unwrap();
+    assert_eq!(
+        rendered,
+        "guideline_id\tlevel\tfile\tbegin_line\r
+G.ERR.01\twarn\tsrc/lib.rs\t3\r
+G.ERR.02\tsevere\tsrc/lib.rs\t3\r
+G.ERR.01\twarn\tsrc/lib.rs\t10\r
+G.ERR.02\tsevere\tsrc/lib.rs\t10\r
+\twarn\tCargo.toml\t8\r
+"
+    );
+    assert_eq!("begin-line".parse::<Column>().unwrap(), Column::BeginLine);
+    assert!("code".parse::<Column>().is_err());
+}
+
+#[test]
+fn render_spans_and_notes() {
+    let mut output = output();
+    let info = std::mem::take(&mut output.check_info[1]);
+    output.check_info[1] = info.with_span(CodeSpan::new((10, 7), (10, 17)));
+    output.check_info[1].secondary_spans =
+        vec![CodeSpan::new((6, 5), (6, 15)).with_label("and (1) occurred earlier here")];
+    output.check_info[1].children = vec![ChildMessage::help("use `?` instead")];
+    let notes = "src/lib.rs:6:5: and (1) occurred earlier here\nhelp: use `?` instead";
+
+    let github = OutputFormat::Github.render(&output).unwrap();
+    assert!(github.contains("line=10,col=7,endLine=10,endColumn=17,"));
+    assert!(github.contains(&format!("Don't panic)%0A{}\n", notes.replace('\n', "%0A"))));
+
+    let checkstyle = OutputFormat::Checkstyle.render(&output).unwrap();
+    assert!(checkstyle.contains(&format!(
+        "Don&apos;t panic)&#10;{}\"",
+        notes.replace('\n', "&#10;")
+    )));
+
+    let gitlab = OutputFormat::Gitlab.render(&output).unwrap();
+    let issues: serde_json::Value = serde_json::from_str(&gitlab).unwrap();
+    assert_eq!(issues[0].get("content"), None);
+    assert_eq!(
+        issues[1]["content"]["body"],
+        "- src/lib.rs:6:5: and (1) occurred earlier here\n- help: use `?` instead\n"
+    );
+
+    let markdown = OutputFormat::Markdown.render(&output).unwrap();
+    assert!(markdown.contains(
+        "(`clippy::unwrap_used`)
+  - src/lib.rs:6:5: and (1) occurred earlier here
+  - help: use `?` instead
+"
+    ));
+
+    let opts = RenderOpts {
+        columns: vec![Column::BeginLine, Column::EndColumn, Column::Notes],
+        ..Default::default()
+    };
+    let csv = OutputFormat::Csv.render_with(&output, &opts).unwrap();
+    assert!(csv.contains(&format!("\r\n10,17,\"{notes}\"\r\n")));
+}
@@ -1,6 +1,13 @@
-use eunomia::parser::{CheckInfo, GuidelineSummary, Output};
+use eunomia::parser::{
+    CheckInfo, CheckLevel, CheckTool, ChildMessage, CodeSpan, GuidelineSummary, Highlight,
+    JsonStruct, Output, OutputDiff, RunMetadata, ToolRun,
+};
+use eunomia::tools::doc_coverage::{CoverageCount, DocCoverage};
+use eunomia::tools::unsafe_audit::{UnsafeCount, UnsafeInventory};
 use eunomia::tools::SupportedTool;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 #[test]
 fn se_check_info_single_entity() {
@@ -16,14 +23,14 @@ fn se_check_info_single_entity() {
         guideline_list: vec![GuidelineSummary {
             id: "P.VAR.01".parse().unwrap(),
             name: "ssss".to_string(),
+            level: CheckLevel::Warn,
         }],
         ..Default::default()
     }];
-    let output = Output {
-        check_info: check_info_list,
-    };
+    let output = Output::from(check_info_list);
 
     let expected_json = r#"{
+  "schema_version": 1,
   "check_info": [
     {
       "file_path": "./src/main.rs",
@@ -35,10 +42,15 @@ fn se_check_info_single_entity() {
       "code_string": "",
       "help_info": "",
       "additional_help_info": "",
+      "span": null,
+      "secondary_spans": [],
+      "children": [],
+      "context": null,
       "guideline_list": [
         {
           "id": "p.var.01",
-          "name": "ssss"
+          "name": "ssss",
+          "level": "warn"
         }
       ]
     }
@@ -63,9 +75,14 @@ fn se_check_info_multiple_entities() {
             code_string: "xxx {\n\n\n xxa }".to_string(),
             help_info: String::new(),
             additional_help_info: String::new(),
+            span: Some(CodeSpan::new((15, 23), (18, 6)).with_label("public enum")),
+            secondary_spans: vec![CodeSpan::lines(3, 3).with_file("./src/lib.rs")],
+            children: vec![ChildMessage::help("try adding #[non_exhaustive]")],
+            context: None,
             guideline_list: vec![GuidelineSummary {
                 id: "P.VAR.01".parse().unwrap(),
                 name: "ssss".to_string(),
+                level: CheckLevel::Warn,
             }],
         },
         CheckInfo {
@@ -78,10 +95,12 @@ fn se_check_info_multiple_entities() {
                 GuidelineSummary {
                     id: "g.exam.ple.01".parse().unwrap(),
                     name: "don't have dead_code".to_string(),
+                    level: CheckLevel::Severe,
                 },
                 GuidelineSummary {
                     id: "p.exam.ple.02".parse().unwrap(),
                     name: "useless variables".to_string(),
+                    level: CheckLevel::Prompt,
                 },
             ],
             ..Default::default()
@@ -93,12 +112,14 @@ fn se_check_info_multiple_entities() {
             guideline_list: vec![GuidelineSummary {
                 id: "g.exam.ple.03".parse().unwrap(),
                 name: "free memory allocation after use".to_string(),
+                level: CheckLevel::Fatal,
             }],
             ..Default::default()
         },
     ];
 
     let expected_json = r#"{
+  "schema_version": 1,
   "check_info": [
     {
       "file_path": "./src/main.rs",
@@ -110,10 +131,36 @@ fn se_check_info_multiple_entities() {
       "code_string": "xxx {\n\n\n xxa }",
       "help_info": "",
       "additional_help_info": "",
+      "span": {
+        "file_path": null,
+        "begin_line": 15,
+        "begin_column": 23,
+        "end_line": 18,
+        "end_column": 6,
+        "label": "public enum"
+      },
+      "secondary_spans": [
+        {
+          "file_path": "./src/lib.rs",
+          "begin_line": 3,
+          "begin_column": null,
+          "end_line": 3,
+          "end_column": null,
+          "label": null
+        }
+      ],
+      "children": [
+        {
+          "kind": "help",
+          "message": "try adding #[non_exhaustive]"
+        }
+      ],
+      "context": null,
       "guideline_list": [
         {
           "id": "p.var.01",
-          "name": "ssss"
+          "name": "ssss",
+          "level": "warn"
         }
       ]
     },
@@ -127,14 +174,20 @@ fn se_check_info_multiple_entities() {
       "code_string": "let x = 1;",
       "help_info": "",
       "additional_help_info": "",
+      "span": null,
+      "secondary_spans": [],
+      "children": [],
+      "context": null,
       "guideline_list": [
         {
           "id": "g.exam.ple.01",
-          "name": "don't have dead_code"
+          "name": "don't have dead_code",
+          "level": "severe"
         },
         {
           "id": "p.exam.ple.02",
-          "name": "useless variables"
+          "name": "useless variables",
+          "level": "prompt"
         }
       ]
     },
@@ -148,20 +201,22 @@ fn se_check_info_multiple_entities() {
       "code_string": "",
       "help_info": "",
       "additional_help_info": "",
+      "span": null,
+      "secondary_spans": [],
+      "children": [],
+      "context": null,
       "guideline_list": [
         {
           "id": "g.exam.ple.03",
-          "name": "free memory allocation after use"
+          "name": "free memory allocation after use",
+          "level": "fatal"
         }
       ]
     }
   ]
 }"#;
 
-    let op_str = Output {
-        check_info: check_info_list,
-    }
-    .to_json_string_pretty();
+    let op_str = Output::from(check_info_list).to_json_string_pretty();
 
     assert!(op_str.is_ok());
     assert_eq!(op_str.unwrap(), expected_json);
@@ -172,12 +227,10 @@ fn se_empty_check_info() {
     // FIXME: this should not be a valid output,
     // after we have derive macro for CheckInfo, all non-option
     // fields should be required, and will throw error when not provided.
-    let op_str = Output {
-        check_info: vec![CheckInfo::default()],
-    }
-    .to_json_string_pretty();
+    let op_str = Output::from(vec![CheckInfo::default()]).to_json_string_pretty();
 
     let expected_json = r#"{
+  "schema_version": 1,
   "check_info": [
     {
       "file_path": null,
@@ -189,6 +242,10 @@ fn se_empty_check_info() {
       "code_string": "",
       "help_info": "",
       "additional_help_info": "",
+      "span": null,
+      "secondary_spans": [],
+      "children": [],
+      "context": null,
       "guideline_list": []
     }
   ]
@@ -197,3 +254,376 @@ fn se_empty_check_info() {
     assert!(op_str.is_ok());
     assert_eq!(op_str.unwrap(), expected_json);
 }
+
+#[test]
+fn check_info_detected_by_tool() {
+    let info = CheckInfo {
+        tool: SupportedTool::Clippy,
+        defect_name: "clippy::almost-swapped".to_string(),
+        ..Default::default()
+    };
+    let rustc_info = CheckInfo {
+        tool: SupportedTool::Clippy,
+        defect_name: "non_ascii_idents".to_string(),
+        ..Default::default()
+    };
+    let miri_info = CheckInfo {
+        tool: SupportedTool::Miri,
+        help_info: "Undefined Behavior: Data race detected between (1) ...".to_string(),
+        ..Default::default()
+    };
+
+    let clippy_tool = CheckTool {
+        name: SupportedTool::Clippy,
+        ident: "clippy::almost_swapped",
+    };
+    let rustc_tool = CheckTool {
+        name: SupportedTool::Rustc,
+        ident: "non-ascii-idents",
+    };
+    let miri_tool = CheckTool {
+        name: SupportedTool::Miri,
+        ident: "Data race detected",
+    };
+
+    assert!(info.is_detected_by(&clippy_tool));
+    assert!(!info.is_detected_by(&rustc_tool));
+    assert!(rustc_info.is_detected_by(&rustc_tool));
+    assert!(miri_info.is_detected_by(&miri_tool));
+    assert!(!miri_info.is_detected_by(&clippy_tool));
+    assert!(!CheckInfo::default().is_detected_by(&miri_tool));
+}
+
+#[test]
+fn de_output_round_trip() {
+    let output_file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/output_lints_expected.json");
+    let content = fs::read_to_string(output_file).unwrap();
+    let output = Output::deserialize(&content).unwrap();
+    assert_eq!(output.check_info.len(), 16);
+    assert_eq!(output.check_info[0].tool, SupportedTool::Rustc);
+    assert_eq!(
+        output.check_info[0].guideline_list[0].level,
+        CheckLevel::Warn
+    );
+    assert_eq!(output.to_json_string_pretty().unwrap(), content);
+}
+
+#[test]
+fn merge_outputs() {
+    let info = |line, id: &str| CheckInfo {
+        file_path: Some(PathBuf::from("src/lib.rs")),
+        defect_name: "clippy::unwrap_used".to_string(),
+        tool: SupportedTool::Clippy,
+        begin_line: Some(line),
+        code_string: "x.unwrap()".to_string(),
+        guideline_list: vec![GuidelineSummary {
+            id: id.parse().unwrap(),
+            name: String::new(),
+            level: CheckLevel::Warn,
+        }],
+        ..Default::default()
+    };
+    let mut output = Output::from(vec![info(3, "G.ERR.01"), info(8, "G.ERR.01")]);
+    output.merge(Output::from(vec![
+        info(3, "G.ERR.02"),
+        info(3, "G.ERR.01"),
+        info(12, "G.ERR.02"),
+    ]));
+
+    let merged = output
+        .check_info
+        .iter()
+        .map(|info| {
+            let ids = info.guideline_list.iter().map(|gl| gl.id.to_string());
+            (info.begin_line.unwrap(), ids.collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        merged,
+        [
+            (3, vec!["g.err.01".to_string(), "g.err.02".to_string()]),
+            (8, vec!["g.err.01".to_string()]),
+            (12, vec!["g.err.02".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn merge_sections_of_crates() {
+    let coverage = |public_items, documented| DocCoverage {
+        total: CoverageCount {
+            public_items,
+            documented,
+            percentage: documented as f64 * 100.0 / public_items as f64,
+        },
+        modules: BTreeMap::from([(
+            "crate".to_string(),
+            CoverageCount {
+                public_items,
+                documented,
+                percentage: documented as f64 * 100.0 / public_items as f64,
+            },
+        )]),
+    };
+    let inventory = |file: &str, blocks, undocumented| {
+        let count = UnsafeCount {
+            blocks,
+            undocumented,
+            ..Default::default()
+        };
+        UnsafeInventory {
+            total: count.clone(),
+            files: BTreeMap::from([(PathBuf::from(file), count.clone())]),
+            modules: BTreeMap::from([("crate".to_string(), count)]),
+        }
+    };
+    let output = |coverage, inventory| Output {
+        doc_coverage: Some(coverage),
+        unsafe_inventory: Some(inventory),
+        ..Output::from(vec![])
+    };
+
+    // both crates have a root module named `crate`
+    let mut merged = output(coverage(4, 3), inventory("a/src/lib.rs", 2, 1));
+    merged.merge(output(coverage(4, 1), inventory("b/src/lib.rs", 3, 0)));
+
+    let coverage = merged.doc_coverage.unwrap();
+    assert_eq!(coverage.total.public_items, 8);
+    assert_eq!(coverage.total.documented, 4);
+    assert_eq!(coverage.total.percentage, 50.0);
+    assert_eq!(coverage.modules["crate"], coverage.total);
+
+    let inventory = merged.unsafe_inventory.unwrap();
+    assert_eq!(inventory.total.blocks, 5);
+    assert_eq!(inventory.total.undocumented, 1);
+    assert_eq!(inventory.files.len(), 2);
+    assert_eq!(inventory.modules["crate"], inventory.total);
+}
+
+#[test]
+fn diff_outputs() {
+    let info = |line, code: &str, id: &str, level| CheckInfo {
+        file_path: Some(PathBuf::from("./src/lib.rs")),
+        defect_name: "clippy::unwrap_used".to_string(),
+        tool: SupportedTool::Clippy,
+        begin_line: Some(line),
+        code_string: code.to_string(),
+        guideline_list: vec![GuidelineSummary {
+            id: id.parse().unwrap(),
+            name: String::new(),
+            level,
+        }],
+        ..Default::default()
+    };
+    let old = Output::from(vec![
+        info(3, "a.unwrap()", "G.ERR.01", CheckLevel::Warn),
+        info(5, "b.unwrap()", "G.ERR.01", CheckLevel::Warn),
+        info(9, "c.unwrap()", "G.ERR.02", CheckLevel::Prompt),
+    ]);
+    // `a` moved to another line, `b` got fixed, and `d` is new
+    let new = Output::from(vec![
+        info(10, "a.unwrap()", "G.ERR.01", CheckLevel::Warn),
+        info(12, "c.unwrap()", "G.ERR.02", CheckLevel::Prompt),
+        info(20, "d.unwrap()", "G.ERR.02", CheckLevel::Prompt),
+    ]);
+    let diff = OutputDiff::new(&old, &new);
+    let lines = |infos: &[&CheckInfo]| {
+        infos
+            .iter()
+            .map(|i| i.begin_line.unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(lines(&diff.new), [20]);
+    assert_eq!(lines(&diff.fixed), [5]);
+    assert_eq!(lines(&diff.unchanged), [10, 12]);
+
+    let deltas = diff
+        .guideline_deltas()
+        .iter()
+        .map(|d| {
+            (
+                d.id.to_string(),
+                d.old_count(),
+                d.new_count(),
+                d.new,
+                d.fixed,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        deltas,
+        [
+            ("g.err.01".to_string(), 2, 1, 0, 1),
+            ("g.err.02".to_string(), 1, 2, 1, 0),
+        ]
+    );
+    assert_eq!(diff.new_at_least(CheckLevel::Prompt).len(), 1);
+    assert!(diff.new_at_least(CheckLevel::Warn).is_empty());
+}
+
+#[test]
+fn capture_source_context() {
+    let info = |file: &str, span| {
+        CheckInfo {
+            file_path: Some(PathBuf::from(file)),
+            ..Default::default()
+        }
+        .with_span(span)
+    };
+    let mut output = Output::from(vec![
+        info("src/lints.rs", CodeSpan::new((22, 13), (22, 16))),
+        info("src/lints.rs", CodeSpan::lines(1, 1)),
+        info("src/missing.rs", CodeSpan::lines(1, 1)),
+    ]);
+    output.metadata = Some(RunMetadata {
+        eunomia_version: String::new(),
+        started_at: String::new(),
+        finished_at: String::new(),
+        paths: vec![Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/mock/src/lib.rs")],
+        rules_file: None,
+        rules_hash: None,
+        tools: vec![],
+        skipped_tools: vec![],
+        requested_guidelines: vec![],
+        enforceable_guidelines: vec![],
+    });
+    output.capture_context(1);
+
+    // paths are relative to the crate, which is the parent of the checked file's directory
+    let lines = &output.check_info[0].context.as_ref().unwrap().lines;
+    let numbers = lines.iter().map(|line| line.line).collect::<Vec<_>>();
+    assert_eq!(numbers, [21, 22, 23]);
+    assert_eq!(lines[1].text, "    let _ = --x;");
+    assert_eq!(lines[0].highlight, None);
+    assert_eq!(
+        lines[1].highlight,
+        Some(Highlight {
+            begin_column: 13,
+            end_column: 16
+        })
+    );
+    // there's no line before the first one
+    let lines = &output.check_info[1].context.as_ref().unwrap().lines;
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].line, 1);
+    assert!(output.check_info[2].context.is_none());
+}
+
+#[test]
+fn summarize_output() {
+    let info = |file: &str, tool, ids: &[&str], level| CheckInfo {
+        file_path: Some(PathBuf::from(file)),
+        tool,
+        guideline_list: ids
+            .iter()
+            .map(|id| GuidelineSummary {
+                id: id.parse().unwrap(),
+                name: String::new(),
+                level,
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let mut output = Output::from(vec![
+        info(
+            "./src/lib.rs",
+            SupportedTool::Clippy,
+            &["G.ERR.01"],
+            CheckLevel::Warn,
+        ),
+        info(
+            "src/lib.rs",
+            SupportedTool::Clippy,
+            &["G.ERR.01", "G.ERR.02"],
+            CheckLevel::Severe,
+        ),
+        info(
+            "src/main.rs",
+            SupportedTool::Rustfmt,
+            &["G.FMT.01"],
+            CheckLevel::Info,
+        ),
+    ]);
+    let run = |name, duration_ms, exit_code| ToolRun {
+        name,
+        version: None,
+        duration_ms: Some(duration_ms),
+        exit_code,
+    };
+    let ids = |ids: &[&str]| ids.iter().map(|id| id.parse().unwrap()).collect();
+    output.metadata = Some(RunMetadata {
+        eunomia_version: String::new(),
+        started_at: String::new(),
+        finished_at: String::new(),
+        paths: vec![],
+        rules_file: None,
+        rules_hash: None,
+        tools: vec![
+            run(SupportedTool::Clippy, 1200, Some(0)),
+            run(SupportedTool::UnsafeAudit, 30, None),
+        ],
+        skipped_tools: vec![],
+        requested_guidelines: ids(&["G.ERR.01", "G.ERR.02", "G.FMT.01", "G.MET.01", "P.TYP.01"]),
+        enforceable_guidelines: ids(&["G.ERR.01", "G.ERR.02", "G.MET.01", "P.TYP.01"]),
+    });
+    output.summarize();
+
+    let summary = output.summary.as_ref().unwrap();
+    assert_eq!(summary.total, 3);
+    assert_eq!(
+        summary.by_level,
+        BTreeMap::from([
+            (CheckLevel::Info, 1),
+            (CheckLevel::Warn, 1),
+            (CheckLevel::Severe, 1)
+        ])
+    );
+    let by_guideline = summary.by_guideline.iter().map(|(id, n)| (id.as_str(), *n));
+    assert_eq!(
+        by_guideline.collect::<Vec<_>>(),
+        [("g.err.01", 2), ("g.err.02", 1), ("g.fmt.01", 1)]
+    );
+    let by_file = summary.by_file.iter().map(|(file, n)| (file.as_str(), *n));
+    assert_eq!(
+        by_file.collect::<Vec<_>>(),
+        [("src/lib.rs", 2), ("src/main.rs", 1)]
+    );
+    let tools = summary
+        .tools
+        .iter()
+        .map(|t| (t.name, t.findings, t.duration_ms, t.exit_code))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tools,
+        [
+            (SupportedTool::Clippy, 2, Some(1200), Some(0)),
+            (SupportedTool::UnsafeAudit, 0, Some(30), None),
+            (SupportedTool::Rustfmt, 1, None, None),
+        ]
+    );
+    let guidelines = summary.guidelines.unwrap();
+    assert_eq!(
+        (
+            guidelines.requested,
+            guidelines.enforceable,
+            guidelines.violated
+        ),
+        (5, 4, 3)
+    );
+    // the violated G.FMT.01 isn't enforceable, so it doesn't count
+    assert_eq!(summary.compliance, Some(0.5));
+
+    // the summary survives a round trip, but not a merge
+    let json = output.to_json_string().unwrap();
+    let mut read = Output::deserialize(&json).unwrap();
+    assert_eq!(read.summary.as_ref(), Some(summary));
+    read.merge(Output::from(vec![]));
+    assert!(read.summary.is_none());
+
+    let mut output = Output::from(vec![]);
+    output.summarize();
+    let summary = output.summary.unwrap();
+    assert_eq!((summary.total, summary.guidelines), (0, None));
+    assert_eq!(summary.compliance, None);
+}
@@ -0,0 +1,256 @@
+mod common;
+use common::setup;
+use eunomia::parser::{JsonStruct, Output, OUTPUT_SCHEMA, SCHEMA_VERSION};
+use eunomia::tools::SupportedTool;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Validate a value against the subset of JSON Schema used by the output schema,
+/// and return the paths of the invalid values.
+fn validate(root: &Value, schema: &Value, value: &Value, path: &str) -> Vec<String> {
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference.trim_start_matches("#/$defs/");
+        return validate(root, &root["$defs"][name], value, path);
+    }
+    if let Some(schemas) = schema["anyOf"].as_array() {
+        let mut errors = vec![];
+        for schema in schemas {
+            let found = validate(root, schema, value, path);
+            if found.is_empty() {
+                return found;
+            }
+            errors.extend(found);
+        }
+        return errors;
+    }
+    let mut errors = vec![];
+    let mut fail = |reason: &str| errors.push(format!("{path}: {reason}"));
+
+    if let Some(ty) = schema.get("type") {
+        let types = match ty {
+            Value::Array(types) => types.iter().map(|t| t.as_str().unwrap()).collect(),
+            _ => vec![ty.as_str().unwrap()],
+        };
+        let matched = types.iter().any(|ty| match *ty {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_u64() || value.is_i64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => panic!("unknown type `{ty}` in the schema"),
+        });
+        if !matched {
+            fail(&format!("expected {ty}, found {value}"));
+            return errors;
+        }
+    }
+    if let Some(variants) = schema["enum"].as_array() {
+        if !variants.contains(value) {
+            fail(&format!("{value} is not one of {variants:?}"));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            fail(&format!("expected {expected}, found {value}"));
+        }
+    }
+    if let (Some(min), Some(n)) = (schema["minimum"].as_f64(), value.as_f64()) {
+        if n < min {
+            fail(&format!("{n} is less than {min}"));
+        }
+    }
+    if let (Some(max), Some(n)) = (schema["maximum"].as_f64(), value.as_f64()) {
+        if n > max {
+            fail(&format!("{n} is greater than {max}"));
+        }
+    }
+    if let Some(object) = value.as_object() {
+        for key in schema["required"].as_array().into_iter().flatten() {
+            if !object.contains_key(key.as_str().unwrap()) {
+                fail(&format!("missing required property {key}"));
+            }
+        }
+        for (key, field) in object {
+            let field_path = format!("{path}.{key}");
+            match (
+                schema["properties"].get(key),
+                &schema["additionalProperties"],
+            ) {
+                (Some(field_schema), _) => {
+                    errors.extend(validate(root, field_schema, field, &field_path));
+                }
+                (None, Value::Bool(false)) => {
+                    errors.push(format!("{field_path}: unknown property"))
+                }
+                (None, Value::Object(_)) => errors.extend(validate(
+                    root,
+                    &schema["additionalProperties"],
+                    field,
+                    &field_path,
+                )),
+                (None, _) => (),
+            }
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in array.iter().enumerate() {
+            errors.extend(validate(root, items, item, &format!("{path}[{i}]")));
+        }
+    }
+    errors
+}
+
+fn assert_valid(output: &Value) {
+    let schema: Value = serde_json::from_str(OUTPUT_SCHEMA).unwrap();
+    let errors = validate(&schema, &schema, output, "$");
+    assert!(errors.is_empty(), "invalid output: {errors:#?}");
+}
+
+#[test]
+fn schema_follows_output() {
+    let schema: Value = serde_json::from_str(OUTPUT_SCHEMA).unwrap();
+    assert_eq!(
+        schema["properties"]["schema_version"]["const"],
+        SCHEMA_VERSION
+    );
+    let tools = SupportedTool::all()
+        .iter()
+        .map(|tool| Value::from(tool.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(schema["$defs"]["tool"]["enum"], Value::from(tools));
+
+    let invalid = serde_json::json!({
+        "schema_version": SCHEMA_VERSION,
+        "check_info": [{"defect_name": "x", "tool": "cargo"}],
+        "extra": true,
+    });
+    let errors = validate(&schema, &schema, &invalid, "$");
+    assert!(errors.contains(&"$.extra: unknown property".to_string()));
+    assert!(errors
+        .iter()
+        .any(|e| e.starts_with("$.check_info[0].tool: ")));
+    assert!(errors
+        .iter()
+        .any(|e| e == "$.check_info[0]: missing required property \"guideline_list\""));
+}
+
+#[test]
+fn print_schema() {
+    setup(|cfg| {
+        let res = Command::new(&cfg.bin_path).arg("schema").output().unwrap();
+        assert!(res.status.success());
+        assert_eq!(String::from_utf8(res.stdout).unwrap(), OUTPUT_SCHEMA);
+    });
+}
+
+#[test]
+fn emitted_outputs_follow_schema() {
+    setup(|cfg| {
+        let expected = fs::read_to_string(cfg.test_dir.join("data/output_lints_expected.json"));
+        assert_valid(&serde_json::from_str(&expected.unwrap()).unwrap());
+
+        let check = |name: &str, rules: &str, guidelines: Option<&str>, src: &str| {
+            let rule_file = cfg.output_dir.join(format!("rules_schema_{name}.json"));
+            let output_file = cfg.output_dir.join(format!("output_schema_{name}.json"));
+            fs::write(&rule_file, rules).unwrap();
+            let mut cmd = Command::new(&cfg.bin_path);
+            if let Some(guidelines) = guidelines {
+                cmd.arg("--guidelines").arg(cfg.test_dir.join(guidelines));
+            }
+            let st = cmd
+                .arg("--rule-file")
+                .arg(&rule_file)
+                .arg("--src-file")
+                .arg(cfg.test_dir.join(src))
+                .arg("--output")
+                .arg(&output_file)
+                .args(["--context-lines", "1"])
+                .env_remove("LANG")
+                .output()
+                .unwrap()
+                .status;
+            assert!(st.success());
+            let content = fs::read_to_string(&output_file).unwrap();
+            let output: Value = serde_json::from_str(&content).unwrap();
+            assert_valid(&output);
+            assert!(Output::deserialize(&content).is_ok());
+            output
+        };
+
+        let output = check(
+            "unsafe_audit",
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["G.UNS.SAS.01", "G.UNS.SAS.02"]}"#,
+            Some("data/guidelines_unsafe_audit.json"),
+            "data/unsafe_audit/src/lib.rs",
+        );
+        assert!(output["unsafe_inventory"].is_object());
+        let context = &output["check_info"][0]["context"]["lines"];
+        assert!(context
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|line| line["highlight"].is_object()));
+        let output = check(
+            "metrics",
+            r#"{"file_path": "src/lib.rs", "metrics": {"max_nesting_depth": 3}}"#,
+            None,
+            "data/metrics/src/lib.rs",
+        );
+        assert!(output["metrics"].is_object());
+        let output = check(
+            "doc_coverage",
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["G.CMT.01", "G.CMT.02"]}"#,
+            Some("data/guidelines_doc_coverage.json"),
+            "data/doc_coverage/src/lib.rs",
+        );
+        assert!(output["doc_coverage"].is_object());
+        let output = check(
+            "panics",
+            r#"{"file_path": "src/lib.rs", "coding_guidelines": ["G.CMT.02"], "panics": {}}"#,
+            None,
+            "data/panics/src/lib.rs",
+        );
+        assert!(output["panics"].is_object());
+        let tools = &output["metadata"]["tools"];
+        assert!(tools
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|t| t["name"] == "panics"));
+    });
+}
+
+#[test]
+fn reject_newer_schema_version() {
+    setup(|cfg| {
+        let input = cfg.output_dir.join("output_schema_newer.json");
+        let newer = serde_json::json!({"schema_version": SCHEMA_VERSION + 1, "check_info": []});
+        fs::write(&input, newer.to_string()).unwrap();
+        let res = Command::new(&cfg.bin_path)
+            .args(["report", "render"])
+            .arg(&input)
+            .output()
+            .unwrap();
+        assert!(!res.status.success());
+        assert!(
+            String::from_utf8_lossy(&res.stderr).contains("is newer than the supported version")
+        );
+
+        // outputs written before versioning are still accepted
+        let older = cfg.output_dir.join("output_schema_older.json");
+        fs::write(&older, r#"{"check_info": []}"#).unwrap();
+        let rendered = cfg.output_dir.join("output_schema_older.csv");
+        let st = Command::new(&cfg.bin_path)
+            .args(["report", "render", "--format", "csv", "--output"])
+            .arg(&rendered)
+            .arg(&older)
+            .status()
+            .unwrap();
+        assert!(st.success());
+        assert!(Path::new(&rendered).exists());
+    });
+}
@@ -66,3 +66,136 @@ fn de_rules_missing_path() {
     let test_cfg = RulesCfg::deserialize(rule_str);
     assert!(test_cfg.is_err());
 }
+
+#[test]
+fn de_rules_with_metrics() {
+    let rule_str = r#"
+{
+    "file_path": "./src/main.rs",
+    "metrics": {
+        "max_fn_lines": 60,
+        "max_params": 4
+    }
+}"#;
+
+    let cfg = RulesCfg::deserialize(rule_str).unwrap();
+    assert_eq!(
+        cfg.metrics,
+        Some(MetricsCfg {
+            max_fn_lines: 60,
+            max_params: 4,
+            ..Default::default()
+        })
+    );
+    assert!(RulesCfg::deserialize(r#"{"file_path": "./src/main.rs"}"#)
+        .unwrap()
+        .metrics
+        .is_none());
+}
+
+#[test]
+fn de_rules_with_manifest() {
+    let rule_str = r#"
+{
+    "file_path": "./src/main.rs",
+    "manifest": {
+        "required_fields": ["license"],
+        "banned_crates": ["openssl"]
+    }
+}"#;
+
+    let cfg = RulesCfg::deserialize(rule_str).unwrap();
+    assert_eq!(
+        cfg.manifest,
+        Some(ManifestCfg {
+            required_fields: vec!["license".to_string()],
+            banned_crates: vec!["openssl".to_string()],
+            ..Default::default()
+        })
+    );
+}
+
+#[test]
+fn de_rules_with_advisory() {
+    let rule_str = r#"
+{
+    "file_path": "./src/main.rs",
+    "advisory": {
+        "db_path": "../advisory-db",
+        "ignore": ["RUSTSEC-2020-0071"]
+    }
+}"#;
+
+    let cfg = RulesCfg::deserialize(rule_str).unwrap();
+    assert_eq!(
+        cfg.advisory,
+        Some(AdvisoryCfg {
+            db_path: "../advisory-db".into(),
+            index_path: None,
+            ignore: vec!["RUSTSEC-2020-0071".to_string()],
+        })
+    );
+    assert!(RulesCfg::deserialize(r#"{"file_path": "./src/main.rs", "advisory": {}}"#).is_err());
+}
+
+#[test]
+fn de_rules_with_license() {
+    let rule_str = r#"
+{
+    "file_path": "./src/main.rs",
+    "license": {
+        "denied": ["GPL-3.0-only", "Apache-2.0 WITH LLVM-exception"]
+    }
+}"#;
+
+    let cfg = RulesCfg::deserialize(rule_str).unwrap();
+    assert_eq!(
+        cfg.license,
+        Some(LicenseCfg {
+            allowed: vec![],
+            denied: vec![
+                "GPL-3.0-only".to_string(),
+                "Apache-2.0 WITH LLVM-exception".to_string()
+            ],
+        })
+    );
+}
+
+#[test]
+fn de_rules_with_rustfmt() {
+    let rule_str = r#"
+{
+    "file_path": "./src/main.rs",
+    "rustfmt": {
+        "edition": "2018",
+        "config": {"max_width": 80, "group_imports": "StdExternalCrate"}
+    }
+}"#;
+
+    let cfg = RulesCfg::deserialize(rule_str).unwrap();
+    let rustfmt = cfg.rustfmt.unwrap();
+    assert_eq!(rustfmt.edition.as_deref(), Some("2018"));
+    assert_eq!(rustfmt.config["max_width"], 80);
+    assert_eq!(rustfmt.config["group_imports"], "StdExternalCrate");
+}
+
+#[test]
+fn de_rules_with_panics() {
+    let rule_str = r#"
+{
+    "file_path": "./src/lib.rs",
+    "panics": {"lints": ["clippy::unwrap_used"]}
+}"#;
+
+    let cfg = RulesCfg::deserialize(rule_str).unwrap();
+    let panics = cfg.panics.unwrap();
+    assert_eq!(panics.lints, ["clippy::unwrap_used"]);
+    assert!(panics.assertions);
+
+    let cfg = RulesCfg::deserialize(r#"{"file_path": "./src/lib.rs", "panics": {}}"#).unwrap();
+    assert!(cfg
+        .panics
+        .unwrap()
+        .lints
+        .contains(&"clippy::indexing_slicing".to_string()));
+}
@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+
+use eunomia::parser::CheckInfo;
+use eunomia::tools::source::parse_sources;
+use eunomia::tools::{PatternOpt, SourceChecker, SupportedTool};
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("data")
+        .join("source_patterns")
+        .join("src")
+}
+
+fn check(patterns: &[&str]) -> Vec<CheckInfo> {
+    let mut opt = PatternOpt::default();
+    for p in patterns {
+        opt.add(p).unwrap();
+    }
+    let sources = parse_sources(&fixture_dir().join("lib.rs")).unwrap().files;
+    let mut result = opt.check_sources(&sources);
+    result.sort_by_key(|info| (info.file_path.clone(), info.begin_line, info.column));
+    result
+}
+
+/// (file name, line, column) of each finding.
+fn positions(infos: &[CheckInfo]) -> Vec<(String, usize, usize)> {
+    infos
+        .iter()
+        .map(|info| {
+            let path = info.file_path.as_ref().unwrap();
+            let name = path.strip_prefix(fixture_dir()).unwrap();
+            (
+                name.display().to_string(),
+                info.begin_line.unwrap(),
+                info.column.unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn parse_modules_of_crate() {
+    let mut sources = parse_sources(&fixture_dir()).unwrap().files;
+    sources.sort_by(|a, b| a.path.cmp(&b.path));
+    let modules = sources
+        .iter()
+        .map(|src| src.module.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(modules, ["crate", "crate::shapes"]);
+}
+
+#[test]
+fn parse_crate_directory() {
+    // only `src` is parsed, and files that fail to parse are skipped
+    let crate_dir = fixture_dir().join("../../source_crate");
+    let sources = parse_sources(&crate_dir).unwrap();
+    let modules = sources
+        .files
+        .iter()
+        .map(|src| src.module.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(modules, ["crate", "crate::shapes"]);
+    assert_eq!(sources.skipped.len(), 1);
+    assert!(sources.skipped[0].0.ends_with("src/broken.rs"));
+    assert!(sources.skipped[0].1.contains("failed to parse source file"));
+}
+
+#[test]
+fn forbidden_paths() {
+    // `exit(1)` is not reported, as a single segment is too ambiguous to match.
+    let res = check(&["path:std::process::exit", "path:dbg"]);
+    assert_eq!(
+        positions(&res),
+        [
+            ("lib.rs".to_string(), 5, 19),
+            ("lib.rs".to_string(), 19, 5),
+            ("shapes/mod.rs".to_string(), 14, 19),
+        ]
+    );
+    assert!(res.iter().all(|info| info.tool == SupportedTool::Pattern));
+    assert_eq!(res[0].defect_name, "path:std::process::exit");
+    assert_eq!(res[0].code_string, "use std::process::exit;");
+    assert_eq!(res[1].defect_name, "path:dbg");
+}
+
+#[test]
+fn item_names() {
+    let res = check(&["item:pub-fn:!^get_", "item:static:^G_"]);
+    assert_eq!(
+        positions(&res),
+        [("lib.rs".to_string(), 8, 12), ("lib.rs".to_string(), 10, 8)]
+    );
+    assert_eq!(
+        res[0].help_info,
+        "name of static `TOTAL` does not match `^G_`"
+    );
+    assert_eq!(
+        res[1].help_info,
+        "name of fn `get_total` does match `^get_`"
+    );
+}
+
+#[test]
+fn item_attributes() {
+    let res = check(&["attr:pub-struct:derive(Debug)"]);
+    assert_eq!(positions(&res), [("shapes/mod.rs".to_string(), 7, 12)]);
+    assert_eq!(
+        res[0].help_info,
+        "struct `Square` is missing attribute `derive(Debug)`"
+    );
+
+    let res = check(&["attr:struct:!derive(Clone)"]);
+    assert_eq!(
+        positions(&res),
+        [
+            ("shapes/mod.rs".to_string(), 2, 12),
+            ("shapes/mod.rs".to_string(), 7, 12),
+        ]
+    );
+}
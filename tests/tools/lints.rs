@@ -1,7 +1,8 @@
 use super::mock_dir;
 use crate::common::setup;
 use anyhow::Result;
-use eunomia::{parser::CheckInfo, tools::*};
+use eunomia::parser::{CheckInfo, ChildMessage, CodeSpan};
+use eunomia::tools::*;
 use std::{fs, path::PathBuf};
 
 /// Manually compare two check info, without the need of impl PartialEq for the entire
@@ -19,6 +20,9 @@ fn assert_eq_check_info(lhs: &CheckInfo, rhs: &CheckInfo) {
     );
     assert_eq!(lhs.code_string, rhs.code_string, "code_string");
     assert_eq!(lhs.tool, rhs.tool, "tool");
+    assert_eq!(lhs.span, rhs.span, "span");
+    assert_eq!(lhs.secondary_spans, rhs.secondary_spans, "secondary_spans");
+    assert_eq!(lhs.children, rhs.children, "children");
     assert_eq!(lhs.guideline_list, rhs.guideline_list, "guideline_list");
 }
 
@@ -73,56 +77,103 @@ fn clippy_lints_default() {
 
 #[test]
 fn clippy_unified_output() {
-    let output_1 = "error: range is out of bounds
-  --> src/lints.rs:12:19
-   |
-12 |     let _ = &x[2..9];
-   |                   ^
-   |
-   = help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#out_of_bounds_indexing
-   = note: `#[deny(clippy::out_of_bounds_indexing)]` on by default";
+    let output_1 = r#"{"reason":"compiler-message","package_id":"mock","message":{"message":"range is out of bounds","code":{"code":"clippy::out_of_bounds_indexing","explanation":null},"level":"error","spans":[{"file_name":"src/lints.rs","byte_start":200,"byte_end":201,"line_start":9,"line_end":9,"column_start":19,"column_end":20,"is_primary":true,"text":[{"text":"    let _ = &x[2..9];","highlight_start":19,"highlight_end":20}],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"for further information visit https://rust-lang.github.io/rust-clippy/main/index.html#out_of_bounds_indexing","code":null,"level":"help","spans":[],"children":[],"rendered":null},{"message":"`#[deny(clippy::out_of_bounds_indexing)]` on by default","code":null,"level":"note","spans":[],"children":[],"rendered":null}],"rendered":"error: range is out of bounds\n"}}"#;
 
     let info = LintsOpt {
         is_clippy: true,
+        path: mock_dir().to_path_buf(),
         ..Default::default()
     }
     .check_info(output_1)
     .unwrap();
 
     let expected = CheckInfo {
-      file_path: Some(PathBuf::from("src/lints.rs")),
+      file_path: Some(mock_dir().join("src/lints.rs")),
       tool: SupportedTool::Clippy,
-      begin_line: Some(12),
+      begin_line: Some(9),
+      end_line: Some(9),
       column: Some(19),
       help_info: "range is out of bounds".to_string(),
       defect_name: "clippy::out_of_bounds_indexing".to_string(),
       code_string: "    let _ = &x[2..9];".to_string(),
       additional_help_info:  "\
-        help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#out_of_bounds_indexing\n\
+        help: for further information visit https://rust-lang.github.io/rust-clippy/main/index.html#out_of_bounds_indexing\n\
         note: `#[deny(clippy::out_of_bounds_indexing)]` on by default\n".to_string(),
+      span: Some(CodeSpan::new((9, 19), (9, 20))),
+      children: vec![
+        ChildMessage::help("for further information visit https://rust-lang.github.io/rust-clippy/main/index.html#out_of_bounds_indexing"),
+        ChildMessage::note("`#[deny(clippy::out_of_bounds_indexing)]` on by default"),
+      ],
       ..Default::default()
    };
 
     assert_eq_check_info(&info, &expected);
+
+    // messages that aren't lint diagnostics are rejected
+    let opt = LintsOpt {
+        path: mock_dir().to_path_buf(),
+        ..Default::default()
+    };
+    assert!(opt.check_info(r#"{"reason":"build-finished","success":true}"#).is_err());
+    assert!(opt.check_info("warning: unused variable: `x`").is_err());
+}
+
+#[test]
+fn filtered_json_output() {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::{ExitStatus, Output};
+
+    let diagnostic = r#"{"message":"denote infinite loops with `loop { ... }`","code":{"code":"while_true","explanation":null},"level":"warning","spans":[{"file_name":"src/lints.rs","byte_start":803,"byte_end":813,"line_start":43,"line_end":43,"column_start":5,"column_end":15,"is_primary":true,"text":[],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[],"rendered":null}"#;
+    // cargo wraps the diagnostics of each target, and reports the build along with them
+    let stdout = format!(
+        "{{\"reason\":\"compiler-message\",\"target\":{{\"kind\":[\"lib\"]}},\"message\":{diagnostic}}}\n\
+        {{\"reason\":\"compiler-message\",\"target\":{{\"kind\":[\"test\"]}},\"message\":{diagnostic}}}\n\
+        {{\"reason\":\"build-finished\",\"success\":true}}\n"
+    );
+    let output = Output {
+        status: ExitStatus::from_raw(0),
+        stdout: stdout.into_bytes(),
+        stderr: b"    Checking mock v0.1.0\n".to_vec(),
+    };
+    let opt = LintsOpt {
+        use_cargo: true,
+        path: mock_dir().to_path_buf(),
+        json_output: true,
+        ..Default::default()
+    };
+
+    let filtered = opt.filter_output(&output);
+    assert_eq!(filtered.stdout.len(), 1);
+    assert!(filtered.stderr.is_empty());
+    let info = opt.check_info(&filtered.stdout[0]).unwrap();
+    assert_eq!(info.file_path, Some(mock_dir().join("src/lints.rs")));
+    assert_eq!(info.defect_name, "while_true");
+    assert_eq!(info.span, Some(CodeSpan::new((43, 5), (43, 15))));
 }
 
 #[test]
 fn clippy_unified_output_short() {
-    let output_1 = "warning: package `mock` is missing `package.categories` metadata
-  |
-  = help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#cargo_common_metadata";
+    // lints of the package point at the beginning of the crate root without any code
+    let output_1 = r#"{"message":"package `mock` is missing `package.categories` metadata","code":{"code":"clippy::cargo_common_metadata","explanation":null},"level":"warning","spans":[{"file_name":"src/lib.rs","byte_start":0,"byte_end":0,"line_start":1,"line_end":1,"column_start":1,"column_end":1,"is_primary":true,"text":[],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"for further information visit https://rust-lang.github.io/rust-clippy/main/index.html#cargo_common_metadata","code":null,"level":"help","spans":[],"children":[],"rendered":null}],"rendered":null}"#;
 
     let opt = LintsOpt {
         is_clippy: true,
+        path: mock_dir().to_path_buf(),
         ..Default::default()
     };
     let info = opt.check_info(output_1).unwrap();
 
     let expected = CheckInfo {
+      file_path: Some(mock_dir().join("src/lib.rs")),
       tool: SupportedTool::Clippy,
+      begin_line: Some(1),
+      end_line: Some(1),
+      column: Some(1),
       help_info: "package `mock` is missing `package.categories` metadata".to_string(),
-      defect_name: "cargo_common_metadata".to_string(),
-      additional_help_info: "help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#cargo_common_metadata\n".to_string(),
+      defect_name: "clippy::cargo_common_metadata".to_string(),
+      additional_help_info: "help: for further information visit https://rust-lang.github.io/rust-clippy/main/index.html#cargo_common_metadata\n".to_string(),
+      span: Some(CodeSpan::new((1, 1), (1, 1))),
+      children: vec![ChildMessage::help("for further information visit https://rust-lang.github.io/rust-clippy/main/index.html#cargo_common_metadata")],
       ..Default::default()
    };
     assert_eq_check_info(&info, &expected);
@@ -165,29 +216,31 @@ fn rustc_lints_using_cargo() {
 
 #[test]
 fn rustc_unified_output() {
-    let output = "warning: denote infinite loops with `loop { ... }`
-  --> src/lints.rs:43:5
-   |
-43 |     while true { }
-   |     ^^^^^^^^^^ help: use `loop`
-   |
-   = note: `#[warn(while_true)]` on by default";
+    let output = r#"{"message":"denote infinite loops with `loop { ... }`","code":{"code":"while_true","explanation":null},"level":"warning","spans":[{"file_name":"src/lints.rs","byte_start":803,"byte_end":813,"line_start":43,"line_end":43,"column_start":5,"column_end":15,"is_primary":true,"text":[{"text":"    while true { }","highlight_start":5,"highlight_end":15}],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"`#[warn(while_true)]` on by default","code":null,"level":"note","spans":[],"children":[],"rendered":null},{"message":"use `loop`","code":null,"level":"help","spans":[{"file_name":"src/lints.rs","byte_start":803,"byte_end":813,"line_start":43,"line_end":43,"column_start":5,"column_end":15,"is_primary":true,"text":[{"text":"    while true { }","highlight_start":5,"highlight_end":15}],"label":null,"suggested_replacement":"loop","suggestion_applicability":"MachineApplicable","expansion":null}],"children":[],"rendered":null}],"rendered":null}"#;
 
     let opt = LintsOpt {
         is_clippy: false,
+        path: mock_dir().join("src").join("lib.rs"),
         ..Default::default()
     };
     let info = opt.check_info(output).unwrap();
 
     let expected = CheckInfo {
-        file_path: Some(PathBuf::from("src/lints.rs")),
+        file_path: Some(mock_dir().join("src/lints.rs")),
         defect_name: "while_true".to_string(),
         tool: SupportedTool::Rustc,
         begin_line: Some(43),
+        end_line: Some(43),
         column: Some(5),
         code_string: "    while true { }".to_string(),
         help_info: "denote infinite loops with `loop { ... }`".to_string(),
-        additional_help_info: "note: `#[warn(while_true)]` on by default\n".to_string(),
+        additional_help_info: "note: `#[warn(while_true)]` on by default\nhelp: use `loop`\n"
+            .to_string(),
+        span: Some(CodeSpan::new((43, 5), (43, 15))),
+        children: vec![
+            ChildMessage::note("`#[warn(while_true)]` on by default"),
+            ChildMessage::help("use `loop`"),
+        ],
         ..Default::default()
     };
     assert_eq_check_info(&info, &expected);
@@ -195,31 +248,32 @@ fn rustc_unified_output() {
 
 #[test]
 fn rustc_unified_output_custom() {
-    let output = "warning: identifier contains non-ASCII characters
-  --> src/lints.rs:53:9
-   |
-53 |     let _变量 = 1;
-   |         ^^^^^
-   |
-   = note: requested on the command line with `-W non-ascii-idents`";
+    let output = r#"{"message":"identifier contains non-ASCII characters","code":{"code":"non_ascii_idents","explanation":null},"level":"warning","spans":[{"file_name":"src/lints.rs","byte_start":973,"byte_end":980,"line_start":53,"line_end":53,"column_start":9,"column_end":12,"is_primary":true,"text":[{"text":"    let _变量 = 1;","highlight_start":9,"highlight_end":12}],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"requested on the command line with `-W non-ascii-idents`","code":null,"level":"note","spans":[],"children":[],"rendered":null}],"rendered":null}"#;
 
     let info = LintsOpt {
         is_clippy: false,
+        path: mock_dir().join("src").join("lib.rs"),
         ..Default::default()
     }
     .check_info(output)
     .unwrap();
 
     let expected = CheckInfo {
-        file_path: Some(PathBuf::from("src/lints.rs")),
-        defect_name: "non-ascii-idents".to_string(),
+        file_path: Some(mock_dir().join("src/lints.rs")),
+        defect_name: "non_ascii_idents".to_string(),
         tool: SupportedTool::Rustc,
         begin_line: Some(53),
+        end_line: Some(53),
         column: Some(9),
         code_string: "    let _变量 = 1;".to_string(),
         help_info: "identifier contains non-ASCII characters".to_string(),
         additional_help_info: "note: requested on the command line with `-W non-ascii-idents`\n"
             .to_string(),
+        // columns are counted by characters, not bytes
+        span: Some(CodeSpan::new((53, 9), (53, 12))),
+        children: vec![ChildMessage::note(
+            "requested on the command line with `-W non-ascii-idents`",
+        )],
         ..Default::default()
     };
     assert_eq_check_info(&info, &expected);